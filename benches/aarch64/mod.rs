@@ -22,7 +22,7 @@ fn create_guest_memory() -> GuestMemoryMmap {
 }
 
 #[derive(Clone, Copy, Default)]
-pub struct FdtPlaceholder([u8; FDT_MAX_SIZE]);
+pub struct FdtPlaceholder(#[allow(dead_code)] [u8; FDT_MAX_SIZE]);
 
 // SAFETY: The layout of the structure is fixed and can be initialized by
 // reading its content from byte array.
@@ -10,21 +10,28 @@
 extern crate linux_loader;
 extern crate vm_memory;
 
+#[cfg(feature = "bzimage")]
 use std::fs::File;
-use std::io::{Cursor, Read};
+use std::io::Cursor;
+#[cfg(feature = "bzimage")]
+use std::io::Read;
 
 use linux_loader::configurator::pvh::PvhBootConfigurator;
 use linux_loader::configurator::{BootConfigurator, BootParams};
+use linux_loader::elf::{Elf64_Ehdr, Elf64_Phdr, ELFCLASS64, ELFDATA2LSB, ET_EXEC, PT_LOAD};
 #[cfg(feature = "bzimage")]
 use linux_loader::loader::bzimage::BzImage;
 use linux_loader::loader::elf::start_info::{hvm_memmap_table_entry, hvm_start_info};
 use linux_loader::loader::elf::Elf;
 use linux_loader::loader::KernelLoader;
-use vm_memory::{Address, GuestAddress, GuestMemoryMmap};
+use vm_memory::{Address, ByteValued, GuestAddress, GuestMemoryMmap};
 
 use criterion::{black_box, Criterion};
 
 const MEM_SIZE: usize = 0x100_0000;
+// Large enough for the 32 MiB segment of the large ELF image, loaded at 1 MiB.
+const LARGE_MEM_SIZE: usize = 0x400_0000;
+const LARGE_SEGMENT_SIZE: usize = 0x200_0000;
 const E820_RAM: u32 = 1;
 const XEN_HVM_START_MAGIC_VALUE: u32 = 0x336ec578;
 
@@ -40,6 +47,35 @@ fn create_elf_pvh_image() -> Vec<u8> {
     .to_vec()
 }
 
+// ELF image with a single 32 MiB segment, about the size of a distro vmlinux.
+fn create_large_elf_image() -> Vec<u8> {
+    let ehdr_size = std::mem::size_of::<Elf64_Ehdr>();
+    let phdr_size = std::mem::size_of::<Elf64_Phdr>();
+
+    let mut ehdr = Elf64_Ehdr::default();
+    ehdr.e_ident[..4].copy_from_slice(b"\x7fELF");
+    ehdr.e_ident[4] = ELFCLASS64 as u8;
+    ehdr.e_ident[5] = ELFDATA2LSB as u8;
+    ehdr.e_type = ET_EXEC as u16;
+    ehdr.e_entry = 0x10_0000;
+    ehdr.e_phoff = ehdr_size as u64;
+    ehdr.e_phentsize = phdr_size as u16;
+    ehdr.e_phnum = 1;
+    let phdr = Elf64_Phdr {
+        p_type: PT_LOAD,
+        p_offset: (ehdr_size + phdr_size) as u64,
+        p_paddr: 0x10_0000,
+        p_filesz: LARGE_SEGMENT_SIZE as u64,
+        p_memsz: LARGE_SEGMENT_SIZE as u64,
+        ..Default::default()
+    };
+
+    let mut image = ehdr.as_slice().to_vec();
+    image.extend_from_slice(phdr.as_slice());
+    image.resize(image.len() + LARGE_SEGMENT_SIZE, 0x90);
+    image
+}
+
 fn build_boot_params() -> (hvm_start_info, Vec<hvm_memmap_table_entry>) {
     let mut start_info = hvm_start_info::default();
     let memmap_entry = hvm_memmap_table_entry {
@@ -111,6 +147,36 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         })
     });
 
+    // Compares reading the segments through a `Read + Seek` reader with copying them straight
+    // from an in-memory (e.g. memory mapped) image.
+    let large_guest_mem: GuestMemoryMmap =
+        GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), LARGE_MEM_SIZE)]).unwrap();
+    let large_elf_image = create_large_elf_image();
+
+    c.bench_function("load_elf_large_cursor", |b| {
+        b.iter(|| {
+            black_box(Elf::load(
+                &large_guest_mem,
+                None,
+                &mut Cursor::new(&large_elf_image),
+                None,
+            ))
+            .unwrap();
+        })
+    });
+
+    c.bench_function("load_elf_large_slice", |b| {
+        b.iter(|| {
+            black_box(Elf::load_from_slice(
+                &large_guest_mem,
+                None,
+                &large_elf_image,
+                None,
+            ))
+            .unwrap();
+        })
+    });
+
     c.bench_function("configure_pvh", |b| {
         b.iter(|| {
             black_box(PvhBootConfigurator::write_bootparams::<GuestMemoryMmap>(
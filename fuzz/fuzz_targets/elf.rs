@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+
+#![no_main]
+
+use std::io::Cursor;
+
+use libfuzzer_sys::fuzz_target;
+use linux_loader::loader::elf::parse_elf;
+
+// Parsing an ELF image doesn't need guest memory, any input must be either parsed or rejected.
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_elf(&mut Cursor::new(data));
+});
@@ -106,7 +106,7 @@ mod tests {
     }
 
     #[derive(Clone, Copy, Default)]
-    struct FdtPlaceholder([u8; FDT_MAX_SIZE]);
+    struct FdtPlaceholder(#[allow(dead_code)] [u8; FDT_MAX_SIZE]);
     unsafe impl ByteValued for FdtPlaceholder {}
 
     #[test]
@@ -41,7 +41,7 @@ pub enum Error {
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     Linux(linux::Error),
     /// Errors specific to the PVH boot protocol configuration.
-    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
     Pvh(pvh::Error),
     /// Errors specific to device tree boot configuration.
     #[cfg(target_arch = "aarch64")]
@@ -61,7 +61,7 @@ impl fmt::Display for Error {
         let desc = match self {
             #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
             Linux(ref _e) => "failed to configure boot parameter by Linux Boot protocol.",
-            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
             Pvh(ref _e) => "failed to configure boot parameter by PVH.",
             #[cfg(target_arch = "aarch64")]
             Fdt(ref _e) => "failed to configure boot parameter by FDT.",
@@ -83,7 +83,7 @@ impl std::error::Error for Error {
         match self {
             #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
             Linux(ref e) => Some(e),
-            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
             Pvh(ref e) => Some(e),
             #[cfg(target_arch = "aarch64")]
             Fdt(ref e) => Some(e),
@@ -119,8 +119,8 @@ pub trait BootConfigurator {
     /// # Arguments
     ///
     /// * `params` - struct containing the header section of the boot parameters, additional
-    ///              sections and modules, and their associated addresses in guest memory. These
-    ///              vary with the boot protocol used.
+    ///   sections and modules, and their associated addresses in guest memory. These
+    ///   vary with the boot protocol used.
     /// * `guest_memory` - guest's physical memory.
     fn write_bootparams<M>(params: &BootParams, guest_memory: &M) -> Result<()>
     where
@@ -477,26 +477,29 @@ mod tests {
             );
 
             // PVH
-            assert_eq!(
-                format!("{}", Error::Pvh(pvh::Error::MemmapTableMissing)),
-                "Boot Configurator: failed to configure boot parameter by PVH."
-            );
-            assert_eq!(
-                format!("{}", Error::Pvh(pvh::Error::MemmapTablePastRamEnd)),
-                "Boot Configurator: failed to configure boot parameter by PVH."
-            );
-            assert_eq!(
-                format!("{}", Error::Pvh(pvh::Error::MemmapTableSetup)),
-                "Boot Configurator: failed to configure boot parameter by PVH."
-            );
-            assert_eq!(
-                format!("{}", Error::Pvh(pvh::Error::StartInfoPastRamEnd)),
-                "Boot Configurator: failed to configure boot parameter by PVH."
-            );
-            assert_eq!(
-                format!("{}", Error::Pvh(pvh::Error::StartInfoSetup)),
-                "Boot Configurator: failed to configure boot parameter by PVH."
-            );
+            #[cfg(feature = "elf")]
+            {
+                assert_eq!(
+                    format!("{}", Error::Pvh(pvh::Error::MemmapTableMissing)),
+                    "Boot Configurator: failed to configure boot parameter by PVH."
+                );
+                assert_eq!(
+                    format!("{}", Error::Pvh(pvh::Error::MemmapTablePastRamEnd)),
+                    "Boot Configurator: failed to configure boot parameter by PVH."
+                );
+                assert_eq!(
+                    format!("{}", Error::Pvh(pvh::Error::MemmapTableSetup)),
+                    "Boot Configurator: failed to configure boot parameter by PVH."
+                );
+                assert_eq!(
+                    format!("{}", Error::Pvh(pvh::Error::StartInfoPastRamEnd)),
+                    "Boot Configurator: failed to configure boot parameter by PVH."
+                );
+                assert_eq!(
+                    format!("{}", Error::Pvh(pvh::Error::StartInfoSetup)),
+                    "Boot Configurator: failed to configure boot parameter by PVH."
+                );
+            }
         }
 
         #[cfg(target_arch = "aarch64")]
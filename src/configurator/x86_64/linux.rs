@@ -12,9 +12,10 @@
 //! Traits and structs for configuring and loading boot parameters on `x86_64` using the Linux
 //! boot protocol.
 
-use vm_memory::{Bytes, GuestMemory};
+use vm_memory::{Address, Bytes, GuestAddress, GuestMemory, GuestUsize};
 
 use crate::configurator::{BootConfigurator, BootParams, Error as BootConfiguratorError, Result};
+use crate::loader_gen::bootparam::{boot_e820_entry, boot_params};
 
 use std::fmt;
 
@@ -28,6 +29,10 @@ pub enum Error {
     ZeroPagePastRamEnd,
     /// Error writing to the zero page of guest memory.
     ZeroPageSetup,
+    /// The command line size doesn't fit in the zero page.
+    CommandLineSizeTooLarge,
+    /// The e820 map of the zero page has no room left for another entry.
+    E820TableFull,
 }
 
 impl fmt::Display for Error {
@@ -36,6 +41,8 @@ impl fmt::Display for Error {
         let desc = match self {
             ZeroPagePastRamEnd => "the zero page extends past the end of guest memory.",
             ZeroPageSetup => "error writing to the zero page of guest memory.",
+            CommandLineSizeTooLarge => "the command line size doesn't fit in the zero page.",
+            E820TableFull => "the e820 map of the zero page is full.",
         };
 
         write!(f, "Linux Boot Configurator: {}", desc,)
@@ -50,13 +57,141 @@ impl From<Error> for BootConfiguratorError {
     }
 }
 
+// Boot flag of a valid setup header.
+const KERNEL_BOOT_FLAG_MAGIC: u16 = 0xaa55;
+// "HdrS" magic number of a valid setup header.
+const KERNEL_HDR_MAGIC: u32 = 0x5372_6448;
+// Boot loader type for loaders without an assigned ID.
+const KERNEL_LOADER_OTHER: u8 = 0xff;
+
+/// Fills in the command line and initrd fields of the zero page in guest memory.
+///
+/// The [`boot_params`] struct at `zero_page_addr` is read from guest memory, updated and written
+/// back, so it can be combined with a setup header or e820 map written there beforehand, e.g.
+/// with [`write_bootparams`]. Besides the command line and initrd location, it sets the boot
+/// flag, the `HdrS` header magic number and the boot loader type. Addresses and sizes above
+/// 4 GiB are split between the setup header fields and their `ext_*` counterparts.
+///
+/// # Arguments
+///
+/// * `guest_memory` - guest's physical memory.
+/// * `zero_page_addr` - address of the zero page in guest memory.
+/// * `cmdline_addr` - address of the kernel command line in guest memory.
+/// * `cmdline_size` - size of the kernel command line.
+/// * `initrd_addr` - address of the initrd in guest memory.
+/// * `initrd_size` - size of the initrd, `0` if there is none.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::configurator::linux::configure_boot_params;
+/// # use vm_memory::{GuestAddress, GuestMemoryMmap};
+/// let guest_memory =
+///     GuestMemoryMmap::<()>::from_ranges(&[(GuestAddress(0x0), 0x100_0000)]).unwrap();
+/// configure_boot_params(
+///     &guest_memory,
+///     GuestAddress(0x7000),
+///     GuestAddress(0x2_0000),
+///     0x100,
+///     GuestAddress(0x80_0000),
+///     0x1000,
+/// )
+/// .unwrap();
+/// ```
+///
+/// [`boot_params`]: ../../loader/bootparam/struct.boot_params.html
+/// [`write_bootparams`]: struct.LinuxBootConfigurator.html#method.write_bootparams
+pub fn configure_boot_params<M>(
+    guest_memory: &M,
+    zero_page_addr: GuestAddress,
+    cmdline_addr: GuestAddress,
+    cmdline_size: GuestUsize,
+    initrd_addr: GuestAddress,
+    initrd_size: GuestUsize,
+) -> Result<()>
+where
+    M: GuestMemory,
+{
+    guest_memory
+        .checked_offset(zero_page_addr, std::mem::size_of::<boot_params>() - 1)
+        .ok_or(Error::ZeroPagePastRamEnd)?;
+    let mut params: boot_params = guest_memory
+        .read_obj(zero_page_addr)
+        .map_err(|_| Error::ZeroPageSetup)?;
+
+    params.hdr.boot_flag = KERNEL_BOOT_FLAG_MAGIC;
+    params.hdr.header = KERNEL_HDR_MAGIC;
+    params.hdr.type_of_loader = KERNEL_LOADER_OTHER;
+    params.hdr.cmdline_size =
+        u32::try_from(cmdline_size).map_err(|_| Error::CommandLineSizeTooLarge)?;
+    // Truncations are intended, the high halves go to the `ext_*` fields.
+    params.hdr.cmd_line_ptr = cmdline_addr.raw_value() as u32;
+    params.ext_cmd_line_ptr = (cmdline_addr.raw_value() >> 32) as u32;
+    params.hdr.ramdisk_image = initrd_addr.raw_value() as u32;
+    params.ext_ramdisk_image = (initrd_addr.raw_value() >> 32) as u32;
+    params.hdr.ramdisk_size = initrd_size as u32;
+    params.ext_ramdisk_size = (initrd_size >> 32) as u32;
+
+    guest_memory
+        .write_obj(params, zero_page_addr)
+        .map_err(|_| Error::ZeroPageSetup)?;
+
+    Ok(())
+}
+
+/// Appends an entry to the e820 memory map of `params`.
+///
+/// The map tells the kernel which guest physical memory it may use, e.g. `mem_type` 1
+/// (`E820_RAM`) for usable RAM and 2 (`E820_RESERVED`) for reserved memory. Entries are appended
+/// in order after the `e820_entries` already in the map, which is then written to guest memory
+/// along with the rest of `params`.
+///
+/// # Arguments
+///
+/// * `params` - boot parameters to add the entry to.
+/// * `addr` - start address of the memory range.
+/// * `size` - size of the memory range.
+/// * `mem_type` - type of the memory range.
+///
+/// # Errors
+///
+/// Returns `E820TableFull` when the map already holds the 128 entries it has room for.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linux_loader::configurator::linux::add_e820_entry;
+/// # use linux_loader::loader::bootparam::boot_params;
+/// const E820_RAM: u32 = 1;
+/// let mut params = boot_params::default();
+/// add_e820_entry(&mut params, 0x0, 0x9_fc00, E820_RAM).unwrap();
+/// add_e820_entry(&mut params, 0x10_0000, 0xff0_0000, E820_RAM).unwrap();
+/// assert_eq!(params.e820_entries, 2);
+/// ```
+pub fn add_e820_entry(params: &mut boot_params, addr: u64, size: u64, mem_type: u32) -> Result<()> {
+    let index = usize::from(params.e820_entries);
+    if index >= params.e820_table.len() {
+        return Err(Error::E820TableFull.into());
+    }
+
+    params.e820_table[index] = boot_e820_entry {
+        addr,
+        size,
+        type_: mem_type,
+    };
+    params.e820_entries += 1;
+
+    Ok(())
+}
+
 impl BootConfigurator for LinuxBootConfigurator {
     /// Writes the boot parameters (configured elsewhere) into guest memory.
     ///
     /// # Arguments
     ///
     /// * `params` - boot parameters. The header contains a [`boot_params`] struct. The `sections`
-    ///              and `modules` are unused.
+    ///   and `modules` are unused.
     /// * `guest_memory` - guest's physical memory.
     ///
     /// # Examples
@@ -115,13 +250,9 @@ impl BootConfigurator for LinuxBootConfigurator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::loader_gen::bootparam::boot_params;
     use std::mem;
-    use vm_memory::{Address, GuestAddress, GuestMemoryMmap};
+    use vm_memory::GuestMemoryMmap;
 
-    const KERNEL_BOOT_FLAG_MAGIC: u16 = 0xaa55;
-    const KERNEL_HDR_MAGIC: u32 = 0x53726448;
-    const KERNEL_LOADER_OTHER: u8 = 0xff;
     const KERNEL_MIN_ALIGNMENT_BYTES: u32 = 0x1000000;
     const MEM_SIZE: u64 = 0x100_0000;
 
@@ -167,6 +298,115 @@ mod tests {
         .is_ok());
     }
 
+    #[test]
+    fn test_configure_boot_params() {
+        let zero_page_addr = GuestAddress(0x30000);
+        let guest_memory = create_guest_mem();
+
+        // The fields set beforehand are kept.
+        let mut params = boot_params::default();
+        params.hdr.kernel_alignment = KERNEL_MIN_ALIGNMENT_BYTES;
+        guest_memory.write_obj(params, zero_page_addr).unwrap();
+
+        configure_boot_params(
+            &guest_memory,
+            zero_page_addr,
+            GuestAddress(0x20000),
+            0x100,
+            GuestAddress(0x1_2345_6000),
+            0x1000,
+        )
+        .unwrap();
+        let params: boot_params = guest_memory.read_obj(zero_page_addr).unwrap();
+        assert_eq!({ params.hdr.boot_flag }, KERNEL_BOOT_FLAG_MAGIC);
+        assert_eq!({ params.hdr.header }, KERNEL_HDR_MAGIC);
+        assert_eq!(params.hdr.type_of_loader, KERNEL_LOADER_OTHER);
+        assert_eq!({ params.hdr.kernel_alignment }, KERNEL_MIN_ALIGNMENT_BYTES);
+        assert_eq!({ params.hdr.cmd_line_ptr }, 0x20000);
+        assert_eq!({ params.ext_cmd_line_ptr }, 0);
+        assert_eq!({ params.hdr.cmdline_size }, 0x100);
+        assert_eq!({ params.hdr.ramdisk_image }, 0x2345_6000);
+        assert_eq!({ params.ext_ramdisk_image }, 0x1);
+        assert_eq!({ params.hdr.ramdisk_size }, 0x1000);
+        assert_eq!({ params.ext_ramdisk_size }, 0);
+
+        // Sizes above 4 GiB are split as well, whatever the host pointer width.
+        configure_boot_params(
+            &guest_memory,
+            zero_page_addr,
+            GuestAddress(0x20000),
+            0x100,
+            GuestAddress(0x1_0000_0000),
+            0x1_0000_2000,
+        )
+        .unwrap();
+        let params: boot_params = guest_memory.read_obj(zero_page_addr).unwrap();
+        assert_eq!({ params.hdr.ramdisk_size }, 0x2000);
+        assert_eq!({ params.ext_ramdisk_size }, 0x1);
+
+        // The zero page may end on the last byte of guest memory, but not past it.
+        let last_zeropg_addr = GuestAddress(
+            guest_memory.last_addr().raw_value() - mem::size_of::<boot_params>() as u64 + 1,
+        );
+        assert!(configure_boot_params(
+            &guest_memory,
+            last_zeropg_addr,
+            GuestAddress(0x20000),
+            0x100,
+            GuestAddress(0),
+            0,
+        )
+        .is_ok());
+        let bad_zeropg_addr = last_zeropg_addr.unchecked_add(1);
+        assert_eq!(
+            configure_boot_params(
+                &guest_memory,
+                bad_zeropg_addr,
+                GuestAddress(0x20000),
+                0x100,
+                GuestAddress(0),
+                0,
+            )
+            .err(),
+            Some(Error::ZeroPagePastRamEnd.into()),
+        );
+    }
+
+    #[test]
+    fn test_add_e820_entry() {
+        let mut params = build_bootparams_common();
+        add_e820_entry(&mut params, 0x0, 0x9_fc00, 1).unwrap();
+        add_e820_entry(&mut params, 0x10_0000, 0xff0_0000, 2).unwrap();
+        assert_eq!(params.e820_entries, 2);
+        assert_eq!(
+            params.e820_table[..2],
+            [
+                boot_e820_entry {
+                    addr: 0x0,
+                    size: 0x9_fc00,
+                    type_: 1
+                },
+                boot_e820_entry {
+                    addr: 0x10_0000,
+                    size: 0xff0_0000,
+                    type_: 2
+                }
+            ]
+        );
+
+        // Fill the map up.
+        for i in 2..128 {
+            add_e820_entry(&mut params, i << 32, 0x1000, 1).unwrap();
+        }
+        assert_eq!(params.e820_entries, 128);
+        assert_eq!({ params.e820_table[127].addr }, 127 << 32);
+        assert_eq!(
+            add_e820_entry(&mut params, 0x1_0000_0000_0000, 0x1000, 1).err(),
+            Some(Error::E820TableFull.into())
+        );
+        assert_eq!(params.e820_entries, 128);
+    }
+
     #[test]
     fn test_error_messages() {
         assert_eq!(
@@ -177,5 +417,13 @@ mod tests {
             format!("{}", Error::ZeroPageSetup),
             "Linux Boot Configurator: error writing to the zero page of guest memory."
         );
+        assert_eq!(
+            format!("{}", Error::CommandLineSizeTooLarge),
+            "Linux Boot Configurator: the command line size doesn't fit in the zero page."
+        );
+        assert_eq!(
+            format!("{}", Error::E820TableFull),
+            "Linux Boot Configurator: the e820 map of the zero page is full."
+        );
     }
 }
@@ -14,4 +14,5 @@
 #![cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 
 pub mod linux;
+#[cfg(feature = "elf")]
 pub mod pvh;
@@ -14,12 +14,13 @@
 
 #![cfg(any(feature = "elf", feature = "bzimage"))]
 
-use vm_memory::{ByteValued, Bytes, GuestMemory};
+use vm_memory::{Address, ByteValued, Bytes, GuestAddress, GuestMemory, GuestUsize};
 
 use crate::configurator::{BootConfigurator, BootParams, Error as BootConfiguratorError, Result};
 use crate::loader_gen::start_info::{hvm_memmap_table_entry, hvm_modlist_entry, hvm_start_info};
 
 use std::fmt;
+use std::mem;
 
 /// Boot configurator for the PVH boot protocol.
 pub struct PvhBootConfigurator {}
@@ -39,6 +40,10 @@ pub enum Error {
     StartInfoPastRamEnd,
     /// Error writing hvm_start_info to guest memory.
     StartInfoSetup,
+    /// The module list extends past the end of guest memory.
+    ModlistPastRamEnd,
+    /// Error writing the module list to guest memory.
+    ModlistSetup,
 }
 
 impl fmt::Display for Error {
@@ -55,6 +60,8 @@ impl fmt::Display for Error {
                 "the hvm_start_info structure extends past the end of guest memory."
             }
             StartInfoSetup => "error writing hvm_start_info to guest memory.",
+            ModlistPastRamEnd => "the module list extends past the end of guest memory.",
+            ModlistSetup => "error writing the module list to guest memory.",
         };
 
         write!(f, "PVH Boot Configurator: {}", desc)
@@ -81,14 +88,110 @@ unsafe impl ByteValued for hvm_memmap_table_entry {}
 // reading its content from byte array.
 unsafe impl ByteValued for hvm_modlist_entry {}
 
+// Magic value of a valid hvm_start_info structure ("xEn3" with the high bit of each byte set).
+const XEN_HVM_START_MAGIC_VALUE: u32 = 0x336e_c578;
+// Version of the hvm_start_info structure, version 1 added the memory map fields.
+const XEN_HVM_START_INFO_VERSION: u32 = 1;
+
+/// Writes an [`hvm_start_info`] structure and its module list into guest memory.
+///
+/// The structure is written at `start_info_addr` with the PVH magic number, the command line
+/// address and the module list, which is written right after it. The memory map is left empty,
+/// it can be written with [`write_bootparams`] instead. `start_info_addr` is what the VMM passes
+/// to the guest in `%rbx` at the PVH entry point.
+///
+/// # Arguments
+///
+/// * `guest_memory` - guest's physical memory.
+/// * `start_info_addr` - address of the `hvm_start_info` structure in guest memory.
+/// * `cmdline_addr` - address of the kernel command line in guest memory.
+/// * `modules` - address and size of each module (e.g. the initrd) in guest memory.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::configurator::pvh::configure_pvh_start_info;
+/// # use vm_memory::{GuestAddress, GuestMemoryMmap};
+/// let guest_memory =
+///     GuestMemoryMmap::<()>::from_ranges(&[(GuestAddress(0x0), 0x100_0000)]).unwrap();
+/// configure_pvh_start_info(
+///     &guest_memory,
+///     GuestAddress(0x6000),
+///     GuestAddress(0x2_0000),
+///     &[(GuestAddress(0x80_0000), 0x1000)],
+/// )
+/// .unwrap();
+/// ```
+///
+/// [`hvm_start_info`]: ../../loader/elf/start_info/struct.hvm_start_info.html
+/// [`write_bootparams`]: struct.PvhBootConfigurator.html#method.write_bootparams
+pub fn configure_pvh_start_info<M>(
+    guest_memory: &M,
+    start_info_addr: GuestAddress,
+    cmdline_addr: GuestAddress,
+    modules: &[(GuestAddress, GuestUsize)],
+) -> Result<()>
+where
+    M: GuestMemory,
+{
+    let start_info_size = mem::size_of::<hvm_start_info>();
+    guest_memory
+        .checked_offset(start_info_addr, start_info_size - 1)
+        .ok_or(Error::StartInfoPastRamEnd)?;
+    let modlist_addr = start_info_addr
+        .checked_add(start_info_size as u64)
+        .ok_or(Error::ModlistPastRamEnd)?;
+    let modlist_size = modules
+        .len()
+        .checked_mul(mem::size_of::<hvm_modlist_entry>())
+        .ok_or(Error::ModlistPastRamEnd)?;
+    if modlist_size > 0 {
+        guest_memory
+            .checked_offset(modlist_addr, modlist_size - 1)
+            .ok_or(Error::ModlistPastRamEnd)?;
+    }
+
+    let start_info = hvm_start_info {
+        magic: XEN_HVM_START_MAGIC_VALUE,
+        version: XEN_HVM_START_INFO_VERSION,
+        nr_modules: u32::try_from(modules.len()).map_err(|_| Error::ModlistPastRamEnd)?,
+        modlist_paddr: if modules.is_empty() {
+            0
+        } else {
+            modlist_addr.raw_value()
+        },
+        cmdline_paddr: cmdline_addr.raw_value(),
+        ..Default::default()
+    };
+
+    for (index, &(module_addr, module_size)) in modules.iter().enumerate() {
+        let entry = hvm_modlist_entry {
+            paddr: module_addr.raw_value(),
+            size: module_size,
+            ..Default::default()
+        };
+        // Can't overflow, the whole list was checked to fit in guest memory.
+        let entry_addr = modlist_addr.unchecked_add((index * mem::size_of_val(&entry)) as u64);
+        guest_memory
+            .write_obj(entry, entry_addr)
+            .map_err(|_| Error::ModlistSetup)?;
+    }
+    guest_memory
+        .write_obj(start_info, start_info_addr)
+        .map_err(|_| Error::StartInfoSetup)?;
+
+    Ok(())
+}
+
 impl BootConfigurator for PvhBootConfigurator {
     /// Writes the boot parameters (configured elsewhere) into guest memory.
     ///
     /// # Arguments
     ///
     /// * `params` - boot parameters. The header contains a [`hvm_start_info`] struct. The
-    ///              sections contain the memory map in a vector of [`hvm_memmap_table_entry`]
-    ///              structs. The modules, if specified, contain [`hvm_modlist_entry`] structs.
+    ///   sections contain the memory map in a vector of [`hvm_memmap_table_entry`]
+    ///   structs. The modules, if specified, contain [`hvm_modlist_entry`] structs.
     /// * `guest_memory` - guest's physical memory.
     ///
     /// [`hvm_start_info`]: ../loader/elf/start_info/struct.hvm_start_info.html
@@ -174,7 +277,6 @@ mod tests {
     use std::mem;
     use vm_memory::{Address, GuestAddress, GuestMemoryMmap};
 
-    const XEN_HVM_START_MAGIC_VALUE: u32 = 0x336ec578;
     const MEM_SIZE: u64 = 0x100_0000;
     const E820_RAM: u32 = 1;
 
@@ -252,6 +354,66 @@ mod tests {
         .is_ok());
     }
 
+    #[test]
+    fn test_configure_pvh_start_info() {
+        let guest_memory = create_guest_mem();
+        let start_info_addr = GuestAddress(0x6000);
+        let modules = [
+            (GuestAddress(0x80_0000), 0x1000),
+            (GuestAddress(0x90_0000), 0x2000),
+        ];
+
+        configure_pvh_start_info(
+            &guest_memory,
+            start_info_addr,
+            GuestAddress(0x2_0000),
+            &modules,
+        )
+        .unwrap();
+        let start_info: hvm_start_info = guest_memory.read_obj(start_info_addr).unwrap();
+        assert_eq!(start_info.magic, XEN_HVM_START_MAGIC_VALUE);
+        assert_eq!(start_info.version, 1);
+        assert_eq!(start_info.cmdline_paddr, 0x2_0000);
+        assert_eq!(start_info.nr_modules, 2);
+        let modlist_addr = start_info_addr.unchecked_add(mem::size_of::<hvm_start_info>() as u64);
+        assert_eq!(start_info.modlist_paddr, modlist_addr.raw_value());
+        let entry: hvm_modlist_entry = guest_memory
+            .read_obj(modlist_addr.unchecked_add(mem::size_of::<hvm_modlist_entry>() as u64))
+            .unwrap();
+        assert_eq!(entry.paddr, 0x90_0000);
+        assert_eq!(entry.size, 0x2000);
+
+        // Without modules.
+        configure_pvh_start_info(&guest_memory, start_info_addr, GuestAddress(0x2_0000), &[])
+            .unwrap();
+        let start_info: hvm_start_info = guest_memory.read_obj(start_info_addr).unwrap();
+        assert_eq!(start_info.nr_modules, 0);
+        assert_eq!(start_info.modlist_paddr, 0);
+
+        // The structure or the module list don't fit in guest memory.
+        let start_info_addr = GuestAddress(MEM_SIZE - mem::size_of::<hvm_start_info>() as u64);
+        assert_eq!(
+            configure_pvh_start_info(
+                &guest_memory,
+                start_info_addr.unchecked_add(1),
+                GuestAddress(0x2_0000),
+                &[]
+            )
+            .err(),
+            Some(Error::StartInfoPastRamEnd.into())
+        );
+        assert_eq!(
+            configure_pvh_start_info(
+                &guest_memory,
+                start_info_addr,
+                GuestAddress(0x2_0000),
+                &modules
+            )
+            .err(),
+            Some(Error::ModlistPastRamEnd.into())
+        );
+    }
+
     #[test]
     fn test_error_messages() {
         assert_eq!(
@@ -271,5 +433,13 @@ mod tests {
             format!("{}", Error::StartInfoSetup),
             "PVH Boot Configurator: error writing hvm_start_info to guest memory."
         );
+        assert_eq!(
+            format!("{}", Error::ModlistPastRamEnd),
+            "PVH Boot Configurator: the module list extends past the end of guest memory."
+        );
+        assert_eq!(
+            format!("{}", Error::ModlistSetup),
+            "PVH Boot Configurator: error writing the module list to guest memory."
+        );
     }
 }
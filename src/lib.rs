@@ -31,12 +31,13 @@
 //! # extern crate linux_loader;
 //! # extern crate vm_memory;
 //! # use std::{io::{Cursor, Read}, fs::File};
+//! # #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
 //! # use linux_loader::configurator::{BootConfigurator, BootParams};
-//! # #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+//! # #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
 //! # use linux_loader::configurator::pvh::PvhBootConfigurator;
-//! # #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+//! # #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
 //! # use linux_loader::loader::elf::start_info::{hvm_memmap_table_entry, hvm_start_info};
-//! # #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+//! # #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
 //! # use linux_loader::loader::elf::Elf;
 //! # use linux_loader::loader::KernelLoader;
 //! # use vm_memory::{Address, GuestAddress, GuestMemoryMmap};
@@ -44,7 +45,7 @@
 //! # const MEM_SIZE: usize = 0x100_0000;
 //! # const XEN_HVM_START_MAGIC_VALUE: u32 = 0x336ec578;
 //!
-//! # #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+//! # #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
 //! fn build_boot_params() -> (hvm_start_info, Vec<hvm_memmap_table_entry>) {
 //!     let mut start_info = hvm_start_info::default();
 //!     let memmap_entry = hvm_memmap_table_entry {
@@ -60,7 +61,7 @@
 //!     (start_info, vec![memmap_entry])
 //! }
 //!
-//! # #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+//! # #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
 //! fn main() {
 //!     let guest_mem = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), MEM_SIZE)]).unwrap();
 //!
@@ -90,7 +91,7 @@
 //!     PvhBootConfigurator::write_bootparams::<GuestMemoryMmap>(&boot_params, &guest_mem).unwrap();
 //! }
 //!
-//! # #[cfg(target_arch = "aarch64")]
+//! # #[cfg(not(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64"))))]
 //! # fn main() {}
 //! ```
 //!
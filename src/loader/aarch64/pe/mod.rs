@@ -29,6 +29,7 @@ unsafe impl ByteValued for arm64_image_header {}
 
 #[derive(Debug, PartialEq, Eq)]
 /// PE kernel loader errors.
+#[non_exhaustive]
 pub enum Error {
     /// Unable to seek to Image end.
     SeekImageEnd,
@@ -93,6 +94,80 @@ struct arm64_image_header {
     res5: u32,
 }
 
+/// Fields of the boot header of an arm64 `Image`, as returned by [`read_arm64_image_header`].
+///
+/// See the kernel documentation in `Documentation/arm64/booting.rst` for their meaning.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Arm64ImageHeader {
+    /// Offset of the image from a 2 MiB aligned base address. Images whose `image_size` is 0
+    /// (kernels older than 3.17) are loaded at 0x80000 instead.
+    pub text_offset: u64,
+    /// Size of the image in memory, including its BSS.
+    pub image_size: u64,
+    /// Kernel flags: endianness (bit 0), page size (bits 1-2) and physical placement (bit 3).
+    pub flags: u64,
+}
+
+impl Arm64ImageHeader {
+    /// Whether the kernel is big endian.
+    pub fn is_big_endian(&self) -> bool {
+        self.flags & 0x1 != 0
+    }
+
+    /// Page size of the kernel, if specified.
+    pub fn page_size(&self) -> Option<u64> {
+        match (self.flags >> 1) & 0x3 {
+            1 => Some(0x1000),
+            2 => Some(0x4000),
+            3 => Some(0x1_0000),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the boot header of an arm64 `Image`, without loading it.
+///
+/// Only the `ARM\x64` magic number is checked, this lets a VMM plan the guest memory layout
+/// from e.g. `text_offset` and `image_size` before loading the kernel.
+///
+/// # Arguments
+///
+/// * `kernel_image` - Input Image format kernel image.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// let kernel_image = include_bytes!("test_image.bin");
+/// let header = pe::read_arm64_image_header(&mut Cursor::new(&kernel_image)).unwrap();
+/// assert_eq!(header.text_offset, 0x80000);
+/// ```
+pub fn read_arm64_image_header<F>(kernel_image: &mut F) -> Result<Arm64ImageHeader>
+where
+    F: Read + Seek,
+{
+    let mut arm64_header: arm64_image_header = Default::default();
+    kernel_image
+        .seek(SeekFrom::Start(0))
+        .map_err(|_| Error::SeekImageHeader)?;
+
+    arm64_header
+        .as_bytes()
+        .read_exact_from(0, kernel_image, mem::size_of::<arm64_image_header>())
+        .map_err(|_| Error::ReadImageHeader)?;
+
+    if u32::from_le(arm64_header.magic) != 0x644d_5241 {
+        return Err(Error::InvalidImageMagicNumber.into());
+    }
+
+    Ok(Arm64ImageHeader {
+        text_offset: u64::from_le(arm64_header.text_offset),
+        image_size: u64::from_le(arm64_header.image_size),
+        flags: u64::from_le(arm64_header.flags),
+    })
+}
+
 impl KernelLoader for PE {
     /// Loads a PE Image into guest memory.
     ///
@@ -117,22 +192,9 @@ impl KernelLoader for PE {
         let kernel_size = kernel_image
             .seek(SeekFrom::End(0))
             .map_err(|_| Error::SeekImageEnd)? as usize;
-        let mut arm64_header: arm64_image_header = Default::default();
-        kernel_image
-            .seek(SeekFrom::Start(0))
-            .map_err(|_| Error::SeekImageHeader)?;
-
-        arm64_header
-            .as_bytes()
-            .read_from(0, kernel_image, mem::size_of::<arm64_image_header>())
-            .map_err(|_| Error::ReadImageHeader)?;
-
-        if u32::from_le(arm64_header.magic) != 0x644d_5241 {
-            return Err(Error::InvalidImageMagicNumber.into());
-        }
-
-        let image_size = u64::from_le(arm64_header.image_size);
-        let mut text_offset = u64::from_le(arm64_header.text_offset);
+        let arm64_header = read_arm64_image_header(kernel_image)?;
+        let image_size = arm64_header.image_size;
+        let mut text_offset = arm64_header.text_offset;
 
         if image_size == 0 {
             text_offset = 0x80000;
@@ -163,6 +225,7 @@ impl KernelLoader for PE {
             .read_exact_from(mem_offset, kernel_image, kernel_size)
             .map_err(|_| Error::ReadKernelImage)?;
 
+        loader_result.bytes_loaded = kernel_size as GuestUsize;
         loader_result.kernel_end = mem_offset
             .raw_value()
             .checked_add(kernel_size as GuestUsize)
@@ -231,6 +294,7 @@ mod tests {
             PE::load(&gm, Some(kernel_addr), &mut Cursor::new(&image), None).unwrap();
         assert_eq!(loader_result.kernel_load.raw_value(), 0x280000);
         assert_eq!(loader_result.kernel_end, 0x281000);
+        assert_eq!(loader_result.bytes_loaded, 0x1000);
 
         // Attempt to load the kernel at an address that is not aligned to 2MB boundary
         let kernel_offset = GuestAddress(0x0030_0000);
@@ -247,4 +311,43 @@ mod tests {
             Err(KernelLoaderError::Pe(Error::InvalidImageMagicNumber))
         );
     }
+
+    #[test]
+    fn test_read_arm64_image_header() {
+        let header = read_arm64_image_header(&mut Cursor::new(&make_image_bin())).unwrap();
+        assert_eq!(header.text_offset, 0x80000);
+
+        let mut image = vec![0u8; 0x40];
+        image[0x8..0x10].copy_from_slice(&0x10_0000u64.to_le_bytes());
+        image[0x10..0x18].copy_from_slice(&0x1_2345u64.to_le_bytes());
+        // Little endian, 16 KiB pages.
+        image[0x18..0x20].copy_from_slice(&0xcu64.to_le_bytes());
+        image[0x38..0x3c].copy_from_slice(b"ARM\x64");
+        let header = read_arm64_image_header(&mut Cursor::new(&image)).unwrap();
+        assert_eq!(
+            header,
+            Arm64ImageHeader {
+                text_offset: 0x10_0000,
+                image_size: 0x1_2345,
+                flags: 0xc,
+            }
+        );
+        assert!(!header.is_big_endian());
+        assert_eq!(header.page_size(), Some(0x4000));
+
+        image[0x18] = 0x1;
+        let header = read_arm64_image_header(&mut Cursor::new(&image)).unwrap();
+        assert!(header.is_big_endian());
+        assert_eq!(header.page_size(), None);
+
+        assert_eq!(
+            read_arm64_image_header(&mut Cursor::new(&image[..0x3c])),
+            Err(KernelLoaderError::Pe(Error::ReadImageHeader))
+        );
+        image[0x3b] = 0x65;
+        assert_eq!(
+            read_arm64_image_header(&mut Cursor::new(&image)),
+            Err(KernelLoaderError::Pe(Error::InvalidImageMagicNumber))
+        );
+    }
 }
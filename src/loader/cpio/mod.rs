@@ -0,0 +1,343 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+
+//! Helpers for loading kernel images stored in an uncompressed `newc` cpio archive.
+
+#![cfg(feature = "cpio")]
+
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
+
+use vm_memory::{GuestAddress, GuestMemory};
+
+use super::{KernelLoader, KernelLoaderResult, Result};
+
+// Magic numbers of the `newc` format, without and with checksums.
+const NEWC_MAGIC: &[u8; 6] = b"070701";
+const NEWC_CRC_MAGIC: &[u8; 6] = b"070702";
+// Size of a `newc` header: the magic number followed by 13 fields of 8 hex digits.
+const HEADER_SIZE: usize = 110;
+// Offsets of the header fields used here.
+const FILESIZE_OFFSET: usize = 54;
+const NAMESIZE_OFFSET: usize = 94;
+// Name of the entry marking the end of the archive.
+const TRAILER_NAME: &[u8] = b"TRAILER!!!";
+// Longest entry name accepted, well above `PATH_MAX`.
+const MAX_NAME_SIZE: u64 = 0x1_0000;
+
+#[derive(Debug, PartialEq, Eq)]
+/// Cpio kernel loader errors.
+#[non_exhaustive]
+pub enum Error {
+    /// The archive has no entry with the requested name.
+    CpioEntryNotFound,
+    /// Invalid cpio header.
+    InvalidCpioHeader,
+    /// Unable to read cpio header.
+    ReadCpioHeader,
+    /// Unable to seek to cpio entry.
+    SeekCpioEntry,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let desc = match self {
+            Error::CpioEntryNotFound => "Cpio entry not found",
+            Error::InvalidCpioHeader => "Invalid cpio header",
+            Error::ReadCpioHeader => "Unable to read cpio header",
+            Error::SeekCpioEntry => "Unable to seek to cpio entry",
+        };
+
+        write!(f, "Kernel Loader: {}", desc)
+    }
+}
+
+impl std::error::Error for Error {}
+
+// Parses a field of 8 hex digits of a `newc` header.
+fn parse_field(header: &[u8; HEADER_SIZE], offset: usize) -> std::result::Result<u64, Error> {
+    std::str::from_utf8(&header[offset..offset + 8])
+        .ok()
+        .and_then(|field| u64::from_str_radix(field, 16).ok())
+        .ok_or(Error::InvalidCpioHeader)
+}
+
+// Headers and data are padded to 4 bytes.
+fn align(offset: u64) -> std::result::Result<u64, Error> {
+    offset
+        .checked_next_multiple_of(4)
+        .ok_or(Error::InvalidCpioHeader)
+}
+
+// Returns the offset in `archive` and the size of the data of the entry named `entry_name`.
+fn find_entry<F>(archive: &mut F, entry_name: &str) -> std::result::Result<(u64, u64), Error>
+where
+    F: Read + Seek,
+{
+    let mut offset = 0;
+    loop {
+        archive
+            .seek(SeekFrom::Start(offset))
+            .map_err(|_| Error::SeekCpioEntry)?;
+        let mut header = [0u8; HEADER_SIZE];
+        archive
+            .read_exact(&mut header)
+            .map_err(|_| Error::ReadCpioHeader)?;
+        if &header[..6] != NEWC_MAGIC && &header[..6] != NEWC_CRC_MAGIC {
+            return Err(Error::InvalidCpioHeader);
+        }
+        let file_size = parse_field(&header, FILESIZE_OFFSET)?;
+        let name_size = parse_field(&header, NAMESIZE_OFFSET)?;
+        if name_size == 0 || name_size > MAX_NAME_SIZE {
+            return Err(Error::InvalidCpioHeader);
+        }
+
+        // The name size includes the null terminator.
+        let mut name = vec![0u8; name_size as usize];
+        archive
+            .read_exact(&mut name)
+            .map_err(|_| Error::ReadCpioHeader)?;
+        if name.pop() != Some(0) {
+            return Err(Error::InvalidCpioHeader);
+        }
+
+        let data_offset = align(offset + HEADER_SIZE as u64 + name_size)?;
+        if name == entry_name.as_bytes() {
+            return Ok((data_offset, file_size));
+        }
+        if name == TRAILER_NAME {
+            return Err(Error::CpioEntryNotFound);
+        }
+        offset = align(
+            data_offset
+                .checked_add(file_size)
+                .ok_or(Error::InvalidCpioHeader)?,
+        )?;
+    }
+}
+
+// Reader over the data of an entry, seen by the loaders as a whole image.
+struct EntryReader<'a, F> {
+    archive: &'a mut F,
+    start: u64,
+    size: u64,
+    offset: u64,
+}
+
+impl<F: Read> Read for EntryReader<'_, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.size.saturating_sub(self.offset);
+        let len = std::cmp::min(buf.len() as u64, remaining) as usize;
+        let len = self.archive.read(&mut buf[..len])?;
+        self.offset += len as u64;
+        Ok(len)
+    }
+}
+
+impl<F: Seek> Seek for EntryReader<'_, F> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let offset = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.size.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.offset.checked_add_signed(delta),
+        };
+        let archive_offset = offset
+            .and_then(|offset| offset.checked_add(self.start))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid seek offset"))?;
+        self.archive.seek(SeekFrom::Start(archive_offset))?;
+        self.offset = archive_offset - self.start;
+        Ok(self.offset)
+    }
+}
+
+/// Loads the kernel image stored as `entry_name` in a cpio archive with the loader `L`.
+///
+/// The archive must be in the uncompressed `newc` format (`cpio -H newc`), as used e.g. for
+/// initramfs images. Its entries are scanned from the start of `archive` until `entry_name` or
+/// the end of the archive is found, and `L` then reads the kernel image straight from the data
+/// of the entry. `entry_name` is compared with the names as stored in the archive, typically
+/// without a leading `/`.
+///
+/// # Arguments
+///
+/// * `guest_mem`: [`GuestMemory`] to load the kernel in.
+/// * `kernel_offset`: Passed through to `L`.
+/// * `archive` - Input cpio archive.
+/// * `entry_name` - Name of the kernel image in the archive.
+/// * `highmem_start_address`: Passed through to `L`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use std::fs::File;
+/// # use linux_loader::loader::*;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// # #[cfg(all(feature = "elf", target_arch = "x86_64"))]
+/// # fn load(gm: &GuestMemoryMmap, archive: &mut File) -> Result<KernelLoaderResult> {
+/// cpio::load_kernel_from_cpio_entry::<elf::Elf, _, _>(gm, None, archive, "boot/vmlinux", None)
+/// # }
+/// ```
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+pub fn load_kernel_from_cpio_entry<L, F, M>(
+    guest_mem: &M,
+    kernel_offset: Option<GuestAddress>,
+    archive: &mut F,
+    entry_name: &str,
+    highmem_start_address: Option<GuestAddress>,
+) -> Result<KernelLoaderResult>
+where
+    L: KernelLoader,
+    F: Read + Seek,
+    M: GuestMemory,
+{
+    let (start, size) = find_entry(archive, entry_name)?;
+    let mut entry = EntryReader {
+        archive,
+        start,
+        size,
+        offset: 0,
+    };
+    entry
+        .seek(SeekFrom::Start(0))
+        .map_err(|_| Error::SeekCpioEntry)?;
+
+    L::load(guest_mem, kernel_offset, &mut entry, highmem_start_address)
+}
+
+#[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::{elf::Elf, Error as KernelLoaderError};
+    use std::io::Cursor;
+    use vm_memory::{Address, GuestAddress};
+    type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+
+    const MEM_SIZE: u64 = 0x100_0000;
+
+    fn create_guest_mem() -> GuestMemoryMmap {
+        GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), (MEM_SIZE as usize))]).unwrap()
+    }
+
+    fn make_elf_bin() -> Vec<u8> {
+        include_bytes!("../x86_64/elf/test_elf.bin").to_vec()
+    }
+
+    // Builds a `newc` archive of regular files, followed by the trailer.
+    fn cpio(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        let trailer = std::str::from_utf8(TRAILER_NAME).unwrap();
+        for (ino, &(name, data)) in entries
+            .iter()
+            .chain([(trailer, &[][..])].iter())
+            .enumerate()
+        {
+            let fields = [
+                ino as u64,
+                0o100644,
+                0,
+                0,
+                1,
+                0,
+                data.len() as u64,
+                0,
+                0,
+                0,
+                0,
+                name.len() as u64 + 1,
+                0,
+            ];
+            archive.extend_from_slice(NEWC_MAGIC);
+            for field in fields {
+                archive.extend_from_slice(format!("{:08x}", field).as_bytes());
+            }
+            archive.extend_from_slice(name.as_bytes());
+            archive.push(0);
+            archive.resize(archive.len().next_multiple_of(4), 0);
+            archive.extend_from_slice(data);
+            archive.resize(archive.len().next_multiple_of(4), 0);
+        }
+        archive
+    }
+
+    #[test]
+    fn test_load_cpio_entry() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        // Entries of odd sizes, so the kernel is not aligned in the archive.
+        let archive = cpio(&[
+            ("init", b"#!/bin/sh\n"),
+            ("boot/vmlinux", &image),
+            ("boot/config", b"CONFIG_X86_64=y"),
+        ]);
+        let kernel_addr = Some(GuestAddress(0x20_0000));
+
+        let loader_result = load_kernel_from_cpio_entry::<Elf, _, _>(
+            &gm,
+            kernel_addr,
+            &mut Cursor::new(&archive),
+            "boot/vmlinux",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            loader_result,
+            Elf::load(&gm, kernel_addr, &mut Cursor::new(&image), None).unwrap()
+        );
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x20_0400);
+
+        // The loaders only see the data of the entry.
+        let too_short = cpio(&[("boot/vmlinux", &image[..0xc0]), ("init", &image)]);
+        assert!(matches!(
+            load_kernel_from_cpio_entry::<Elf, _, _>(
+                &gm,
+                None,
+                &mut Cursor::new(&too_short),
+                "boot/vmlinux",
+                None,
+            ),
+            Err(KernelLoaderError::Elf(
+                crate::loader::elf::Error::TruncatedSegment { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_cpio_errors() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let mut archive = cpio(&[("init", b"#!/bin/sh\n"), ("vmlinux", &image)]);
+        let load = |archive: &[u8], name| {
+            load_kernel_from_cpio_entry::<Elf, _, _>(
+                &gm,
+                None,
+                &mut Cursor::new(archive),
+                name,
+                None,
+            )
+            .err()
+        };
+
+        assert_eq!(
+            load(&archive, "boot/vmlinux"),
+            Some(KernelLoaderError::Cpio(Error::CpioEntryNotFound))
+        );
+        // Truncated before the trailer.
+        assert_eq!(
+            load(&archive[..archive.len() - 0x10], "boot/vmlinux"),
+            Some(KernelLoaderError::Cpio(Error::ReadCpioHeader))
+        );
+        // Size of the first entry not in hex digits.
+        archive[FILESIZE_OFFSET] = b'x';
+        assert_eq!(
+            load(&archive, "vmlinux"),
+            Some(KernelLoaderError::Cpio(Error::InvalidCpioHeader))
+        );
+        // Old binary format.
+        assert_eq!(
+            load(&[0xc7, 0x71].repeat(0x100), "vmlinux"),
+            Some(KernelLoaderError::Cpio(Error::InvalidCpioHeader))
+        );
+    }
+}
@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+
+//! Traits and structs for loading gzip compressed kernel images into guest memory.
+
+#![cfg(feature = "gzip")]
+
+use std::fmt;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::marker::PhantomData;
+
+use flate2::read::GzDecoder;
+use vm_memory::{GuestAddress, GuestMemory};
+
+use super::{KernelLoader, KernelLoaderResult, Result};
+
+// Magic number at the start of every gzip stream (RFC 1952).
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Debug, PartialEq, Eq)]
+/// Gzip kernel loader errors.
+#[non_exhaustive]
+pub enum Error {
+    /// Unable to decompress gzip image.
+    DecompressGzipImage,
+    /// Unable to read gzip magic number.
+    ReadGzipMagic,
+    /// Unable to seek to gzip image start.
+    SeekGzipStart,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let desc = match self {
+            Error::DecompressGzipImage => "Unable to decompress gzip image",
+            Error::ReadGzipMagic => "Unable to read gzip magic number",
+            Error::SeekGzipStart => "Unable to seek to gzip image start",
+        };
+
+        write!(f, "Kernel Loader: {}", desc)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Gzip compressed kernel image support.
+///
+/// Wraps another [`KernelLoader`]: when the image starts with the gzip magic number it is
+/// decompressed in memory and the result is handed to `L`, otherwise the image is passed to `L`
+/// untouched.
+///
+/// [`KernelLoader`]: ../trait.KernelLoader.html
+pub struct Gzip<L: KernelLoader>(PhantomData<L>);
+
+impl<L: KernelLoader> KernelLoader for Gzip<L> {
+    /// Loads a possibly gzip compressed kernel image into guest memory.
+    ///
+    /// The inner loaders need to seek in the image, so a compressed image is fully decompressed
+    /// into an in-memory buffer first.
+    ///
+    /// # Arguments
+    ///
+    /// * `guest_mem`: [`GuestMemory`] to load the kernel in.
+    /// * `kernel_offset`: Passed through to the inner loader.
+    /// * `kernel_image` - Input kernel image, either gzip compressed or not.
+    /// * `highmem_start_address`: Passed through to the inner loader.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate vm_memory;
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::GuestAddress;
+    /// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+    /// # #[cfg(all(feature = "elf", target_arch = "x86_64"))]
+    /// # fn load(gm: &GuestMemoryMmap, image: &[u8]) -> Result<KernelLoaderResult> {
+    /// gzip::Gzip::<elf::Elf>::load(gm, None, &mut Cursor::new(image), None)
+    /// # }
+    /// ```
+    ///
+    /// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+    fn load<F, M: GuestMemory>(
+        guest_mem: &M,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+        highmem_start_address: Option<GuestAddress>,
+    ) -> Result<KernelLoaderResult>
+    where
+        F: Read + Seek,
+    {
+        kernel_image
+            .seek(SeekFrom::Start(0))
+            .map_err(|_| Error::SeekGzipStart)?;
+
+        let mut magic = [0u8; GZIP_MAGIC.len()];
+        let is_gzip = match kernel_image.read_exact(&mut magic) {
+            Ok(()) => magic == GZIP_MAGIC,
+            // Too short to be a gzip stream, let the inner loader deal with it.
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => false,
+            Err(_) => return Err(Error::ReadGzipMagic.into()),
+        };
+
+        kernel_image
+            .seek(SeekFrom::Start(0))
+            .map_err(|_| Error::SeekGzipStart)?;
+        if !is_gzip {
+            return L::load(
+                guest_mem,
+                kernel_offset,
+                kernel_image,
+                highmem_start_address,
+            );
+        }
+
+        let mut decompressed = Vec::new();
+        GzDecoder::new(&mut *kernel_image)
+            .read_to_end(&mut decompressed)
+            .map_err(|_| Error::DecompressGzipImage)?;
+
+        L::load(
+            guest_mem,
+            kernel_offset,
+            &mut Cursor::new(decompressed),
+            highmem_start_address,
+        )
+    }
+}
+
+#[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::{elf::Elf, Error as KernelLoaderError};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use vm_memory::{Address, GuestAddress};
+    type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+
+    const MEM_SIZE: u64 = 0x100_0000;
+
+    fn create_guest_mem() -> GuestMemoryMmap {
+        GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), (MEM_SIZE as usize))]).unwrap()
+    }
+
+    fn make_elf_bin() -> Vec<u8> {
+        include_bytes!("../x86_64/elf/test_elf.bin").to_vec()
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_load_gzip_elf() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let kernel_addr = GuestAddress(0x200000);
+
+        let expected = Elf::load(&gm, Some(kernel_addr), &mut Cursor::new(&image), None).unwrap();
+        let loader_result =
+            Gzip::<Elf>::load(&gm, Some(kernel_addr), &mut Cursor::new(gzip(&image)), None)
+                .unwrap();
+        assert_eq!(loader_result, expected);
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x200400);
+    }
+
+    #[test]
+    fn test_load_uncompressed_elf() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let loader_result = Gzip::<Elf>::load(&gm, None, &mut Cursor::new(&image), None).unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x400);
+
+        // Errors from the inner loader are returned as they are.
+        assert!(matches!(
+            Gzip::<Elf>::load(&gm, None, &mut Cursor::new(&image[..1]), None),
+            Err(KernelLoaderError::Elf(
+                crate::loader::elf::Error::ReadElfHeader(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_bad_gzip() {
+        let gm = create_guest_mem();
+        let mut image = gzip(&make_elf_bin());
+        image.truncate(image.len() / 2);
+        assert_eq!(
+            Some(KernelLoaderError::Gzip(Error::DecompressGzipImage)),
+            Gzip::<Elf>::load(&gm, None, &mut Cursor::new(&image), None).err()
+        );
+    }
+}
@@ -16,11 +16,21 @@
 //! - [Elf](elf/struct.Elf.html): elf image loader.
 //! - [BzImage](bzimage/struct.BzImage.html): bzImage loader.
 //! - [PE](pe/struct.PE.html): PE image loader.
+//! - [Multiboot](multiboot/struct.Multiboot.html): multiboot image loader.
+//! - [Gzip](gzip/struct.Gzip.html): gzip compressed image loader, wrapping one of the loaders
+//!   above.
+//! - [load_kernel_verified](verify/fn.load_kernel_verified.html): loads an image with one of the
+//!   loaders above after checking its SHA-256 digest.
+//! - [load_kernel_from_cpio_entry](cpio/fn.load_kernel_from_cpio_entry.html): loads an image
+//!   stored in a cpio archive with one of the loaders above.
 
 extern crate vm_memory;
 
+use std::ffi::{CStr, CString};
 use std::fmt;
-use std::io::{Read, Seek};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use vm_memory::ByteValued;
@@ -33,16 +43,49 @@ pub use crate::cmdline::Cmdline;
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 mod x86_64;
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[cfg(all(
+    any(feature = "elf", feature = "bzimage"),
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
 pub use x86_64::*;
 
 #[cfg(target_arch = "aarch64")]
 mod aarch64;
-#[cfg(target_arch = "aarch64")]
+#[cfg(all(feature = "pe", target_arch = "aarch64"))]
 pub use aarch64::*;
 
-#[derive(Debug, PartialEq, Eq)]
+#[cfg(feature = "gzip")]
+pub mod gzip;
+
+#[cfg(feature = "verify")]
+pub mod verify;
+
+#[cfg(feature = "cpio")]
+pub mod cpio;
+
+#[derive(Debug)]
 /// Kernel loader errors.
+///
+/// Like the errors of the individual loaders, this may get new variants in minor releases, so
+/// matching it needs a wildcard arm.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linux_loader::loader::Error;
+/// fn is_cmdline_error(err: &Error) -> bool {
+///     match err {
+///         Error::InvalidCommandLine
+///         | Error::CommandLineCopy
+///         | Error::CommandLineOverflow
+///         | Error::CommandLineContainsNul
+///         | Error::CommandLineOverlapsReserved => true,
+///         _ => false,
+///     }
+/// }
+/// assert!(is_cmdline_error(&Error::CommandLineOverflow));
+/// ```
+#[non_exhaustive]
 pub enum Error {
     /// Failed to load bzimage.
     #[cfg(all(feature = "bzimage", any(target_arch = "x86", target_arch = "x86_64")))]
@@ -52,20 +95,78 @@ pub enum Error {
     #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
     Elf(elf::Error),
 
+    /// Failed to load multiboot image.
+    #[cfg(all(
+        feature = "multiboot",
+        any(target_arch = "x86", target_arch = "x86_64")
+    ))]
+    Multiboot(multiboot::Error),
+
     /// Failed to load PE image.
     #[cfg(all(feature = "pe", target_arch = "aarch64"))]
     Pe(pe::Error),
 
+    /// Failed to load gzip compressed image.
+    #[cfg(feature = "gzip")]
+    Gzip(gzip::Error),
+
+    /// Failed to verify kernel image.
+    #[cfg(feature = "verify")]
+    Verify(verify::Error),
+
+    /// Failed to load kernel image from a cpio archive.
+    #[cfg(feature = "cpio")]
+    Cpio(cpio::Error),
+
     /// Invalid command line.
     InvalidCommandLine,
     /// Failed writing command line to guest memory.
     CommandLineCopy,
     /// Command line overflowed guest memory.
     CommandLineOverflow,
+    /// Command line string contains a null byte.
+    CommandLineContainsNul,
+    /// Command line overlaps a reserved guest memory region.
+    CommandLineOverlapsReserved,
     /// Invalid kernel start address.
     InvalidKernelStartAddress,
     /// Memory to load kernel image is too small.
     MemoryOverflow,
+    /// Initrd image does not fit in guest memory.
+    InitrdImageSizeTooLarge,
+    /// Failed to read initrd image into guest memory.
+    ReadInitrdImage,
+    /// Failed to seek in initrd image.
+    SeekInitrdImage,
+    /// Flat binary does not fit in guest memory.
+    FlatBinaryTooLarge,
+    /// Failed to read flat binary into guest memory.
+    ReadFlatBinary,
+    /// Failed to seek in flat binary.
+    SeekFlatBinary,
+    /// Flat binary region is outside of the image or of guest memory.
+    RegionOutOfBounds,
+    /// Device tree blob does not start with the FDT magic number.
+    InvalidFdtMagic,
+    /// Device tree blob size does not match its header.
+    FdtSizeMismatch,
+    /// Device tree blob does not fit in guest memory.
+    FdtTooLarge,
+    /// Failed to write device tree blob to guest memory.
+    WriteFdt,
+    /// Failed to read back loaded guest memory.
+    ReadGuestMemory,
+    /// Initrd alignment is not a power of two.
+    InvalidInitrdAlignment,
+    /// Failed to read the start of the image to detect its format.
+    ReadImageFormat,
+    /// Failed to seek in the image to detect its format.
+    SeekImageFormat,
+    /// Failed to open the kernel image file.
+    OpenKernelImage(io::Error),
+    /// Invalid relocation table, or relocation entry outside of guest memory.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    InvalidRelocationEntry,
 }
 
 /// A specialized [`Result`] type for the kernel loader.
@@ -80,14 +181,47 @@ impl fmt::Display for Error {
             Error::Bzimage(ref _e) => "failed to load bzImage kernel image",
             #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
             Error::Elf(ref _e) => "failed to load ELF kernel image",
+            #[cfg(all(
+                feature = "multiboot",
+                any(target_arch = "x86", target_arch = "x86_64")
+            ))]
+            Error::Multiboot(ref _e) => "failed to load multiboot kernel image",
             #[cfg(all(feature = "pe", target_arch = "aarch64"))]
             Error::Pe(ref _e) => "failed to load PE kernel image",
+            #[cfg(feature = "gzip")]
+            Error::Gzip(ref _e) => "failed to load gzip compressed kernel image",
+            #[cfg(feature = "verify")]
+            Error::Verify(ref _e) => "failed to verify kernel image",
+            #[cfg(feature = "cpio")]
+            Error::Cpio(ref _e) => "failed to load kernel image from a cpio archive",
 
             Error::InvalidCommandLine => "invalid command line provided",
             Error::CommandLineCopy => "failed writing command line to guest memory",
             Error::CommandLineOverflow => "command line overflowed guest memory",
+            Error::CommandLineContainsNul => "command line string contains a null byte",
+            Error::CommandLineOverlapsReserved => {
+                "command line overlaps a reserved guest memory region"
+            }
             Error::InvalidKernelStartAddress => "invalid kernel start address",
             Error::MemoryOverflow => "memory to load kernel image is not enough",
+            Error::InitrdImageSizeTooLarge => "initrd image does not fit in guest memory",
+            Error::ReadInitrdImage => "failed to read initrd image into guest memory",
+            Error::SeekInitrdImage => "failed to seek in initrd image",
+            Error::FlatBinaryTooLarge => "flat binary does not fit in guest memory",
+            Error::ReadFlatBinary => "failed to read flat binary into guest memory",
+            Error::SeekFlatBinary => "failed to seek in flat binary",
+            Error::RegionOutOfBounds => "flat binary region is out of bounds",
+            Error::InvalidFdtMagic => "invalid device tree blob magic number",
+            Error::FdtSizeMismatch => "device tree blob size does not match its header",
+            Error::FdtTooLarge => "device tree blob does not fit in guest memory",
+            Error::WriteFdt => "failed to write device tree blob to guest memory",
+            Error::ReadGuestMemory => "failed to read back loaded guest memory",
+            Error::InvalidInitrdAlignment => "initrd alignment is not a power of two",
+            Error::ReadImageFormat => "failed to read image to detect its format",
+            Error::SeekImageFormat => "failed to seek in image to detect its format",
+            Error::OpenKernelImage(_) => "failed to open kernel image file",
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Error::InvalidRelocationEntry => "invalid relocation entry",
         };
 
         write!(f, "Kernel Loader: {}", desc)
@@ -101,14 +235,143 @@ impl std::error::Error for Error {
             Error::Bzimage(ref e) => Some(e),
             #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
             Error::Elf(ref e) => Some(e),
+            #[cfg(all(
+                feature = "multiboot",
+                any(target_arch = "x86", target_arch = "x86_64")
+            ))]
+            Error::Multiboot(ref e) => Some(e),
             #[cfg(all(feature = "pe", target_arch = "aarch64"))]
             Error::Pe(ref e) => Some(e),
+            #[cfg(feature = "gzip")]
+            Error::Gzip(ref e) => Some(e),
+            #[cfg(feature = "verify")]
+            Error::Verify(ref e) => Some(e),
+            #[cfg(feature = "cpio")]
+            Error::Cpio(ref e) => Some(e),
 
             Error::InvalidCommandLine => None,
             Error::CommandLineCopy => None,
             Error::CommandLineOverflow => None,
+            Error::CommandLineContainsNul => None,
+            Error::CommandLineOverlapsReserved => None,
             Error::InvalidKernelStartAddress => None,
             Error::MemoryOverflow => None,
+            Error::InitrdImageSizeTooLarge => None,
+            Error::ReadInitrdImage => None,
+            Error::SeekInitrdImage => None,
+            Error::FlatBinaryTooLarge => None,
+            Error::ReadFlatBinary => None,
+            Error::SeekFlatBinary => None,
+            Error::RegionOutOfBounds => None,
+            Error::InvalidFdtMagic => None,
+            Error::FdtSizeMismatch => None,
+            Error::FdtTooLarge => None,
+            Error::WriteFdt => None,
+            Error::ReadGuestMemory => None,
+            Error::InvalidInitrdAlignment => None,
+            Error::ReadImageFormat => None,
+            Error::SeekImageFormat => None,
+            Error::OpenKernelImage(ref e) => Some(e),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Error::InvalidRelocationEntry => None,
+        }
+    }
+}
+
+// The wrapped I/O errors can't be compared, so `OpenKernelImage` errors are equal regardless of
+// their source.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            #[cfg(all(feature = "bzimage", any(target_arch = "x86", target_arch = "x86_64")))]
+            (Error::Bzimage(e), Error::Bzimage(other_e)) => e == other_e,
+            #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+            (Error::Elf(e), Error::Elf(other_e)) => e == other_e,
+            #[cfg(all(
+                feature = "multiboot",
+                any(target_arch = "x86", target_arch = "x86_64")
+            ))]
+            (Error::Multiboot(e), Error::Multiboot(other_e)) => e == other_e,
+            #[cfg(all(feature = "pe", target_arch = "aarch64"))]
+            (Error::Pe(e), Error::Pe(other_e)) => e == other_e,
+            #[cfg(feature = "gzip")]
+            (Error::Gzip(e), Error::Gzip(other_e)) => e == other_e,
+            #[cfg(feature = "verify")]
+            (Error::Verify(e), Error::Verify(other_e)) => e == other_e,
+            #[cfg(feature = "cpio")]
+            (Error::Cpio(e), Error::Cpio(other_e)) => e == other_e,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for Error {}
+
+impl Error {
+    // Whether the error comes from the image (or initrd) being shorter than expected, or failing
+    // to be read.
+    fn is_read_error(&self) -> bool {
+        match self {
+            #[cfg(all(feature = "bzimage", any(target_arch = "x86", target_arch = "x86_64")))]
+            Error::Bzimage(e) => matches!(
+                e,
+                bzimage::Error::ReadBzImageHeader | bzimage::Error::ReadBzImageCompressedKernel
+            ),
+            #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+            Error::Elf(e) => matches!(
+                e,
+                elf::Error::ReadElfHeader(_)
+                    | elf::Error::ReadKernelImage { .. }
+                    | elf::Error::ReadProgramHeader(_)
+                    | elf::Error::ReadSectionHeader(_)
+                    | elf::Error::ReadNoteHeader(_)
+                    | elf::Error::ReadSymbolTable(_)
+                    | elf::Error::TruncatedSegment { .. }
+                    | elf::Error::SegmentPastEof { .. }
+                    | elf::Error::ProgramHeaderPastEof
+            ),
+            #[cfg(all(
+                feature = "multiboot",
+                any(target_arch = "x86", target_arch = "x86_64")
+            ))]
+            Error::Multiboot(e) => matches!(
+                e,
+                multiboot::Error::ReadKernelImage | multiboot::Error::ReadMultibootHeader
+            ),
+            #[cfg(all(feature = "pe", target_arch = "aarch64"))]
+            Error::Pe(e) => matches!(
+                e,
+                pe::Error::ReadKernelImage | pe::Error::ReadImageHeader | pe::Error::ReadDtbImage
+            ),
+            #[cfg(feature = "gzip")]
+            Error::Gzip(e) => matches!(e, gzip::Error::ReadGzipMagic),
+            #[cfg(feature = "verify")]
+            Error::Verify(e) => matches!(e, verify::Error::ReadKernelImage),
+            #[cfg(feature = "cpio")]
+            Error::Cpio(e) => matches!(e, cpio::Error::ReadCpioHeader),
+            Error::ReadInitrdImage | Error::ReadFlatBinary | Error::ReadImageFormat => true,
+            _ => false,
+        }
+    }
+}
+
+/// Converts a loader error into an [`io::Error`], e.g. for `?` to work in functions returning
+/// [`io::Result`].
+///
+/// Failures to read the image map to [`io::ErrorKind::UnexpectedEof`] and all the other errors
+/// to [`io::ErrorKind::Other`]. The loader error is kept as the inner error of the
+/// [`io::Error`], which has the same `Display` output.
+///
+/// [`io::Error`]: https://doc.rust-lang.org/std/io/struct.Error.html
+/// [`io::Result`]: https://doc.rust-lang.org/std/io/type.Result.html
+/// [`io::ErrorKind::UnexpectedEof`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+/// [`io::ErrorKind::Other`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.Other
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        if err.is_read_error() {
+            io::Error::new(io::ErrorKind::UnexpectedEof, err)
+        } else {
+            io::Error::other(err)
         }
     }
 }
@@ -127,6 +390,16 @@ impl From<bzimage::Error> for Error {
     }
 }
 
+#[cfg(all(
+    feature = "multiboot",
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+impl From<multiboot::Error> for Error {
+    fn from(err: multiboot::Error) -> Self {
+        Error::Multiboot(err)
+    }
+}
+
 #[cfg(all(feature = "pe", target_arch = "aarch64"))]
 impl From<pe::Error> for Error {
     fn from(err: pe::Error) -> Self {
@@ -134,6 +407,27 @@ impl From<pe::Error> for Error {
     }
 }
 
+#[cfg(feature = "gzip")]
+impl From<gzip::Error> for Error {
+    fn from(err: gzip::Error) -> Self {
+        Error::Gzip(err)
+    }
+}
+
+#[cfg(feature = "verify")]
+impl From<verify::Error> for Error {
+    fn from(err: verify::Error) -> Self {
+        Error::Verify(err)
+    }
+}
+
+#[cfg(feature = "cpio")]
+impl From<cpio::Error> for Error {
+    fn from(err: cpio::Error) -> Self {
+        Error::Cpio(err)
+    }
+}
+
 /// Result of [`KernelLoader.load()`](trait.KernelLoader.html#tymethod.load).
 ///
 /// This specifies where the kernel is loading and passes additional
@@ -146,6 +440,9 @@ pub struct KernelLoaderResult {
     /// Offset in guest memory corresponding to the end of kernel image, in case the device tree
     /// blob and initrd will be loaded adjacent to kernel image.
     pub kernel_end: GuestUsize,
+    /// Number of bytes copied from the kernel image to guest memory. Unlike `kernel_end`, this
+    /// does not account for the zeroed out parts of the kernel, such as the BSS.
+    pub bytes_loaded: GuestUsize,
     /// Configuration for the VMM to use to fill zero page for bzImage direct boot.
     /// See https://www.kernel.org/doc/Documentation/x86/boot.txt.
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -157,6 +454,130 @@ pub struct KernelLoaderResult {
     pub pvh_boot_cap: elf::PvhBootCapability,
 }
 
+// The generated `setup_header` only derives `PartialEq`, but it is plain integer data, so the
+// comparison is a full equivalence.
+impl Eq for KernelLoaderResult {}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl KernelLoaderResult {
+    /// Returns the end of the guest memory used by the protected-mode kernel of a bzImage.
+    ///
+    /// A bzImage decompresses itself in place and uses `init_size` bytes from `kernel_load`
+    /// while doing so, which is usually more than its size in the image. This is the highest of
+    /// `kernel_end` and `kernel_load + init_size`: the initrd, command line or anything else the
+    /// VMM loads above the kernel has to start at or after it. Returns `None` for images without
+    /// a setup header.
+    pub fn protected_mode_end(&self) -> Option<GuestUsize> {
+        let setup_header = self.setup_header?;
+        let init_end = self
+            .kernel_load
+            .raw_value()
+            .saturating_add(u64::from(setup_header.init_size));
+        Some(std::cmp::max(self.kernel_end, init_end))
+    }
+}
+
+/// Kernel image format, as detected by [`sniff_format`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// 32-bit ELF image.
+    Elf32,
+    /// 64-bit ELF image.
+    Elf64,
+    /// bzImage.
+    BzImage,
+    /// PE image, or arm64 `Image` (which is also a PE image when built with an EFI stub).
+    Pe,
+    /// gzip compressed image.
+    Gzip,
+    /// None of the above.
+    Unknown,
+}
+
+/// Detects the format of a kernel image from its magic numbers.
+///
+/// This only looks at the magic numbers, the image may still be rejected by the loader of its
+/// format. The image is rewound to its start before returning, so it can be passed to a loader
+/// right away.
+///
+/// # Arguments
+///
+/// * `kernel_image` - Input kernel image.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// let kernel_image = include_bytes!("x86_64/elf/test_elf.bin");
+/// assert_eq!(
+///     sniff_format(&mut Cursor::new(&kernel_image)).unwrap(),
+///     ImageFormat::Elf64
+/// );
+/// ```
+pub fn sniff_format<F>(kernel_image: &mut F) -> Result<ImageFormat>
+where
+    F: Read + Seek,
+{
+    // The bzImage `HdrS` magic number is the furthest one from the start of the image.
+    const BZIMAGE_MAGIC_OFFSET: usize = 0x202;
+
+    kernel_image
+        .seek(SeekFrom::Start(0))
+        .map_err(|_| Error::SeekImageFormat)?;
+    let mut magic = vec![];
+    kernel_image
+        .take(BZIMAGE_MAGIC_OFFSET as u64 + 4)
+        .read_to_end(&mut magic)
+        .map_err(|_| Error::ReadImageFormat)?;
+    kernel_image
+        .seek(SeekFrom::Start(0))
+        .map_err(|_| Error::SeekImageFormat)?;
+
+    // bzImages with an EFI stub also start with `MZ`, so `HdrS` is checked first.
+    let format = if magic.starts_with(b"\x7fELF") {
+        match magic.get(4) {
+            Some(1) => ImageFormat::Elf32,
+            Some(2) => ImageFormat::Elf64,
+            _ => ImageFormat::Unknown,
+        }
+    } else if magic.starts_with(&[0x1f, 0x8b]) {
+        ImageFormat::Gzip
+    } else if magic.get(BZIMAGE_MAGIC_OFFSET..) == Some(b"HdrS") {
+        ImageFormat::BzImage
+    } else if magic.starts_with(b"MZ") || magic.get(0x38..0x3c) == Some(b"ARM\x64") {
+        ImageFormat::Pe
+    } else {
+        ImageFormat::Unknown
+    };
+    Ok(format)
+}
+
+/// Start of high memory on x86 (1 MiB), above the real mode memory, legacy video memory and
+/// BIOS areas. Protected mode kernels are usually loaded there.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub const HIMEM_START: GuestAddress = GuestAddress(0x10_0000);
+
+/// Recommended `highmem_start_address` argument of
+/// [`KernelLoader::load`](trait.KernelLoader.html#tymethod.load) for the target architecture,
+/// i.e. the lowest address a kernel may be loaded at.
+///
+/// This is [`HIMEM_START`] on x86, other architectures don't reserve low memory.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linux_loader::loader::*;
+/// # #[cfg(target_arch = "x86_64")]
+/// assert_eq!(default_highmem_start_address(), Some(HIMEM_START));
+/// ```
+pub fn default_highmem_start_address() -> Option<GuestAddress> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    return Some(HIMEM_START);
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    return None;
+}
+
 /// Trait that specifies kernel image loading support.
 pub trait KernelLoader {
     /// How to load a specific kernel image format into the guest memory.
@@ -179,6 +600,206 @@ pub trait KernelLoader {
         F: Read + Seek;
 }
 
+/// Kernel image read with positioned reads, such as `pread`.
+///
+/// Unlike `Read + Seek`, the offset is passed to every read and no cursor is kept in the image,
+/// so it can be shared. This has the same signature as [`FileExt::read_at`], which implements it
+/// on unix.
+///
+/// [`FileExt::read_at`]: https://doc.rust-lang.org/std/os/unix/fs/trait.FileExt.html#tymethod.read_at
+pub trait PositionedRead {
+    /// Reads bytes from offset `offset` of the image into `buf`, returning how many were read.
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+}
+
+#[cfg(unix)]
+impl<T: std::os::unix::fs::FileExt> PositionedRead for T {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        std::os::unix::fs::FileExt::read_at(self, buf, offset)
+    }
+}
+
+/// `Read + Seek` adapter over a [`PositionedRead`] image of `size` bytes, which keeps the offset
+/// on its side.
+struct PositionedReader<'a, F> {
+    image: &'a F,
+    offset: u64,
+    size: u64,
+}
+
+impl<F: PositionedRead> Read for PositionedReader<'_, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // The image ends at `size`, whatever is past it.
+        let max_len = self.size.saturating_sub(self.offset);
+        let buf_len = std::cmp::min(buf.len() as u64, max_len) as usize;
+        let len = self.image.read_at(&mut buf[..buf_len], self.offset)?;
+        self.offset += len as u64;
+        Ok(len)
+    }
+}
+
+impl<F> Seek for PositionedReader<'_, F> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let offset = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.size.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.offset.checked_add_signed(delta),
+        };
+        self.offset = offset.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative offset",
+            )
+        })?;
+        Ok(self.offset)
+    }
+}
+
+/// Loads a kernel image read with positioned reads with the loader `L`.
+///
+/// The loaders need the size of the image, e.g. to find where it ends, so it has to be given as
+/// `image_size`. For a file, it is the length from its metadata. Nothing past `image_size` is
+/// read.
+///
+/// # Arguments
+///
+/// * `guest_mem`: [`GuestMemory`] to load the kernel in.
+/// * `kernel_offset`: Passed through to `L`.
+/// * `kernel_image` - Input kernel image.
+/// * `image_size` - Size of the kernel image, in bytes.
+/// * `highmem_start_address`: Passed through to `L`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use std::fs::File;
+/// # use linux_loader::loader::*;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// # #[cfg(all(feature = "elf", target_arch = "x86_64"))]
+/// # fn load(gm: &GuestMemoryMmap, kernel: &File) -> std::io::Result<KernelLoaderResult> {
+/// let image_size = kernel.metadata()?.len();
+/// Ok(load_kernel_pread::<elf::Elf, _, _>(gm, None, kernel, image_size, None).unwrap())
+/// # }
+/// ```
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+pub fn load_kernel_pread<L, F, M>(
+    guest_mem: &M,
+    kernel_offset: Option<GuestAddress>,
+    kernel_image: &F,
+    image_size: u64,
+    highmem_start_address: Option<GuestAddress>,
+) -> Result<KernelLoaderResult>
+where
+    L: KernelLoader,
+    F: PositionedRead,
+    M: GuestMemory,
+{
+    L::load(
+        guest_mem,
+        kernel_offset,
+        &mut PositionedReader {
+            image: kernel_image,
+            offset: 0,
+            size: image_size,
+        },
+        highmem_start_address,
+    )
+}
+
+/// Loads a kernel image file into guest memory with the loader `L`.
+///
+/// This opens the file at `path` and hands it to `L`, see
+/// [`KernelLoader.load()`](trait.KernelLoader.html#tymethod.load).
+///
+/// # Arguments
+///
+/// * `guest_mem`: [`GuestMemory`] to load the kernel in.
+/// * `kernel_offset`: Passed through to `L`.
+/// * `path` - Path of the kernel image file.
+/// * `highmem_start_address`: Passed through to `L`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// # #[cfg(all(feature = "elf", target_arch = "x86_64"))]
+/// # fn load(gm: &GuestMemoryMmap) -> Result<KernelLoaderResult> {
+/// load_kernel_from_path::<elf::Elf, _, _>(gm, None, "/boot/vmlinux", None)
+/// # }
+/// ```
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+pub fn load_kernel_from_path<L, M, P>(
+    guest_mem: &M,
+    kernel_offset: Option<GuestAddress>,
+    path: P,
+    highmem_start_address: Option<GuestAddress>,
+) -> Result<KernelLoaderResult>
+where
+    L: KernelLoader,
+    M: GuestMemory,
+    P: AsRef<Path>,
+{
+    let mut kernel_image = File::open(path).map_err(Error::OpenKernelImage)?;
+
+    L::load(
+        guest_mem,
+        kernel_offset,
+        &mut kernel_image,
+        highmem_start_address,
+    )
+}
+
+/// Loads a kernel image held in memory into guest memory with the loader `L`.
+///
+/// This is [`KernelLoader.load()`](trait.KernelLoader.html#tymethod.load) over an in-memory
+/// reader of `kernel_image`, for callers which already have the whole image, e.g. embedded in
+/// their binary.
+///
+/// # Arguments
+///
+/// * `guest_mem`: [`GuestMemory`] to load the kernel in.
+/// * `kernel_offset`: Passed through to `L`.
+/// * `kernel_image` - Input kernel image.
+/// * `highmem_start_address`: Passed through to `L`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// # #[cfg(all(feature = "elf", target_arch = "x86_64"))]
+/// # fn load(gm: &GuestMemoryMmap, kernel_image: &[u8]) -> Result<KernelLoaderResult> {
+/// load_kernel_bytes::<elf::Elf, _>(gm, None, kernel_image, None)
+/// # }
+/// ```
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+pub fn load_kernel_bytes<L, M>(
+    guest_mem: &M,
+    kernel_offset: Option<GuestAddress>,
+    kernel_image: &[u8],
+    highmem_start_address: Option<GuestAddress>,
+) -> Result<KernelLoaderResult>
+where
+    L: KernelLoader,
+    M: GuestMemory,
+{
+    L::load(
+        guest_mem,
+        kernel_offset,
+        &mut io::Cursor::new(kernel_image),
+        highmem_start_address,
+    )
+}
+
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 // SAFETY: The layout of the structure is fixed and can be initialized by
 // reading its content from byte array.
@@ -191,6 +812,10 @@ unsafe impl ByteValued for bootparam::boot_params {}
 
 /// Writes the command line string to the given guest memory slice.
 ///
+/// Returns the number of bytes written, including the null terminator, e.g. for the
+/// `cmdline_size` of the boot parameters or to find where the next region can start. An empty
+/// command line is written as a lone null terminator, i.e. 1 byte.
+///
 /// # Arguments
 ///
 /// * `guest_mem` - [`GuestMemory`] that will be partially overwritten by the command line.
@@ -212,102 +837,1976 @@ unsafe impl ByteValued for bootparam::boot_params {}
 /// let mut cl = Cmdline::new(10).unwrap();
 /// cl.insert("foo", "bar");
 /// let mut buf = vec![0u8;8];
-/// let result = load_cmdline(&gm, GuestAddress(0x1000), &cl).unwrap();
+/// let len = load_cmdline(&gm, GuestAddress(0x1000), &cl).unwrap();
+/// assert_eq!(len, 8);
 /// gm.read_slice(buf.as_mut_slice(), GuestAddress(0x1000)).unwrap();
 /// assert_eq!(buf.as_slice(), "foo=bar\0".as_bytes());
 pub fn load_cmdline<M: GuestMemory>(
     guest_mem: &M,
     guest_addr: GuestAddress,
     cmdline: &Cmdline,
-) -> Result<()> {
+) -> Result<usize> {
     // We need a null terminated string because that's what the Linux
     // kernel expects when parsing the command line:
     // https://elixir.bootlin.com/linux/v5.10.139/source/kernel/params.c#L179
     let cmdline_string = cmdline
         .as_cstring()
         .map_err(|_| Error::InvalidCommandLine)?;
-
     let cmdline_bytes = cmdline_string.as_bytes_with_nul();
 
-    let end = guest_addr
-        // Underflow not possible because the cmdline contains at least
-        // a byte (null terminator)
-        .checked_add((cmdline_bytes.len() - 1) as u64)
-        .ok_or(Error::CommandLineOverflow)?;
-    if end > guest_mem.last_addr() {
-        return Err(Error::CommandLineOverflow);
-    }
-
-    guest_mem
-        .write_slice(cmdline_bytes, guest_addr)
-        .map_err(|_| Error::CommandLineCopy)?;
+    write_cmdline(guest_mem, guest_addr, cmdline_bytes)?;
 
-    Ok(())
+    Ok(cmdline_bytes.len())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use vm_memory::{Address, GuestAddress};
-    type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
-
-    const MEM_SIZE: u64 = 0x100_0000;
+/// Writes the command line string to the given guest memory slice, making sure it fits in a
+/// command line region of `capacity` bytes.
+///
+/// This is meant for VMMs which reserve a fixed size region for the command line, e.g. because
+/// of the `cmdline_size` limit advertised by the x86 setup header.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] that will be partially overwritten by the command line.
+/// * `guest_addr` - The address in `guest_mem` at which to load the command line.
+/// * `cmdline` - The kernel command line.
+/// * `capacity` - Size of the command line region, including the null terminator.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// let mut cl = Cmdline::new(20).unwrap();
+/// cl.insert("foo", "bar").unwrap();
+/// assert!(load_cmdline_with_capacity(&gm, GuestAddress(0x1000), &cl, 8).is_ok());
+/// assert_eq!(
+///     load_cmdline_with_capacity(&gm, GuestAddress(0x1000), &cl, 7),
+///     Err(Error::CommandLineOverflow)
+/// );
+/// ```
+pub fn load_cmdline_with_capacity<M: GuestMemory>(
+    guest_mem: &M,
+    guest_addr: GuestAddress,
+    cmdline: &Cmdline,
+    capacity: GuestUsize,
+) -> Result<()> {
+    let cmdline_string = cmdline
+        .as_cstring()
+        .map_err(|_| Error::InvalidCommandLine)?;
 
-    fn create_guest_mem() -> GuestMemoryMmap {
-        GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), (MEM_SIZE as usize))]).unwrap()
+    let cmdline_bytes = cmdline_string.as_bytes_with_nul();
+    if cmdline_bytes.len() as GuestUsize > capacity {
+        return Err(Error::CommandLineOverflow);
     }
 
-    #[test]
-    fn test_cmdline_overflow() {
-        let gm = create_guest_mem();
-        let mut cl = Cmdline::new(10).unwrap();
-        cl.insert_str("12345").unwrap();
+    write_cmdline(guest_mem, guest_addr, cmdline_bytes)
+}
 
-        let cmdline_address = GuestAddress(u64::MAX - 5);
-        assert_eq!(
-            Err(Error::CommandLineOverflow),
-            load_cmdline(&gm, cmdline_address, &cl)
-        );
+/// Writes the command line string to the given guest memory slice, zeroing out the rest of the
+/// command line region.
+///
+/// This is [`load_cmdline_with_capacity`], except that the `capacity` bytes of the region are
+/// all written. When the region is reused, e.g. across reboots, no byte of a longer previous
+/// command line is left after the null terminator of the new one, where kernels scanning the
+/// whole region could pick them up. The whole region must be in guest memory.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] that will be partially overwritten by the command line.
+/// * `guest_addr` - The address in `guest_mem` at which to load the command line.
+/// * `cmdline` - The kernel command line.
+/// * `capacity` - Size of the command line region, including the null terminator.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// let mut cl = Cmdline::new(20).unwrap();
+/// cl.insert("foo", "bar").unwrap();
+/// assert!(load_cmdline_clear(&gm, GuestAddress(0x1000), &cl, 0x800).is_ok());
+/// ```
+pub fn load_cmdline_clear<M: GuestMemory>(
+    guest_mem: &M,
+    guest_addr: GuestAddress,
+    cmdline: &Cmdline,
+    capacity: GuestUsize,
+) -> Result<()> {
+    let cmdline_string = cmdline
+        .as_cstring()
+        .map_err(|_| Error::InvalidCommandLine)?;
 
-        let cmdline_address = GuestAddress(MEM_SIZE - 5);
-        assert_eq!(
-            Err(Error::CommandLineOverflow),
-            load_cmdline(&gm, cmdline_address, &cl)
-        );
-        let cmdline_address = GuestAddress(MEM_SIZE - 6);
-        assert!(load_cmdline(&gm, cmdline_address, &cl).is_ok());
+    let cmdline_bytes = cmdline_string.as_bytes_with_nul();
+    if cmdline_bytes.len() as GuestUsize > capacity {
+        return Err(Error::CommandLineOverflow);
     }
 
-    #[test]
-    fn test_cmdline_write_end_regresion() {
-        let gm = create_guest_mem();
-        let mut cmdline_address = GuestAddress(45);
-        let sample_buf = &[1; 100];
-
-        // Fill in guest memory with non zero bytes
-        gm.write(sample_buf, cmdline_address).unwrap();
-
-        let mut cl = Cmdline::new(10).unwrap();
-
-        // Test loading an empty cmdline
-        load_cmdline(&gm, cmdline_address, &cl).unwrap();
-        let val: u8 = gm.read_obj(cmdline_address).unwrap();
-        assert_eq!(val, b'\0');
+    // The region is written from host memory, it can't be larger than the address space.
+    let capacity = usize::try_from(capacity).map_err(|_| Error::CommandLineOverflow)?;
+    let mut region = vec![0u8; capacity];
+    region[..cmdline_bytes.len()].copy_from_slice(cmdline_bytes);
+    write_cmdline(guest_mem, guest_addr, &region)
+}
+
+/// Writes the command line string to the given guest memory slice, making sure it doesn't
+/// overlap any of the `reserved` regions.
+///
+/// This is meant to catch a command line address landing inside e.g. the kernel or initrd which
+/// were just loaded, which would silently corrupt them.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] that will be partially overwritten by the command line.
+/// * `guest_addr` - The address in `guest_mem` at which to load the command line.
+/// * `cmdline` - The kernel command line.
+/// * `reserved` - Start address and size of the regions the command line must not overlap.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// let mut cl = Cmdline::new(20).unwrap();
+/// cl.insert("foo", "bar").unwrap();
+/// let kernel = [(GuestAddress(0x1_0000), 0x1000)];
+/// assert!(load_cmdline_checked(&gm, GuestAddress(0x1000), &cl, &kernel).is_ok());
+/// assert_eq!(
+///     load_cmdline_checked(&gm, GuestAddress(0x1_0800), &cl, &kernel),
+///     Err(Error::CommandLineOverlapsReserved)
+/// );
+/// ```
+pub fn load_cmdline_checked<M: GuestMemory>(
+    guest_mem: &M,
+    guest_addr: GuestAddress,
+    cmdline: &Cmdline,
+    reserved: &[(GuestAddress, GuestUsize)],
+) -> Result<()> {
+    let cmdline_string = cmdline
+        .as_cstring()
+        .map_err(|_| Error::InvalidCommandLine)?;
+
+    let cmdline_bytes = cmdline_string.as_bytes_with_nul();
+    let start = guest_addr.raw_value();
+    let end = start
+        .checked_add(cmdline_bytes.len() as u64)
+        .ok_or(Error::CommandLineOverflow)?;
+    if reserved.iter().any(|&(region_addr, region_size)| {
+        let region_start = region_addr.raw_value();
+        let region_end = region_start.saturating_add(region_size);
+        start < region_end && region_start < end
+    }) {
+        return Err(Error::CommandLineOverlapsReserved);
+    }
+
+    write_cmdline(guest_mem, guest_addr, cmdline_bytes)
+}
+
+/// Writes a command line string to the given guest memory slice.
+///
+/// Unlike [`load_cmdline`], this takes the command line as a plain string, which is null
+/// terminated before being written to guest memory.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] that will be partially overwritten by the command line.
+/// * `guest_addr` - The address in `guest_mem` at which to load the command line.
+/// * `cmdline` - The kernel command line, which must not contain null bytes.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// assert!(load_cmdline_str(&gm, GuestAddress(0x1000), "console=ttyS0").is_ok());
+/// ```
+pub fn load_cmdline_str<M: GuestMemory>(
+    guest_mem: &M,
+    guest_addr: GuestAddress,
+    cmdline: &str,
+) -> Result<()> {
+    let cmdline_string = CString::new(cmdline).map_err(|_| Error::CommandLineContainsNul)?;
+
+    write_cmdline(guest_mem, guest_addr, cmdline_string.as_bytes_with_nul())
+}
+
+/// Appends arguments to a command line already in guest memory.
+///
+/// `extra` is written right after the `existing_len` bytes of the command line at `guest_addr`,
+/// separated from them by a space, and the result is null terminated again. Returns the length
+/// of the new command line, without the null terminator. Nothing is written when the new command
+/// line doesn't fit in guest memory.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] that will be partially overwritten by the command line.
+/// * `guest_addr` - The address in `guest_mem` of the existing command line.
+/// * `existing_len` - Length of the existing command line, without the null terminator.
+/// * `extra` - The arguments to append.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::{Bytes, GuestAddress};
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// load_cmdline_str(&gm, GuestAddress(0x1000), "console=ttyS0").unwrap();
+/// let len = append_cmdline(&gm, GuestAddress(0x1000), 13, c"quiet").unwrap();
+/// assert_eq!(len, 19);
+/// ```
+pub fn append_cmdline<M: GuestMemory>(
+    guest_mem: &M,
+    guest_addr: GuestAddress,
+    existing_len: GuestUsize,
+    extra: &CStr,
+) -> Result<GuestUsize> {
+    let extra_bytes = extra.to_bytes_with_nul();
+    let mut appended = Vec::with_capacity(extra_bytes.len() + 1);
+    if existing_len > 0 && extra_bytes.len() > 1 {
+        appended.push(b' ');
+    }
+    appended.extend_from_slice(extra_bytes);
+
+    let append_addr = guest_addr
+        .checked_add(existing_len)
+        .ok_or(Error::CommandLineOverflow)?;
+    write_cmdline(guest_mem, append_addr, &appended)?;
+
+    // Can't overflow, the new command line fits in guest memory.
+    Ok(existing_len + appended.len() as GuestUsize - 1)
+}
+
+/// Reads a command line back from guest memory.
+///
+/// This is the counterpart of [`load_cmdline`]: bytes are read from `guest_addr` up to the first
+/// null terminator, which must be within `max_len` bytes and within guest memory. Nothing past
+/// the null terminator is read.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] to read the command line from.
+/// * `guest_addr` - The address in `guest_mem` of the command line.
+/// * `max_len` - Maximum size of the command line, including the null terminator.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// load_cmdline_str(&gm, GuestAddress(0x1000), "console=ttyS0").unwrap();
+/// let cmdline = read_cmdline(&gm, GuestAddress(0x1000), 0x800).unwrap();
+/// assert_eq!(cmdline.as_bytes(), b"console=ttyS0");
+/// ```
+pub fn read_cmdline<M: GuestMemory>(
+    guest_mem: &M,
+    guest_addr: GuestAddress,
+    max_len: GuestUsize,
+) -> Result<CString> {
+    // Size of the chunks in which guest memory is read back.
+    const CHUNK_SIZE: usize = 0x100;
+
+    if guest_addr > guest_mem.last_addr() {
+        return Err(Error::CommandLineOverflow);
+    }
+    // Bytes left in guest memory from `guest_addr`.
+    let available = guest_mem
+        .last_addr()
+        .unchecked_offset_from(guest_addr)
+        .saturating_add(1);
+    // The command line is read into host memory, so it can't be larger than the address space.
+    let limit = usize::try_from(max_len.min(available)).map_err(|_| Error::CommandLineOverflow)?;
+
+    let mut cmdline = Vec::new();
+    let mut chunk = [0u8; CHUNK_SIZE];
+    while cmdline.len() < limit {
+        let len = std::cmp::min(CHUNK_SIZE, limit - cmdline.len());
+        // Can't overflow, the whole chunk is in guest memory.
+        let chunk_addr = guest_addr.unchecked_add(cmdline.len() as u64);
+        guest_mem
+            .read_slice(&mut chunk[..len], chunk_addr)
+            .map_err(|_| Error::ReadGuestMemory)?;
+        if let Some(nul) = chunk[..len].iter().position(|&b| b == 0) {
+            cmdline.extend_from_slice(&chunk[..nul]);
+            // Can't fail, `cmdline` contains no null bytes.
+            return CString::new(cmdline).map_err(|_| Error::InvalidCommandLine);
+        }
+        cmdline.extend_from_slice(&chunk[..len]);
+    }
+
+    Err(Error::CommandLineOverflow)
+}
+
+/// Writes the null terminated command line bytes to guest memory.
+fn write_cmdline<M: GuestMemory>(
+    guest_mem: &M,
+    guest_addr: GuestAddress,
+    cmdline_bytes: &[u8],
+) -> Result<()> {
+    // Address of the null terminator, `end` and `last_addr()` are both inclusive.
+    let end = guest_addr
+        // Underflow not possible because the cmdline contains at least
+        // a byte (null terminator)
+        .checked_add((cmdline_bytes.len() - 1) as u64)
+        .ok_or(Error::CommandLineOverflow)?;
+    if end > guest_mem.last_addr() {
+        return Err(Error::CommandLineOverflow);
+    }
+
+    guest_mem
+        .write_slice(cmdline_bytes, guest_addr)
+        .map_err(|_| Error::CommandLineCopy)?;
+
+    Ok(())
+}
+
+/// Loads an initrd image into guest memory.
+///
+/// The whole image is copied to `guest_addr`. Returns the load address and the size of the
+/// image, which is what the VMM needs to fill in the boot protocol fields.
+///
+/// `guest_addr` can be anywhere in guest memory, e.g. below the kernel for boot protocols
+/// expecting the initrd at a low fixed address. Checking that the initrd doesn't overlap the
+/// kernel is up to the caller.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] to load the initrd in.
+/// * `initrd_image` - Input initrd image.
+/// * `guest_addr` - The address in `guest_mem` at which to load the initrd.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// let initrd = vec![0xaa_u8; 0x1000];
+/// let (addr, size) = load_initrd(&gm, &mut Cursor::new(&initrd), GuestAddress(0x8_0000)).unwrap();
+/// assert_eq!(addr, GuestAddress(0x8_0000));
+/// assert_eq!(size, 0x1000);
+/// ```
+pub fn load_initrd<F, M: GuestMemory>(
+    guest_mem: &M,
+    initrd_image: &mut F,
+    guest_addr: GuestAddress,
+) -> Result<(GuestAddress, GuestUsize)>
+where
+    F: Read + Seek,
+{
+    let size = initrd_image
+        .seek(SeekFrom::End(0))
+        .map_err(|_| Error::SeekInitrdImage)?;
+    initrd_image
+        .seek(SeekFrom::Start(0))
+        .map_err(|_| Error::SeekInitrdImage)?;
+
+    if !fits_in_guest_memory(guest_mem, guest_addr, size) {
+        return Err(Error::InitrdImageSizeTooLarge);
+    }
+
+    // Can't fail on 64-bit hosts, the image fits in guest memory.
+    let count = usize::try_from(size).map_err(|_| Error::InitrdImageSizeTooLarge)?;
+    guest_mem
+        .read_exact_from(guest_addr, initrd_image, count)
+        .map_err(|_| Error::ReadInitrdImage)?;
+
+    Ok((guest_addr, size))
+}
+
+/// Loads an initrd image into guest memory, making sure it fits in `available` bytes.
+///
+/// This is meant for VMMs which only have `available` bytes of guest RAM left for the initrd,
+/// e.g. after the kernel. The size of the image is checked before anything is read from it, so
+/// a too large image fails right away with `InitrdImageSizeTooLarge`. Returns the load address
+/// and the size of the image.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] to load the initrd in.
+/// * `initrd_image` - Input initrd image.
+/// * `guest_addr` - The address in `guest_mem` at which to load the initrd.
+/// * `available` - Number of bytes available for the initrd at `guest_addr`.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// let initrd = vec![0xaa_u8; 0x1000];
+/// let mut image = Cursor::new(&initrd);
+/// assert!(load_initrd_with_limit(&gm, &mut image, GuestAddress(0x8_0000), 0x1000).is_ok());
+/// assert_eq!(
+///     load_initrd_with_limit(&gm, &mut image, GuestAddress(0x8_0000), 0xfff),
+///     Err(Error::InitrdImageSizeTooLarge)
+/// );
+/// ```
+pub fn load_initrd_with_limit<F, M: GuestMemory>(
+    guest_mem: &M,
+    initrd_image: &mut F,
+    guest_addr: GuestAddress,
+    available: GuestUsize,
+) -> Result<(GuestAddress, GuestUsize)>
+where
+    F: Read + Seek,
+{
+    let size = initrd_image
+        .seek(SeekFrom::End(0))
+        .map_err(|_| Error::SeekInitrdImage)?;
+    initrd_image
+        .seek(SeekFrom::Start(0))
+        .map_err(|_| Error::SeekInitrdImage)?;
+    if size > available {
+        return Err(Error::InitrdImageSizeTooLarge);
+    }
+
+    load_initrd(guest_mem, initrd_image, guest_addr)
+}
+
+/// Loads an initrd image into guest memory, as high as possible below `max_addr`.
+///
+/// `max_addr` is the highest address the initrd may occupy, like the `initrd_addr_max` field of
+/// the x86 setup header: kernels can't access an initrd above it. The initrd is placed at the
+/// highest page aligned address at which it ends at or below both `max_addr` and the end of
+/// guest memory. Returns the load address and the size of the image.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] to load the initrd in.
+/// * `initrd_image` - Input initrd image.
+/// * `max_addr` - The highest address the initrd may occupy.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// let initrd = vec![0xaa_u8; 0x1800];
+/// let (addr, size) =
+///     load_initrd_below(&gm, &mut Cursor::new(&initrd), GuestAddress(0x8_ffff)).unwrap();
+/// assert_eq!(addr, GuestAddress(0x8_e000));
+/// assert_eq!(size, 0x1800);
+/// ```
+pub fn load_initrd_below<F, M: GuestMemory>(
+    guest_mem: &M,
+    initrd_image: &mut F,
+    max_addr: GuestAddress,
+) -> Result<(GuestAddress, GuestUsize)>
+where
+    F: Read + Seek,
+{
+    // Alignment of the initrd load address.
+    const INITRD_ALIGN: u64 = 0x1000;
+
+    let size = initrd_image
+        .seek(SeekFrom::End(0))
+        .map_err(|_| Error::SeekInitrdImage)?;
+
+    let last_addr = std::cmp::min(max_addr, guest_mem.last_addr()).raw_value();
+    let guest_addr = last_addr
+        .checked_sub(size.saturating_sub(1))
+        .map(|start| start & !(INITRD_ALIGN - 1))
+        .ok_or(Error::InitrdImageSizeTooLarge)?;
+
+    load_initrd(guest_mem, initrd_image, GuestAddress(guest_addr))
+}
+
+/// Placement of the kernel, initrd and command line for [`load_image`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoadLayout {
+    /// Passed through to the kernel loader as `kernel_offset`.
+    pub kernel_start: Option<GuestAddress>,
+    /// Passed through to the kernel loader as `highmem_start_address`.
+    pub highmem_start_address: Option<GuestAddress>,
+    /// Alignment of the initrd, which is placed right after the end of the kernel. Must be a
+    /// power of two, 0 is the same as 1.
+    pub initrd_align: u64,
+    /// The address at which to load the command line.
+    pub cmdline_addr: GuestAddress,
+}
+
+/// Placements reported by [`load_image`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoadedImage {
+    /// Result of the kernel loader.
+    pub kernel: KernelLoaderResult,
+    /// Load address and size of the initrd, if any.
+    pub initrd: Option<(GuestAddress, GuestUsize)>,
+    /// Load address of the command line.
+    pub cmdline_addr: GuestAddress,
+    /// Size of the command line, including the null terminator.
+    pub cmdline_size: GuestUsize,
+    /// Record of the placements above, e.g. to be saved along with a snapshot.
+    pub boot_layout: BootLayout,
+}
+
+/// Where the boot artifacts were placed in guest memory, as plain addresses and sizes.
+///
+/// This is e.g. saved along with a snapshot or sent along with a migrating VM, for the restoring
+/// VMM to check that the guest memory it restores matches the placements it expects. It is
+/// serializable with the `serde` feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BootLayout {
+    /// Kernel entry point.
+    pub entry: u64,
+    /// `kernel_offset` the kernel was loaded with, if any.
+    pub kernel_start: Option<u64>,
+    /// End of the kernel in guest memory.
+    pub kernel_end: u64,
+    /// Load address and size of the initrd, if any.
+    pub initrd: Option<(u64, u64)>,
+    /// Load address and size of the command line, including the null terminator.
+    pub cmdline: (u64, u64),
+    /// Address of the zero page or of the device tree blob, if any. This is set up by the VMM
+    /// after loading the images, so it is left to `None` by [`load_image`].
+    pub boot_params: Option<u64>,
+}
+
+/// Loads a kernel, an optional initrd and the command line into guest memory.
+///
+/// The kernel is loaded with the loader `L`, the initrd is then loaded at the first address
+/// aligned to `layout.initrd_align` after `kernel_end` and the command line is written to
+/// `layout.cmdline_addr`.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] to load the images in.
+/// * `kernel` - Input kernel image.
+/// * `initrd` - Input initrd image, if any.
+/// * `cmdline` - The kernel command line.
+/// * `layout` - Where to place the kernel, initrd and command line.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// # #[cfg(all(feature = "elf", target_arch = "x86_64"))]
+/// # fn load(gm: &GuestMemoryMmap, kernel: &[u8], initrd: &[u8]) -> Result<LoadedImage> {
+/// let layout = LoadLayout {
+///     kernel_start: Some(GuestAddress(0x20_0000)),
+///     initrd_align: 0x1000,
+///     cmdline_addr: GuestAddress(0x2_0000),
+///     ..Default::default()
+/// };
+/// load_image::<elf::Elf, _, _, _>(
+///     gm,
+///     &mut Cursor::new(kernel),
+///     Some(&mut Cursor::new(initrd)),
+///     c"console=ttyS0",
+///     layout,
+/// )
+/// # }
+/// ```
+pub fn load_image<L, F, G, M>(
+    guest_mem: &M,
+    kernel: &mut F,
+    initrd: Option<&mut G>,
+    cmdline: &CStr,
+    layout: LoadLayout,
+) -> Result<LoadedImage>
+where
+    L: KernelLoader,
+    F: Read + Seek,
+    G: Read + Seek,
+    M: GuestMemory,
+{
+    let initrd_align = std::cmp::max(layout.initrd_align, 1);
+    if !initrd_align.is_power_of_two() {
+        return Err(Error::InvalidInitrdAlignment);
+    }
+
+    let kernel_result = L::load(
+        guest_mem,
+        layout.kernel_start,
+        kernel,
+        layout.highmem_start_address,
+    )?;
+
+    let initrd = match initrd {
+        Some(initrd) => {
+            let initrd_addr = kernel_result
+                .kernel_end
+                .checked_add(initrd_align - 1)
+                .map(|addr| addr & !(initrd_align - 1))
+                .ok_or(Error::InitrdImageSizeTooLarge)?;
+            Some(load_initrd(guest_mem, initrd, GuestAddress(initrd_addr))?)
+        }
+        None => None,
+    };
+
+    let cmdline_bytes = cmdline.to_bytes_with_nul();
+    write_cmdline(guest_mem, layout.cmdline_addr, cmdline_bytes)?;
+
+    let cmdline_size = cmdline_bytes.len() as GuestUsize;
+    Ok(LoadedImage {
+        kernel: kernel_result,
+        initrd,
+        cmdline_addr: layout.cmdline_addr,
+        cmdline_size,
+        boot_layout: BootLayout {
+            entry: kernel_result.kernel_load.raw_value(),
+            kernel_start: layout.kernel_start.map(|start| start.raw_value()),
+            kernel_end: kernel_result.kernel_end,
+            initrd: initrd.map(|(addr, size)| (addr.raw_value(), size)),
+            cmdline: (layout.cmdline_addr.raw_value(), cmdline_size),
+            boot_params: None,
+        },
+    })
+}
+
+/// Loads a flattened device tree blob into guest memory.
+///
+/// The blob must start with a valid FDT header, whose `totalsize` field matches the size of
+/// `fdt`. Returns the guest address of the blob.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] to load the device tree blob in.
+/// * `fdt_addr` - The address in `guest_mem` at which to load the device tree blob.
+/// * `fdt` - The device tree blob.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// # fn load(gm: &GuestMemoryMmap, fdt: &[u8]) -> Result<GuestAddress> {
+/// load_fdt(gm, GuestAddress(0x8000_0000), fdt)
+/// # }
+/// ```
+pub fn load_fdt<M: GuestMemory>(
+    guest_mem: &M,
+    fdt_addr: GuestAddress,
+    fdt: &[u8],
+) -> Result<GuestAddress> {
+    // Magic number of the FDT header, stored big endian like all its fields.
+    const FDT_MAGIC: u32 = 0xd00d_feed;
+
+    // `magic` and `totalsize` are the first two fields of the header.
+    if fdt.len() < 8 || fdt[0..4] != FDT_MAGIC.to_be_bytes() {
+        return Err(Error::InvalidFdtMagic);
+    }
+    let totalsize = u32::from_be_bytes([fdt[4], fdt[5], fdt[6], fdt[7]]);
+    if totalsize as usize != fdt.len() {
+        return Err(Error::FdtSizeMismatch);
+    }
+
+    if !fits_in_guest_memory(guest_mem, fdt_addr, fdt.len() as u64) {
+        return Err(Error::FdtTooLarge);
+    }
+    guest_mem
+        .write_slice(fdt, fdt_addr)
+        .map_err(|_| Error::WriteFdt)?;
+
+    Ok(fdt_addr)
+}
+
+/// Loads a flat binary into guest memory.
+///
+/// The image is copied verbatim to `load_addr`, which is also the entry point of the kernel. This
+/// is meant for raw kernel blobs which have no ELF or bzImage structure.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] to load the binary in.
+/// * `load_addr` - The address in `guest_mem` at which to load the binary.
+/// * `image` - Input flat binary.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// let image = vec![0x90_u8; 0x100];
+/// let result = load_flat_binary(&gm, GuestAddress(0x1000), &mut Cursor::new(&image)).unwrap();
+/// assert_eq!(result.kernel_load, GuestAddress(0x1000));
+/// assert_eq!(result.kernel_end, 0x1100);
+/// ```
+pub fn load_flat_binary<F, M: GuestMemory>(
+    guest_mem: &M,
+    load_addr: GuestAddress,
+    image: &mut F,
+) -> Result<KernelLoaderResult>
+where
+    F: Read + Seek,
+{
+    let size = image
+        .seek(SeekFrom::End(0))
+        .map_err(|_| Error::SeekFlatBinary)?;
+    image
+        .seek(SeekFrom::Start(0))
+        .map_err(|_| Error::SeekFlatBinary)?;
+
+    if !fits_in_guest_memory(guest_mem, load_addr, size) {
+        return Err(Error::FlatBinaryTooLarge);
+    }
+
+    let mut loader_result = KernelLoaderResult {
+        kernel_load: load_addr,
+        ..Default::default()
+    };
+
+    let count = usize::try_from(size).map_err(|_| Error::FlatBinaryTooLarge)?;
+    guest_mem
+        .read_exact_from(load_addr, image, count)
+        .map_err(|_| Error::ReadFlatBinary)?;
+
+    // Can't overflow, the binary fits in guest memory.
+    loader_result.kernel_end = load_addr.raw_value() + size;
+    loader_result.bytes_loaded = size;
+
+    Ok(loader_result)
+}
+
+/// Loads regions of a flat binary into guest memory.
+///
+/// Each `(file_offset, guest_addr, len)` region copies `len` bytes of `image` starting at
+/// `file_offset` to `guest_addr`. This is meant for images whose file layout doesn't match their
+/// guest physical layout, e.g. blobs with a stripped header. All regions are checked against the
+/// image size and guest memory before anything is copied.
+///
+/// The returned `kernel_load` is the guest address of the first region, `kernel_end` the end of
+/// the highest region and `bytes_loaded` the total length of the regions.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] to load the binary in.
+/// * `regions` - The regions to copy, as `(file_offset, guest_addr, len)` tuples.
+/// * `image` - Input flat binary.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// let image = vec![0x90_u8; 0x200];
+/// let regions = [(0x100, GuestAddress(0x1000), 0x100)];
+/// let result = load_flat_binary_regions(&gm, &regions, &mut Cursor::new(&image)).unwrap();
+/// assert_eq!(result.kernel_load, GuestAddress(0x1000));
+/// assert_eq!(result.kernel_end, 0x1100);
+/// ```
+pub fn load_flat_binary_regions<F, M: GuestMemory>(
+    guest_mem: &M,
+    regions: &[(u64, GuestAddress, u64)],
+    image: &mut F,
+) -> Result<KernelLoaderResult>
+where
+    F: Read + Seek,
+{
+    let size = image
+        .seek(SeekFrom::End(0))
+        .map_err(|_| Error::SeekFlatBinary)?;
+
+    let mut loader_result = KernelLoaderResult::default();
+    for (index, &(file_offset, guest_addr, len)) in regions.iter().enumerate() {
+        match file_offset.checked_add(len) {
+            Some(file_end) if file_end <= size => {}
+            _ => return Err(Error::RegionOutOfBounds),
+        }
+        if !fits_in_guest_memory(guest_mem, guest_addr, len) {
+            return Err(Error::RegionOutOfBounds);
+        }
+
+        if index == 0 {
+            loader_result.kernel_load = guest_addr;
+        }
+        // Can't overflow, the region fits in guest memory.
+        loader_result.kernel_end = loader_result.kernel_end.max(guest_addr.raw_value() + len);
+        loader_result.bytes_loaded += len;
+    }
+
+    for &(file_offset, guest_addr, len) in regions {
+        image
+            .seek(SeekFrom::Start(file_offset))
+            .map_err(|_| Error::SeekFlatBinary)?;
+        let count = usize::try_from(len).map_err(|_| Error::RegionOutOfBounds)?;
+        guest_mem
+            .read_exact_from(guest_addr, image, count)
+            .map_err(|_| Error::ReadFlatBinary)?;
+    }
+
+    Ok(loader_result)
+}
+
+/// Applies a relocation table to a kernel loaded at a non-default address.
+///
+/// `relocs` is a table in the format of the x86 decompressor (`arch/x86/boot/compressed`): a list
+/// of little endian 32-bit offsets read from the end of the table towards its start, up to a 0
+/// entry. `delta` is added to the 32-bit word at `kernel_load` plus each offset. The whole table
+/// is checked before guest memory is written, so nothing is patched when an entry is invalid.
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] holding the loaded kernel.
+/// * `kernel_load` - The address in `guest_mem` the offsets are relative to.
+/// * `relocs` - The relocation table.
+/// * `delta` - Difference between the actual and the default load address of the kernel.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::{Bytes, GuestAddress};
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// gm.write_obj(0x1000_u32, GuestAddress(0x1_0010)).unwrap();
+/// // A single relocation at offset 0x10, after the terminator.
+/// let relocs = [0u32, 0x10].iter().flat_map(|r| r.to_le_bytes()).collect::<Vec<u8>>();
+/// apply_relocations(&gm, GuestAddress(0x1_0000), &relocs, 0x20_0000).unwrap();
+/// assert_eq!(gm.read_obj::<u32>(GuestAddress(0x1_0010)).unwrap(), 0x20_1000);
+/// ```
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn apply_relocations<M: GuestMemory>(
+    guest_mem: &M,
+    kernel_load: GuestAddress,
+    relocs: &[u8],
+    delta: i64,
+) -> Result<()> {
+    const ENTRY_SIZE: usize = std::mem::size_of::<u32>();
+
+    if !relocs.len().is_multiple_of(ENTRY_SIZE) {
+        return Err(Error::InvalidRelocationEntry);
+    }
+    let mut addrs = vec![];
+    let mut terminated = false;
+    for entry in relocs.chunks_exact(ENTRY_SIZE).rev() {
+        let offset = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+        if offset == 0 {
+            terminated = true;
+            break;
+        }
+        let addr = kernel_load
+            .checked_add(u64::from(offset))
+            .filter(|&addr| fits_in_guest_memory(guest_mem, addr, ENTRY_SIZE as u64))
+            .ok_or(Error::InvalidRelocationEntry)?;
+        addrs.push(addr);
+    }
+    if !terminated {
+        return Err(Error::InvalidRelocationEntry);
+    }
+
+    for addr in addrs {
+        let word: u32 = guest_mem
+            .read_obj(addr)
+            .map_err(|_| Error::InvalidRelocationEntry)?;
+        // Only the low 32 bits of the relocated address are kept, as in the decompressor.
+        guest_mem
+            .write_obj(word.wrapping_add(delta as u32), addr)
+            .map_err(|_| Error::InvalidRelocationEntry)?;
+    }
+    Ok(())
+}
+
+/// Computes the CRC-32 of guest memory regions, e.g. the ones a kernel was loaded to.
+///
+/// The regions are read back in order and hashed as a single stream, using the IEEE 802.3
+/// CRC-32 (the one of `zlib` and `cksum -a crc32b`). This is meant for consistency checks, such
+/// as comparing the loaded kernel on both ends of a migration, not for authentication. The
+/// regions of an ELF kernel are given by
+/// [`Elf::parse_segments`](elf/struct.Elf.html#method.parse_segments).
+///
+/// # Arguments
+///
+/// * `guest_mem` - [`GuestMemory`] to read the regions from.
+/// * `regions` - Start address and size of each region.
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::{Bytes, GuestAddress};
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x10_0000)]).unwrap();
+/// gm.write_slice(b"123456789", GuestAddress(0x1000)).unwrap();
+/// let crc = checksum_loaded(&gm, &[(GuestAddress(0x1000), 9)]).unwrap();
+/// assert_eq!(crc, 0xcbf4_3926);
+/// ```
+pub fn checksum_loaded<M: GuestMemory>(
+    guest_mem: &M,
+    regions: &[(GuestAddress, GuestUsize)],
+) -> Result<u32> {
+    // Size of the chunks in which guest memory is read back.
+    const CHUNK_SIZE: usize = 0x1000;
+
+    let mut crc = !0u32;
+    let mut chunk = [0u8; CHUNK_SIZE];
+    for &(addr, size) in regions {
+        let mut offset = 0;
+        while offset < size {
+            // Fits in `usize`, it is at most `CHUNK_SIZE`.
+            let len = std::cmp::min(CHUNK_SIZE as GuestUsize, size - offset) as usize;
+            let chunk_addr = addr.checked_add(offset).ok_or(Error::ReadGuestMemory)?;
+            guest_mem
+                .read_slice(&mut chunk[..len], chunk_addr)
+                .map_err(|_| Error::ReadGuestMemory)?;
+            crc = crc32_update(crc, &chunk[..len]);
+            offset += len as GuestUsize;
+        }
+    }
+
+    Ok(!crc)
+}
+
+/// Updates a (bit inverted) CRC-32 with `data`, one bit at a time.
+fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    // Reversed IEEE 802.3 polynomial.
+    const CRC32_POLY: u32 = 0xedb8_8320;
+
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ CRC32_POLY
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// Checks that `size` bytes starting at `guest_addr` are within guest memory.
+fn fits_in_guest_memory<M: GuestMemory>(
+    guest_mem: &M,
+    guest_addr: GuestAddress,
+    size: u64,
+) -> bool {
+    if size == 0 {
+        return true;
+    }
+    match guest_addr.checked_add(size - 1) {
+        Some(end) => end <= guest_mem.last_addr(),
+        None => false,
+    }
+}
+
+// The tests of the loaders are gated on their features, like the loaders. Each loader builds
+// and is tested on its own, e.g. with `cargo test --no-default-features --features elf`, see
+// `.buildkite/custom-tests.json` for the combinations run in CI.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use vm_memory::{Address, GuestAddress};
+    type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+
+    const MEM_SIZE: u64 = 0x100_0000;
+
+    fn create_guest_mem() -> GuestMemoryMmap {
+        GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), (MEM_SIZE as usize))]).unwrap()
+    }
+
+    #[test]
+    fn test_cmdline_overflow() {
+        let gm = create_guest_mem();
+        let mut cl = Cmdline::new(10).unwrap();
+        cl.insert_str("12345").unwrap();
+
+        let cmdline_address = GuestAddress(u64::MAX - 5);
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline(&gm, cmdline_address, &cl)
+        );
+
+        let cmdline_address = GuestAddress(MEM_SIZE - 5);
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline(&gm, cmdline_address, &cl)
+        );
+        let cmdline_address = GuestAddress(MEM_SIZE - 6);
+        assert_eq!(load_cmdline(&gm, cmdline_address, &cl), Ok(6));
+    }
+
+    #[test]
+    fn test_cmdline_capacity() {
+        let gm = create_guest_mem();
+        let mut cl = Cmdline::new(20).unwrap();
+        cl.insert_str("console=ttyS0").unwrap();
+        let cmdline_address = GuestAddress(0x1000);
+
+        // 13 characters plus the null terminator.
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline_with_capacity(&gm, cmdline_address, &cl, 13)
+        );
+        load_cmdline_with_capacity(&gm, cmdline_address, &cl, 14).unwrap();
+        let mut buf = [0u8; 14];
+        gm.read_slice(&mut buf, cmdline_address).unwrap();
+        assert_eq!(&buf, b"console=ttyS0\0");
+        // The capacity is a guest size, it isn't truncated to the host pointer width.
+        load_cmdline_with_capacity(&gm, cmdline_address, &cl, 0x1_0000_0000).unwrap();
+
+        // The guest memory bounds still apply.
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline_with_capacity(&gm, GuestAddress(MEM_SIZE - 13), &cl, 0x100)
+        );
+    }
+
+    #[test]
+    fn test_cmdline_clear() {
+        let gm = create_guest_mem();
+        let cmdline_address = GuestAddress(0x1000);
+        gm.write_slice(&[0xff; 0x40], cmdline_address).unwrap();
+
+        let mut cl = Cmdline::new(0x40).unwrap();
+        cl.insert_str("console=ttyS0 reboot=k panic=1").unwrap();
+        load_cmdline(&gm, cmdline_address, &cl).unwrap();
+
+        let mut cl = Cmdline::new(20).unwrap();
+        cl.insert_str("quiet").unwrap();
+        load_cmdline_clear(&gm, cmdline_address, &cl, 0x20).unwrap();
+        let mut buf = [0xffu8; 0x40];
+        gm.read_slice(&mut buf, cmdline_address).unwrap();
+        assert_eq!(&buf[..6], b"quiet\0");
+        assert!(buf[6..0x20].iter().all(|&b| b == 0));
+        // Nothing is written past the region.
+        assert_eq!(buf[0x20..], [0xff; 0x20]);
+
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline_clear(&gm, cmdline_address, &cl, 5)
+        );
+        // The whole region must be in guest memory.
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline_clear(&gm, GuestAddress(MEM_SIZE - 0x10), &cl, 0x20)
+        );
+        load_cmdline_clear(&gm, GuestAddress(MEM_SIZE - 0x20), &cl, 0x20).unwrap();
+    }
+
+    #[test]
+    fn test_cmdline_boundary() {
+        let gm = create_guest_mem();
+        let last_addr = gm.last_addr();
+        let mut cl = Cmdline::new(10).unwrap();
+        cl.insert_str("123").unwrap();
+
+        // The null terminator lands right on the last byte of guest memory.
+        assert_eq!(
+            load_cmdline(&gm, GuestAddress(last_addr.raw_value() - 3), &cl),
+            Ok(4)
+        );
+        let val: u8 = gm.read_obj(last_addr).unwrap();
+        assert_eq!(val, b'\0');
+        // One byte past it.
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline(&gm, GuestAddress(last_addr.raw_value() - 2), &cl)
+        );
+
+        // An empty command line is just the null terminator.
+        let cl = Cmdline::new(10).unwrap();
+        assert_eq!(load_cmdline(&gm, last_addr, &cl), Ok(1));
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline(&gm, last_addr.unchecked_add(1), &cl)
+        );
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline(&gm, GuestAddress(u64::MAX), &cl)
+        );
+    }
+
+    #[test]
+    fn test_cmdline_write_end_regresion() {
+        let gm = create_guest_mem();
+        let mut cmdline_address = GuestAddress(45);
+        let sample_buf = &[1; 100];
+
+        // Fill in guest memory with non zero bytes
+        gm.write(sample_buf, cmdline_address).unwrap();
+
+        let mut cl = Cmdline::new(10).unwrap();
+
+        // Test loading an empty cmdline
+        assert_eq!(load_cmdline(&gm, cmdline_address, &cl), Ok(1));
+        let val: u8 = gm.read_obj(cmdline_address).unwrap();
+        assert_eq!(val, b'\0');
 
         // Test loading an non-empty cmdline
         cl.insert_str("123").unwrap();
-        load_cmdline(&gm, cmdline_address, &cl).unwrap();
+        assert_eq!(load_cmdline(&gm, cmdline_address, &cl), Ok(4));
+
+        let val: u8 = gm.read_obj(cmdline_address).unwrap();
+        assert_eq!(val, b'1');
+        cmdline_address = cmdline_address.unchecked_add(1);
+        let val: u8 = gm.read_obj(cmdline_address).unwrap();
+        assert_eq!(val, b'2');
+        cmdline_address = cmdline_address.unchecked_add(1);
+        let val: u8 = gm.read_obj(cmdline_address).unwrap();
+        assert_eq!(val, b'3');
+        cmdline_address = cmdline_address.unchecked_add(1);
+        let val: u8 = gm.read_obj(cmdline_address).unwrap();
+        assert_eq!(val, b'\0');
+    }
+
+    #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+    #[test]
+    fn test_cmdline_checked() {
+        let gm = create_guest_mem();
+        let mut cl = Cmdline::new(10).unwrap();
+        cl.insert_str("12345").unwrap();
+
+        let image = include_bytes!("x86_64/elf/test_elf.bin");
+        let kernel_addr = GuestAddress(0x20_0000);
+        let result = load_kernel_bytes::<elf::Elf, _>(&gm, Some(kernel_addr), image, None).unwrap();
+        let kernel = [(kernel_addr, result.kernel_end - kernel_addr.raw_value())];
+
+        // Inside the loaded kernel, the kernel is left untouched.
+        let mut before = [0u8; 6];
+        gm.read_slice(&mut before, GuestAddress(0x20_0400)).unwrap();
+        assert_eq!(
+            Err(Error::CommandLineOverlapsReserved),
+            load_cmdline_checked(&gm, GuestAddress(0x20_0400), &cl, &kernel)
+        );
+        let mut after = [0u8; 6];
+        gm.read_slice(&mut after, GuestAddress(0x20_0400)).unwrap();
+        assert_eq!(before, after);
+
+        // Straddling the start of the kernel.
+        assert_eq!(
+            Err(Error::CommandLineOverlapsReserved),
+            load_cmdline_checked(&gm, GuestAddress(0x1f_fffc), &cl, &kernel)
+        );
+        // Right before and right after the kernel.
+        assert!(load_cmdline_checked(&gm, GuestAddress(0x1f_fffa), &cl, &kernel).is_ok());
+        assert!(load_cmdline_checked(&gm, GuestAddress(result.kernel_end), &cl, &kernel).is_ok());
+    }
 
-        let val: u8 = gm.read_obj(cmdline_address).unwrap();
-        assert_eq!(val, b'1');
-        cmdline_address = cmdline_address.unchecked_add(1);
-        let val: u8 = gm.read_obj(cmdline_address).unwrap();
-        assert_eq!(val, b'2');
-        cmdline_address = cmdline_address.unchecked_add(1);
-        let val: u8 = gm.read_obj(cmdline_address).unwrap();
-        assert_eq!(val, b'3');
-        cmdline_address = cmdline_address.unchecked_add(1);
-        let val: u8 = gm.read_obj(cmdline_address).unwrap();
-        assert_eq!(val, b'\0');
+    #[test]
+    fn test_cmdline_str() {
+        let gm = create_guest_mem();
+        let cmdline_address = GuestAddress(0x1000);
+        gm.write_slice(&[0xff; 16], cmdline_address).unwrap();
+
+        load_cmdline_str(&gm, cmdline_address, "foo=bar").unwrap();
+        let mut buf = [0u8; 9];
+        gm.read_slice(&mut buf, cmdline_address).unwrap();
+        assert_eq!(&buf, b"foo=bar\0\xff");
+
+        assert_eq!(
+            Err(Error::CommandLineContainsNul),
+            load_cmdline_str(&gm, cmdline_address, "foo\0bar")
+        );
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            load_cmdline_str(&gm, GuestAddress(MEM_SIZE - 7), "foo=bar")
+        );
+    }
+
+    #[test]
+    fn test_append_cmdline() {
+        let gm = create_guest_mem();
+        let cmdline_address = GuestAddress(0x1000);
+        gm.write_slice(&[0xff; 32], cmdline_address).unwrap();
+        let read_cmdline = |len: GuestUsize| {
+            let mut buf = vec![0u8; len as usize];
+            gm.read_slice(&mut buf, cmdline_address).unwrap();
+            buf
+        };
+
+        load_cmdline_str(&gm, cmdline_address, "console=ttyS0").unwrap();
+        let len = append_cmdline(&gm, cmdline_address, 13, c"quiet panic=1").unwrap();
+        assert_eq!(len, 27);
+        assert_eq!(read_cmdline(len + 2), b"console=ttyS0 quiet panic=1\0\xff");
+
+        // Nothing to append to, or nothing to append.
+        let len = append_cmdline(&gm, cmdline_address, 0, c"quiet").unwrap();
+        assert_eq!(len, 5);
+        assert_eq!(read_cmdline(len + 1), b"quiet\0");
+        assert_eq!(append_cmdline(&gm, cmdline_address, len, c"").unwrap(), 5);
+        assert_eq!(read_cmdline(len + 1), b"quiet\0");
+
+        // The new command line, including its null terminator, must fit in guest memory.
+        let cmdline_address = GuestAddress(MEM_SIZE - 8);
+        load_cmdline_str(&gm, cmdline_address, "foo").unwrap();
+        assert_eq!(append_cmdline(&gm, cmdline_address, 3, c"bar").unwrap(), 7);
+        load_cmdline_str(&gm, cmdline_address, "foo").unwrap();
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            append_cmdline(&gm, cmdline_address, 3, c"barb")
+        );
+        let mut buf = [0u8; 4];
+        gm.read_slice(&mut buf, cmdline_address).unwrap();
+        assert_eq!(&buf, b"foo\0");
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            append_cmdline(&gm, GuestAddress(u64::MAX), 1, c"bar")
+        );
+    }
+
+    #[test]
+    fn test_read_cmdline() {
+        let gm = create_guest_mem();
+        let cmdline_address = GuestAddress(0x1000);
+        gm.write_slice(&[0xff; 0x400], cmdline_address).unwrap();
+
+        // Longer than a read chunk.
+        let long = "a".repeat(0x180);
+        load_cmdline_str(&gm, cmdline_address, &long).unwrap();
+        let cmdline = read_cmdline(&gm, cmdline_address, 0x800).unwrap();
+        assert_eq!(cmdline.as_bytes(), long.as_bytes());
+
+        load_cmdline_str(&gm, cmdline_address, "console=ttyS0").unwrap();
+        let cmdline = read_cmdline(&gm, cmdline_address, 14).unwrap();
+        assert_eq!(cmdline.as_bytes(), b"console=ttyS0");
+        let cmdline = read_cmdline(&gm, cmdline_address, 0x800).unwrap();
+        assert_eq!(cmdline.as_bytes(), b"console=ttyS0");
+
+        // No null terminator within `max_len`.
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            read_cmdline(&gm, cmdline_address, 13)
+        );
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            read_cmdline(&gm, cmdline_address, 0)
+        );
+
+        // Near the end of guest memory.
+        let last_addr = gm.last_addr();
+        load_cmdline_str(&gm, GuestAddress(last_addr.raw_value() - 3), "123").unwrap();
+        let cmdline = read_cmdline(&gm, GuestAddress(last_addr.raw_value() - 3), 0x800).unwrap();
+        assert_eq!(cmdline.as_bytes(), b"123");
+        gm.write_obj(0xffu8, last_addr).unwrap();
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            read_cmdline(&gm, GuestAddress(last_addr.raw_value() - 3), 0x800)
+        );
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            read_cmdline(&gm, last_addr.unchecked_add(1), 0x800)
+        );
+        assert_eq!(
+            Err(Error::CommandLineOverflow),
+            read_cmdline(&gm, GuestAddress(u64::MAX), 0x800)
+        );
+    }
+
+    #[test]
+    fn test_load_initrd() {
+        let gm = create_guest_mem();
+        let initrd = vec![0xaa_u8; 0x1000];
+        let initrd_addr = GuestAddress(MEM_SIZE - 0x1000);
+
+        let (addr, size) = load_initrd(&gm, &mut Cursor::new(&initrd), initrd_addr).unwrap();
+        assert_eq!(addr, initrd_addr);
+        assert_eq!(size, initrd.len() as u64);
+
+        let mut buf = vec![0u8; initrd.len()];
+        gm.read_slice(buf.as_mut_slice(), initrd_addr).unwrap();
+        assert_eq!(buf, initrd);
+    }
+
+    #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+    #[test]
+    fn test_load_initrd_low() {
+        let gm = create_guest_mem();
+        let kernel_image = include_bytes!("x86_64/elf/test_elf.bin");
+        let kernel_addr = GuestAddress(0x20_0000);
+        let loader_result =
+            load_kernel_bytes::<elf::Elf, _>(&gm, Some(kernel_addr), kernel_image, None).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x20_042e);
+
+        // Right below the kernel, which spans [0x20_0000, 0x20_042e).
+        let initrd = vec![0xaa_u8; 0x1000];
+        let initrd_addr = GuestAddress(0x1f_f000);
+        let (addr, size) = load_initrd(&gm, &mut Cursor::new(&initrd), initrd_addr).unwrap();
+        assert_eq!(addr, initrd_addr);
+        assert_eq!(addr.raw_value() + size, kernel_addr.raw_value());
+
+        let mut buf = vec![0u8; initrd.len()];
+        gm.read_slice(buf.as_mut_slice(), initrd_addr).unwrap();
+        assert_eq!(buf, initrd);
+        // The kernel is untouched.
+        let mut buf = [0u8; 0x1d];
+        gm.read_slice(&mut buf, kernel_addr).unwrap();
+        assert_eq!(&buf[..], &kernel_image[0xb0..0xcd]);
+    }
+
+    #[test]
+    fn test_load_initrd_too_large() {
+        let gm = create_guest_mem();
+        let initrd = vec![0xaa_u8; 0x1000];
+
+        assert_eq!(
+            Err(Error::InitrdImageSizeTooLarge),
+            load_initrd(
+                &gm,
+                &mut Cursor::new(&initrd),
+                GuestAddress(MEM_SIZE - 0xfff)
+            )
+        );
+        assert_eq!(
+            Err(Error::InitrdImageSizeTooLarge),
+            load_initrd(&gm, &mut Cursor::new(&initrd), GuestAddress(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_load_initrd_with_limit() {
+        let gm = create_guest_mem();
+        let initrd = vec![0xaa_u8; 0x1000];
+        let initrd_addr = GuestAddress(0x10_0000);
+
+        assert_eq!(
+            load_initrd_with_limit(&gm, &mut Cursor::new(&initrd), initrd_addr, 0x1000),
+            Ok((initrd_addr, 0x1000))
+        );
+
+        // Nothing is read from a too large image, guest memory is left untouched.
+        gm.write_slice(&[0u8; 0x1000], initrd_addr).unwrap();
+        let mut image = Cursor::new(&initrd);
+        assert_eq!(
+            Err(Error::InitrdImageSizeTooLarge),
+            load_initrd_with_limit(&gm, &mut image, initrd_addr, 0xfff)
+        );
+        assert_eq!(image.position(), 0);
+        let mut buf = vec![0xffu8; initrd.len()];
+        gm.read_slice(buf.as_mut_slice(), initrd_addr).unwrap();
+        assert!(buf.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_load_initrd_below() {
+        let gm = create_guest_mem();
+        let initrd = vec![0xaa_u8; 0x1800];
+
+        let (addr, size) =
+            load_initrd_below(&gm, &mut Cursor::new(&initrd), GuestAddress(0x20_0fff)).unwrap();
+        assert_eq!(addr, GuestAddress(0x1f_f000));
+        assert_eq!(size, initrd.len() as u64);
+        let mut buf = vec![0u8; initrd.len()];
+        gm.read_slice(buf.as_mut_slice(), addr).unwrap();
+        assert_eq!(buf, initrd);
+
+        // Capped by the end of guest memory.
+        let (addr, _) =
+            load_initrd_below(&gm, &mut Cursor::new(&initrd), GuestAddress(u64::MAX)).unwrap();
+        assert_eq!(addr, GuestAddress(MEM_SIZE - 0x2000));
+
+        // Doesn't fit below `max_addr`.
+        assert_eq!(
+            Err(Error::InitrdImageSizeTooLarge),
+            load_initrd_below(&gm, &mut Cursor::new(&initrd), GuestAddress(0x17fe))
+        );
+        assert!(load_initrd_below(&gm, &mut Cursor::new(&initrd), GuestAddress(0x17ff)).is_ok());
+    }
+
+    // Minimal device tree blob: a header and an empty memory reservation block and
+    // structure block, as generated by `dtc` from `/dts-v1/; / { };`.
+    fn make_fdt() -> Vec<u8> {
+        let mut fdt = vec![];
+        for field in [
+            0xd00d_feedu32, // magic
+            0x48,           // totalsize
+            0x38,           // off_dt_struct
+            0x48,           // off_dt_strings
+            0x28,           // off_mem_rsvmap
+            17,             // version
+            16,             // last_comp_version
+            0,              // boot_cpuid_phys
+            0,              // size_dt_strings
+            0x10,           // size_dt_struct
+        ] {
+            fdt.extend_from_slice(&field.to_be_bytes());
+        }
+        // Memory reservation block terminator.
+        fdt.extend_from_slice(&[0u8; 16]);
+        // FDT_BEGIN_NODE (root node, empty name), FDT_END_NODE, FDT_END.
+        for token in [1u32, 0, 2, 9] {
+            fdt.extend_from_slice(&token.to_be_bytes());
+        }
+        fdt
+    }
+
+    #[test]
+    fn test_load_fdt() {
+        let gm = create_guest_mem();
+        let fdt = make_fdt();
+        let fdt_addr = GuestAddress(MEM_SIZE - fdt.len() as u64);
+
+        assert_eq!(load_fdt(&gm, fdt_addr, &fdt), Ok(fdt_addr));
+        let mut buf = vec![0u8; fdt.len()];
+        gm.read_slice(buf.as_mut_slice(), fdt_addr).unwrap();
+        assert_eq!(buf, fdt);
+
+        assert_eq!(
+            Err(Error::FdtTooLarge),
+            load_fdt(&gm, fdt_addr.unchecked_add(1), &fdt)
+        );
+        assert_eq!(
+            Err(Error::FdtSizeMismatch),
+            load_fdt(&gm, GuestAddress(0), &fdt[..fdt.len() - 4])
+        );
+
+        let mut bad_fdt = fdt.clone();
+        bad_fdt[0] = 0xfe;
+        assert_eq!(
+            Err(Error::InvalidFdtMagic),
+            load_fdt(&gm, GuestAddress(0), &bad_fdt)
+        );
+        assert_eq!(
+            Err(Error::InvalidFdtMagic),
+            load_fdt(&gm, GuestAddress(0), &fdt[..4])
+        );
+    }
+
+    #[test]
+    fn test_load_flat_binary() {
+        let gm = create_guest_mem();
+        let image: Vec<u8> = (0..=0xff_u8).collect();
+        let load_addr = GuestAddress(MEM_SIZE - 0x100);
+
+        let result = load_flat_binary(&gm, load_addr, &mut Cursor::new(&image)).unwrap();
+        assert_eq!(result.kernel_load, load_addr);
+        assert_eq!(result.kernel_end, MEM_SIZE);
+        assert_eq!(result.bytes_loaded, 0x100);
+
+        let mut buf = vec![0u8; image.len()];
+        gm.read_slice(buf.as_mut_slice(), load_addr).unwrap();
+        assert_eq!(buf, image);
+
+        assert_eq!(
+            Err(Error::FlatBinaryTooLarge),
+            load_flat_binary(&gm, GuestAddress(MEM_SIZE - 0xff), &mut Cursor::new(&image))
+        );
+    }
+
+    #[test]
+    fn test_load_flat_binary_regions() {
+        let gm = create_guest_mem();
+        let image: Vec<u8> = (0..=0xff_u8).collect();
+        let regions = [
+            (0x80, GuestAddress(0x1000), 0x40),
+            (0x10, GuestAddress(MEM_SIZE - 0x20), 0x20),
+        ];
+
+        let result = load_flat_binary_regions(&gm, &regions, &mut Cursor::new(&image)).unwrap();
+        assert_eq!(result.kernel_load, GuestAddress(0x1000));
+        assert_eq!(result.kernel_end, MEM_SIZE);
+        assert_eq!(result.bytes_loaded, 0x60);
+
+        let mut buf = vec![0u8; 0x48];
+        gm.read_slice(buf.as_mut_slice(), GuestAddress(0x1000))
+            .unwrap();
+        assert_eq!(buf[..0x40], image[0x80..0xc0]);
+        assert_eq!(buf[0x40..], [0u8; 8]);
+        let mut buf = vec![0u8; 0x20];
+        gm.read_slice(buf.as_mut_slice(), GuestAddress(MEM_SIZE - 0x20))
+            .unwrap();
+        assert_eq!(buf, image[0x10..0x30]);
+
+        // Nothing is copied when one of the regions is out of bounds.
+        let gm = create_guest_mem();
+        for bad_region in [
+            (0xf0, GuestAddress(0x2000), 0x11),
+            (u64::MAX, GuestAddress(0x2000), 0x2),
+            (0, GuestAddress(MEM_SIZE - 0x1f), 0x20),
+            (0, GuestAddress(u64::MAX), 0x2),
+        ] {
+            assert_eq!(
+                Err(Error::RegionOutOfBounds),
+                load_flat_binary_regions(
+                    &gm,
+                    &[(0, GuestAddress(0x1000), 0x10), bad_region],
+                    &mut Cursor::new(&image)
+                )
+            );
+        }
+        assert_eq!(gm.read_obj::<u8>(GuestAddress(0x100f)).unwrap(), 0);
+
+        assert_eq!(
+            Error::RegionOutOfBounds.to_string(),
+            "Kernel Loader: flat binary region is out of bounds"
+        );
+    }
+
+    #[test]
+    fn test_sniff_format() {
+        let sniff = |image: &[u8]| {
+            let mut cursor = Cursor::new(image);
+            let format = sniff_format(&mut cursor).unwrap();
+            assert_eq!(cursor.position(), 0);
+            format
+        };
+
+        assert_eq!(
+            sniff(include_bytes!("x86_64/elf/test_elf.bin")),
+            ImageFormat::Elf64
+        );
+        assert_eq!(
+            sniff(include_bytes!("x86_64/elf/test_elf32.bin")),
+            ImageFormat::Elf32
+        );
+        assert_eq!(sniff(b"\x7fELF\x03"), ImageFormat::Unknown);
+        assert_eq!(sniff(&[0x1f, 0x8b, 0x08, 0x00]), ImageFormat::Gzip);
+        assert_eq!(
+            sniff(include_bytes!("aarch64/pe/test_image.bin")),
+            ImageFormat::Pe
+        );
+
+        // A bzImage with an EFI stub starts like a PE image.
+        let mut bzimage = vec![0u8; 0x400];
+        bzimage[..2].copy_from_slice(b"MZ");
+        bzimage[0x202..0x206].copy_from_slice(b"HdrS");
+        assert_eq!(sniff(&bzimage), ImageFormat::BzImage);
+
+        assert_eq!(sniff(&[0x5a; 0x300]), ImageFormat::Unknown);
+        assert_eq!(sniff(&[]), ImageFormat::Unknown);
+    }
+
+    #[test]
+    fn test_himem_start() {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            assert_eq!(HIMEM_START, GuestAddress(0x10_0000));
+            assert_eq!(default_highmem_start_address(), Some(HIMEM_START));
+        }
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        assert_eq!(default_highmem_start_address(), None);
+
+        #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            let gm = create_guest_mem();
+            let image = include_bytes!("x86_64/elf/test_elf.bin");
+            let loader_result =
+                load_kernel_bytes::<elf::Elf, _>(&gm, Some(HIMEM_START), image, None).unwrap();
+            assert_eq!(loader_result.kernel_load, GuestAddress(0x10_0400));
+            assert_eq!(loader_result.kernel_end, 0x10_042e);
+
+            // The entry point of the image is in low memory.
+            assert_eq!(
+                Some(Error::Elf(elf::Error::InvalidEntryAddress)),
+                load_kernel_bytes::<elf::Elf, _>(&gm, None, image, default_highmem_start_address())
+                    .err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_into_io_error() {
+        let err = io::Error::from(Error::ReadInitrdImage);
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(
+            err.to_string(),
+            "Kernel Loader: failed to read initrd image into guest memory"
+        );
+
+        let err = io::Error::from(Error::CommandLineOverflow);
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert_eq!(
+            err.to_string(),
+            "Kernel Loader: command line overflowed guest memory"
+        );
+        assert_eq!(
+            err.into_inner()
+                .unwrap()
+                .downcast::<Error>()
+                .ok()
+                .map(|e| *e),
+            Some(Error::CommandLineOverflow)
+        );
+
+        #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            let image = include_bytes!("x86_64/elf/test_elf.bin");
+            let load = |image: &[u8]| -> io::Result<KernelLoaderResult> {
+                Ok(load_kernel_bytes::<elf::Elf, _>(
+                    &create_guest_mem(),
+                    None,
+                    image,
+                    None,
+                )?)
+            };
+            assert!(load(image).is_ok());
+
+            let err = load(&image[..0x20]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+            assert_eq!(
+                err.to_string(),
+                "Kernel Loader: failed to load ELF kernel image"
+            );
+
+            let mut bad_image = image.to_vec();
+            bad_image[0x1] = 0x33;
+            assert_eq!(load(&bad_image).unwrap_err().kind(), io::ErrorKind::Other);
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_apply_relocations() {
+        let gm = create_guest_mem();
+        let kernel_load = GuestAddress(0x10_0000);
+        let relocs = |entries: &[u32]| -> Vec<u8> {
+            entries
+                .iter()
+                .flat_map(|entry| entry.to_le_bytes())
+                .collect()
+        };
+        for (offset, word) in [(0x10, 0x1000_u32), (0x24, 0xffff_fff0), (0x100, 0x0)] {
+            gm.write_obj(word, kernel_load.unchecked_add(offset))
+                .unwrap();
+        }
+        let read = |offset| {
+            gm.read_obj::<u32>(kernel_load.unchecked_add(offset))
+                .unwrap()
+        };
+
+        // The entries before the terminator, read from the end, are not relocations.
+        let table = relocs(&[0x100, 0, 0x24, 0x10]);
+        apply_relocations(&gm, kernel_load, &table, 0x20).unwrap();
+        assert_eq!(read(0x10), 0x1020);
+        // Wraps around at 32 bits.
+        assert_eq!(read(0x24), 0x10);
+        assert_eq!(read(0x100), 0x0);
+
+        // Negative deltas move the kernel down.
+        apply_relocations(&gm, kernel_load, &table, -0x20).unwrap();
+        assert_eq!(read(0x10), 0x1000);
+        assert_eq!(read(0x24), 0xffff_fff0);
+
+        // Nothing is patched when an entry is invalid, here past the end of guest memory.
+        for table in [
+            relocs(&[0, 0x10, MEM_SIZE as u32 - 0x10_0000 - 3]),
+            relocs(&[0x10]),
+            vec![0, 0, 0, 0, 0x10],
+        ] {
+            assert_eq!(
+                Err(Error::InvalidRelocationEntry),
+                apply_relocations(&gm, kernel_load, &table, 0x20)
+            );
+            assert_eq!(read(0x10), 0x1000);
+        }
+        // A table with just the terminator is fine.
+        apply_relocations(&gm, kernel_load, &relocs(&[0]), 0x20).unwrap();
+        assert_eq!(read(0x10), 0x1000);
+    }
+
+    #[test]
+    fn test_checksum_loaded() {
+        let gm = create_guest_mem();
+        // Standard CRC-32 check value.
+        gm.write_slice(b"123456789", GuestAddress(0x1000)).unwrap();
+        assert_eq!(
+            checksum_loaded(&gm, &[(GuestAddress(0x1000), 9)]),
+            Ok(0xcbf4_3926)
+        );
+        // Regions are hashed as a single stream.
+        assert_eq!(
+            checksum_loaded(&gm, &[(GuestAddress(0x1000), 4), (GuestAddress(0x1004), 5)]),
+            Ok(0xcbf4_3926)
+        );
+        assert_eq!(checksum_loaded(&gm, &[]), Ok(0));
+
+        assert_eq!(
+            Err(Error::ReadGuestMemory),
+            checksum_loaded(&gm, &[(GuestAddress(MEM_SIZE - 4), 8)])
+        );
+    }
+
+    #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+    #[test]
+    fn test_checksum_loaded_elf() {
+        let gm = create_guest_mem();
+        let image = include_bytes!("x86_64/elf/test_elf.bin");
+        let kernel_addr = Some(GuestAddress(0x20_0000));
+        load_kernel_bytes::<elf::Elf, _>(&gm, kernel_addr, image, None).unwrap();
+        let regions: Vec<_> = elf::Elf::parse_segments(kernel_addr, &mut Cursor::new(&image))
+            .unwrap()
+            .iter()
+            .map(|segment| (segment.guest_addr, segment.mem_size))
+            .collect();
+
+        let crc = checksum_loaded(&gm, &regions).unwrap();
+        assert_eq!(checksum_loaded(&gm, &regions), Ok(crc));
+
+        // Any change to the loaded kernel shows up in the checksum.
+        gm.write_obj(0xffu8, regions[0].0).unwrap();
+        assert_ne!(checksum_loaded(&gm, &regions), Ok(crc));
+    }
+
+    #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+    #[test]
+    fn test_load_kernel_from_path() {
+        let gm = create_guest_mem();
+        let image = include_bytes!("x86_64/elf/test_elf.bin");
+        let path = std::env::temp_dir().join(format!("linux-loader-path-{}", std::process::id()));
+        std::fs::write(&path, image).unwrap();
+
+        let kernel_addr = Some(GuestAddress(0x20_0000));
+        let loader_result = load_kernel_from_path::<elf::Elf, _, _>(&gm, kernel_addr, &path, None);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            loader_result.unwrap(),
+            load_kernel_bytes::<elf::Elf, _>(&gm, kernel_addr, image, None).unwrap()
+        );
+
+        // The file is gone.
+        let err =
+            load_kernel_from_path::<elf::Elf, _, _>(&gm, kernel_addr, &path, None).unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::OpenKernelImage(e) if e.kind() == io::ErrorKind::NotFound
+        ));
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(
+            source.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::NotFound
+        );
+        assert_eq!(err, Error::OpenKernelImage(io::ErrorKind::Other.into()));
+    }
+
+    #[cfg(all(
+        unix,
+        feature = "elf",
+        any(target_arch = "x86", target_arch = "x86_64")
+    ))]
+    #[test]
+    fn test_load_kernel_pread() {
+        use std::io::Write;
+
+        let gm = create_guest_mem();
+        let image = include_bytes!("x86_64/elf/test_elf.bin");
+        let path = std::env::temp_dir().join(format!("linux-loader-pread-{}", std::process::id()));
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(image)
+            .unwrap();
+        let file = std::fs::File::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let kernel_addr = Some(GuestAddress(0x20_0000));
+        let loader_result =
+            load_kernel_pread::<elf::Elf, _, _>(&gm, kernel_addr, &file, image.len() as u64, None)
+                .unwrap();
+        assert_eq!(
+            loader_result,
+            load_kernel_bytes::<elf::Elf, _>(&gm, kernel_addr, image, None).unwrap()
+        );
+        assert_eq!(loader_result.kernel_end, 0x20_042e);
+
+        // `File` implements `PositionedRead` through `FileExt`.
+        let mut buf = [0u8; 4];
+        assert_eq!(PositionedRead::read_at(&file, &mut buf, 0).unwrap(), 4);
+        assert_eq!(&buf, b"\x7fELF");
+
+        // The image is cut at `image_size`, in the middle of the first segment.
+        assert!(matches!(
+            load_kernel_pread::<elf::Elf, _, _>(&gm, None, &file, 0xc0, None),
+            Err(Error::Elf(elf::Error::TruncatedSegment {
+                index: 0,
+                expected: 0x1d,
+                got: 0x10
+            }))
+        ));
+    }
+
+    #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+    #[test]
+    fn test_load_image() {
+        let gm = create_guest_mem();
+        let kernel = include_bytes!("x86_64/elf/test_elf.bin");
+        let initrd = vec![0xaa_u8; 0x100];
+        let cmdline = c"console=ttyS0";
+        let layout = LoadLayout {
+            kernel_start: Some(GuestAddress(0x20_0000)),
+            initrd_align: 0x1000,
+            cmdline_addr: GuestAddress(0x2_0000),
+            ..Default::default()
+        };
+
+        let loaded = load_image::<elf::Elf, _, _, _>(
+            &gm,
+            &mut Cursor::new(&kernel),
+            Some(&mut Cursor::new(&initrd)),
+            cmdline,
+            layout,
+        )
+        .unwrap();
+        assert_eq!(loaded.kernel.kernel_load, GuestAddress(0x20_0400));
+        assert_eq!(loaded.kernel.kernel_end, 0x20_042e);
+        // The initrd starts at the first aligned address after the kernel.
+        assert_eq!(loaded.initrd, Some((GuestAddress(0x20_1000), 0x100)));
+        assert_eq!(loaded.cmdline_addr, GuestAddress(0x2_0000));
+        assert_eq!(loaded.cmdline_size, 14);
+        assert_eq!(
+            loaded.boot_layout,
+            BootLayout {
+                entry: 0x20_0400,
+                kernel_start: Some(0x20_0000),
+                kernel_end: 0x20_042e,
+                initrd: Some((0x20_1000, 0x100)),
+                cmdline: (0x2_0000, 14),
+                boot_params: None,
+            }
+        );
+
+        let mut buf = vec![0u8; 0x100];
+        gm.read_slice(&mut buf, GuestAddress(0x20_1000)).unwrap();
+        assert_eq!(buf, initrd);
+        let mut buf = [0u8; 14];
+        gm.read_slice(&mut buf, GuestAddress(0x2_0000)).unwrap();
+        assert_eq!(&buf, cmdline.to_bytes_with_nul());
+
+        let loaded = load_image::<elf::Elf, _, Cursor<&[u8]>, _>(
+            &gm,
+            &mut Cursor::new(&kernel),
+            None,
+            cmdline,
+            layout,
+        )
+        .unwrap();
+        assert_eq!(loaded.initrd, None);
+        assert_eq!(loaded.boot_layout.initrd, None);
+
+        assert_eq!(
+            Some(Error::InvalidInitrdAlignment),
+            load_image::<elf::Elf, _, _, _>(
+                &gm,
+                &mut Cursor::new(&kernel),
+                Some(&mut Cursor::new(&initrd)),
+                cmdline,
+                LoadLayout {
+                    initrd_align: 0x1001,
+                    ..layout
+                },
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn test_loader_result_clone() {
+        // Cloned results compare equal, e.g. once stored in the state of a VMM.
+        fn clone_and_compare<T: Clone + Eq + fmt::Debug>(value: &T) -> T {
+            let clone = value.clone();
+            assert_eq!(&clone, value);
+            clone
+        }
+
+        let loader_result = KernelLoaderResult {
+            kernel_load: GuestAddress(0x20_0400),
+            kernel_end: 0x20_042e,
+            bytes_loaded: 0x2b,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            setup_header: Some(bootparam::setup_header {
+                version: 0x20f,
+                ..Default::default()
+            }),
+            #[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+            pvh_boot_cap: elf::PvhBootCapability::PvhEntryPresent(GuestAddress(0x100)),
+        };
+
+        let mut clone = clone_and_compare(&loader_result);
+        clone.kernel_end += 1;
+        assert_ne!(clone, loader_result);
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            let mut clone = clone_and_compare(&loader_result);
+            clone.setup_header = None;
+            assert_ne!(clone, loader_result);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_boot_layout_serde() {
+        let boot_layout = BootLayout {
+            entry: 0x20_0400,
+            kernel_start: Some(0x20_0000),
+            kernel_end: 0x20_042e,
+            initrd: Some((0x20_1000, 0x100)),
+            cmdline: (0x2_0000, 14),
+            boot_params: Some(0x7000),
+        };
+
+        let json = serde_json::to_string(&boot_layout).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BootLayout>(&json).unwrap(),
+            boot_layout
+        );
+        let boot_layout = BootLayout::default();
+        let json = serde_json::to_string(&boot_layout).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BootLayout>(&json).unwrap(),
+            boot_layout
+        );
     }
 }
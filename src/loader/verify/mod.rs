@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+
+//! Helpers for loading kernel images whose SHA-256 digest is known in advance.
+
+#![cfg(feature = "verify")]
+
+use std::fmt;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use sha2::{Digest, Sha256};
+use vm_memory::{GuestAddress, GuestMemory};
+
+use super::{KernelLoader, KernelLoaderResult, Result};
+
+// Size of the chunks in which the image is read and hashed.
+const CHUNK_SIZE: usize = 0x1_0000;
+
+#[derive(Debug, PartialEq, Eq)]
+/// Verified kernel loader errors.
+#[non_exhaustive]
+pub enum Error {
+    /// SHA-256 digest of the kernel image does not match the expected one.
+    DigestMismatch,
+    /// Unable to read kernel image.
+    ReadKernelImage,
+    /// Unable to seek to kernel image start.
+    SeekKernelStart,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let desc = match self {
+            Error::DigestMismatch => "Kernel image digest mismatch",
+            Error::ReadKernelImage => "Unable to read kernel image",
+            Error::SeekKernelStart => "Unable to seek to kernel image start",
+        };
+
+        write!(f, "Kernel Loader: {}", desc)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Loads a kernel image with the loader `L` after checking its SHA-256 digest.
+///
+/// The digest covers the whole file, read sequentially once from its start to its end, i.e. it
+/// is the value `sha256sum` gives for the image file. It does not depend on which parts of the
+/// file are copied to guest memory by `L`. The image is read into memory while being hashed and
+/// `L` then loads it from that buffer, so the image is read only once and nothing is written to
+/// guest memory if the digest does not match.
+///
+/// # Arguments
+///
+/// * `guest_mem`: [`GuestMemory`] to load the kernel in.
+/// * `kernel_offset`: Passed through to `L`.
+/// * `kernel_image` - Input kernel image.
+/// * `highmem_start_address`: Passed through to `L`.
+/// * `expected_sha256` - Expected SHA-256 digest of the whole image.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate vm_memory;
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// # use vm_memory::GuestAddress;
+/// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+/// # #[cfg(all(feature = "elf", target_arch = "x86_64"))]
+/// # fn load(
+/// #     gm: &GuestMemoryMmap,
+/// #     image: &[u8],
+/// #     expected_sha256: &[u8; 32],
+/// # ) -> Result<KernelLoaderResult> {
+/// verify::load_kernel_verified::<elf::Elf, _, _>(
+///     gm,
+///     None,
+///     &mut Cursor::new(image),
+///     None,
+///     expected_sha256,
+/// )
+/// # }
+/// ```
+///
+/// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+pub fn load_kernel_verified<L, F, M>(
+    guest_mem: &M,
+    kernel_offset: Option<GuestAddress>,
+    kernel_image: &mut F,
+    highmem_start_address: Option<GuestAddress>,
+    expected_sha256: &[u8; 32],
+) -> Result<KernelLoaderResult>
+where
+    L: KernelLoader,
+    F: Read + Seek,
+    M: GuestMemory,
+{
+    kernel_image
+        .seek(SeekFrom::Start(0))
+        .map_err(|_| Error::SeekKernelStart)?;
+
+    let mut hasher = Sha256::new();
+    let mut image = Vec::new();
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+    loop {
+        let len = match kernel_image.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => return Err(Error::ReadKernelImage.into()),
+        };
+        hasher.update(&chunk[..len]);
+        image.extend_from_slice(&chunk[..len]);
+    }
+
+    if hasher.finalize().as_slice() != expected_sha256 {
+        return Err(Error::DigestMismatch.into());
+    }
+
+    L::load(
+        guest_mem,
+        kernel_offset,
+        &mut Cursor::new(image),
+        highmem_start_address,
+    )
+}
+
+#[cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::{elf::Elf, Error as KernelLoaderError};
+    use vm_memory::{Bytes, GuestAddress};
+    type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+
+    const MEM_SIZE: u64 = 0x100_0000;
+
+    fn create_guest_mem() -> GuestMemoryMmap {
+        GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), (MEM_SIZE as usize))]).unwrap()
+    }
+
+    fn make_elf_bin() -> Vec<u8> {
+        include_bytes!("../x86_64/elf/test_elf.bin").to_vec()
+    }
+
+    // `sha256sum src/loader/x86_64/elf/test_elf.bin`
+    const TEST_ELF_SHA256: [u8; 32] = [
+        0x35, 0x3e, 0xee, 0x7f, 0xfc, 0x0d, 0xbf, 0x77, 0x93, 0x76, 0xd7, 0x70, 0x44, 0x13, 0xae,
+        0xa4, 0xf4, 0xe8, 0x2a, 0x31, 0x08, 0xd5, 0xb8, 0x73, 0xd5, 0xf1, 0xc6, 0xc9, 0x00, 0x17,
+        0xe9, 0x87,
+    ];
+
+    #[test]
+    fn test_load_verified() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let mut cursor = Cursor::new(&image);
+        // The digest doesn't depend on the current position of the reader.
+        cursor.seek(SeekFrom::Start(0x100)).unwrap();
+
+        let loader_result =
+            load_kernel_verified::<Elf, _, _>(&gm, None, &mut cursor, None, &TEST_ELF_SHA256)
+                .unwrap();
+        assert_eq!(
+            loader_result,
+            Elf::load(&gm, None, &mut Cursor::new(&image), None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_digest_mismatch() {
+        let gm = create_guest_mem();
+        let mut image = make_elf_bin();
+        // Flip a bit in the section headers, which are not loaded.
+        let last = image.len() - 1;
+        image[last] ^= 0x1;
+
+        assert_eq!(
+            Some(KernelLoaderError::Verify(Error::DigestMismatch)),
+            load_kernel_verified::<Elf, _, _>(
+                &gm,
+                Some(GuestAddress(0x20_0000)),
+                &mut Cursor::new(&image),
+                None,
+                &TEST_ELF_SHA256
+            )
+            .err()
+        );
+
+        // Nothing was loaded.
+        let mut buf = [0xffu8; 0x42e];
+        gm.read_slice(&mut buf, GuestAddress(0x20_0000)).unwrap();
+        assert!(buf.iter().all(|&b| b == 0));
+    }
+}
@@ -23,6 +23,7 @@ use super::super::{
 
 #[derive(Debug, PartialEq, Eq)]
 /// Bzimage kernel loader errors.
+#[non_exhaustive]
 pub enum Error {
     /// Invalid bzImage binary.
     InvalidBzImage,
@@ -40,6 +41,21 @@ pub enum Error {
     SeekBzImageCompressedKernel,
     /// Underflow occurred during an arithmetic operation.
     Underflow,
+    /// Kernel start address is not aligned to the `kernel_alignment` of a relocatable kernel.
+    UnalignedKernelStart,
+    /// Kernel version string is not null terminated ASCII.
+    InvalidKernelVersion,
+    /// Unable to read kernel version string.
+    ReadKernelVersion,
+    /// Unable to seek to kernel version string.
+    SeekKernelVersion,
+    /// Boot protocol version of the image is older than the required one.
+    UnsupportedBootProtocolVersion {
+        /// Boot protocol version of the image.
+        found: u16,
+        /// Minimum boot protocol version required.
+        required: u16,
+    },
 }
 
 impl fmt::Display for Error {
@@ -53,22 +69,148 @@ impl fmt::Display for Error {
             Error::SeekBzImageHeader => "Unable to seek bzImage header",
             Error::SeekBzImageCompressedKernel => "Unable to seek bzImage compressed kernel",
             Error::Underflow => "Underflow occurred during an arithmetic operation",
+            Error::UnalignedKernelStart => "Kernel start address is not aligned",
+            Error::InvalidKernelVersion => "Invalid kernel version string",
+            Error::ReadKernelVersion => "Unable to read kernel version string",
+            Error::SeekKernelVersion => "Unable to seek to kernel version string",
+            Error::UnsupportedBootProtocolVersion { .. } => "Unsupported boot protocol version",
         };
 
-        write!(f, "Kernel Loader: {}", desc)
+        write!(f, "Kernel Loader: {}", desc)?;
+        if let Error::UnsupportedBootProtocolVersion { found, required } = self {
+            write!(f, ": found {:#06x}, required {:#06x}", found, required)?;
+        }
+        Ok(())
     }
 }
 
 impl std::error::Error for Error {}
 
+/// Reads the setup header of a bzImage, without loading it.
+///
+/// This lets a VMM inspect e.g. `version`, `relocatable_kernel`, `kernel_alignment`,
+/// `pref_address` or `init_size` before choosing where to load the kernel. Only the `HdrS` magic
+/// number is checked, the header is returned as it is in the image.
+///
+/// # Arguments
+///
+/// * `kernel_image` - Input bzImage image.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// let kernel_image = include_bytes!("bzimage");
+/// let setup_header = bzimage::read_bzimage_header(&mut Cursor::new(&kernel_image)).unwrap();
+/// assert_eq!({ setup_header.header }, 0x5372_6448);
+/// ```
+pub fn read_bzimage_header<F>(kernel_image: &mut F) -> Result<bootparam::setup_header>
+where
+    F: Read + Seek,
+{
+    kernel_image
+        .seek(SeekFrom::Start(0x1F1))
+        .map_err(|_| Error::SeekBzImageHeader)?;
+
+    let mut boot_header = bootparam::setup_header::default();
+    boot_header
+        .as_bytes()
+        .read_from(0, kernel_image, mem::size_of::<bootparam::setup_header>())
+        .map_err(|_| Error::ReadBzImageHeader)?;
+
+    // If the `HdrS` magic number is not found at offset 0x202, the boot protocol version is
+    // "old", the image type is assumed as zImage, not bzImage.
+    if boot_header.header != 0x5372_6448 {
+        return Err(Error::InvalidBzImage.into());
+    }
+
+    Ok(boot_header)
+}
+
+/// Reads the kernel version string of a bzImage, without loading it.
+///
+/// The `kernel_version` field of the setup header points to a null terminated ASCII string in
+/// the setup sectors, e.g. `"6.1.0 (user@host) #1 SMP ..."`, which is handy for logging or to
+/// check the kernel before booting it. Returns `None` when the image doesn't have one, i.e. the
+/// field is 0 or the boot protocol is older than 2.00.
+///
+/// # Arguments
+///
+/// * `kernel_image` - Input bzImage image.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// let kernel_image = include_bytes!("bzimage");
+/// let version = bzimage::read_kernel_version(&mut Cursor::new(&kernel_image)).unwrap();
+/// assert!(version.is_some());
+/// ```
+pub fn read_kernel_version<F>(kernel_image: &mut F) -> Result<Option<String>>
+where
+    F: Read + Seek,
+{
+    // Longest version string accepted, the kernel's own is well below.
+    const MAX_KERNEL_VERSION_LEN: u64 = 0x200;
+
+    let boot_header = read_bzimage_header(kernel_image)?;
+    if boot_header.version < 0x0200 || boot_header.kernel_version == 0 {
+        return Ok(None);
+    }
+
+    // The offset is relative to the start of the setup header sector.
+    kernel_image
+        .seek(SeekFrom::Start(
+            0x200 + u64::from(boot_header.kernel_version),
+        ))
+        .map_err(|_| Error::SeekKernelVersion)?;
+    let mut version = Vec::new();
+    kernel_image
+        .take(MAX_KERNEL_VERSION_LEN + 1)
+        .read_to_end(&mut version)
+        .map_err(|_| Error::ReadKernelVersion)?;
+
+    let len = version
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or(Error::InvalidKernelVersion)?;
+    version.truncate(len);
+    if !version.is_ascii() {
+        return Err(Error::InvalidKernelVersion.into());
+    }
+    // Can't fail, the string is ASCII.
+    Ok(String::from_utf8(version).ok())
+}
+
 /// Big zImage (bzImage) kernel image support.
 pub struct BzImage;
 
+// Alignment required by a relocatable kernel (boot protocol 2.05+), if any.
+fn relocatable_alignment(boot_header: &bootparam::setup_header) -> Option<u64> {
+    if boot_header.version < 0x0205 || boot_header.relocatable_kernel == 0 {
+        return None;
+    }
+    Some(u64::from(boot_header.kernel_alignment)).filter(|align| align.is_power_of_two())
+}
+
 impl KernelLoader for BzImage {
     /// Loads a kernel from a bzImage to guest memory.
     ///
-    /// The kernel is loaded at `code32_start`, the default load address stored in the bzImage
-    /// setup header.
+    /// Without `kernel_offset`, the kernel is loaded at `pref_address` rounded up to
+    /// `kernel_alignment` for relocatable kernels (boot protocol 2.10+), and at `code32_start`,
+    /// the default load address stored in the bzImage setup header, otherwise. A `kernel_offset`
+    /// given for a relocatable kernel must be aligned to `kernel_alignment`, loading fails with
+    /// `UnalignedKernelStart` otherwise.
+    ///
+    /// The returned `kernel_end` is the end of the compressed kernel. The kernel decompresses
+    /// itself in place and needs `init_size` bytes from `kernel_load` while doing so, which is
+    /// usually well above the size of the image: the VMM must not place e.g. the initrd below
+    /// `kernel_load + init_size`. `init_size` is part of the returned `setup_header`, and
+    /// [`KernelLoaderResult::protected_mode_end`] gives the first address past both.
+    ///
+    /// [`KernelLoaderResult::protected_mode_end`]: ../struct.KernelLoaderResult.html#method.protected_mode_end
     ///
     /// # Arguments
     ///
@@ -107,32 +249,76 @@ impl KernelLoader for BzImage {
         kernel_image: &mut F,
         highmem_start_address: Option<GuestAddress>,
     ) -> Result<KernelLoaderResult>
+    where
+        F: Read + Seek,
+    {
+        Self::load_with_min_version(
+            guest_mem,
+            kernel_offset,
+            kernel_image,
+            highmem_start_address,
+            0,
+        )
+    }
+}
+
+impl BzImage {
+    /// Loads a kernel from a bzImage to guest memory, if it implements at least boot protocol
+    /// `min_version`.
+    ///
+    /// Features of the boot protocol depend on its version, e.g. relocatable kernels need 2.05
+    /// (`0x0205`) and `ext_ramdisk_image` in the boot parameters 2.12 (`0x020c`). A VMM relying on
+    /// them can reject older kernels upfront: loading fails with `UnsupportedBootProtocolVersion`
+    /// and nothing is written to guest memory when the `version` of the setup header is lower
+    /// than `min_version`. `load` accepts any version from 2.00 up.
+    ///
+    /// See [`load`](struct.BzImage.html#method.load) for the meaning of the other arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate vm_memory;
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::GuestAddress;
+    /// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+    /// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x100_0000)]).unwrap();
+    /// let kernel_image = include_bytes!("bzimage");
+    /// bzimage::BzImage::load_with_min_version(
+    ///     &gm,
+    ///     Some(GuestAddress(0x20_0000)),
+    ///     &mut Cursor::new(&kernel_image),
+    ///     None,
+    ///     0x0205,
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn load_with_min_version<F, M: GuestMemory>(
+        guest_mem: &M,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+        highmem_start_address: Option<GuestAddress>,
+        min_version: u16,
+    ) -> Result<KernelLoaderResult>
     where
         F: Read + Seek,
     {
         let mut kernel_size = kernel_image
             .seek(SeekFrom::End(0))
             .map_err(|_| Error::SeekBzImageEnd)? as usize;
-        kernel_image
-            .seek(SeekFrom::Start(0x1F1))
-            .map_err(|_| Error::SeekBzImageHeader)?;
-
-        let mut boot_header = bootparam::setup_header::default();
-        boot_header
-            .as_bytes()
-            .read_from(0, kernel_image, mem::size_of::<bootparam::setup_header>())
-            .map_err(|_| Error::ReadBzImageHeader)?;
-
-        // If the `HdrS` magic number is not found at offset 0x202, the boot protocol version is
-        // "old", the image type is assumed as zImage, not bzImage.
-        if boot_header.header != 0x5372_6448 {
-            return Err(Error::InvalidBzImage.into());
-        }
+        let mut boot_header = read_bzimage_header(kernel_image)?;
 
         // Follow the section related to loading the rest of the kernel in the linux boot protocol.
         if (boot_header.version < 0x0200) || ((boot_header.loadflags & 0x1) == 0x0) {
             return Err(Error::InvalidBzImage.into());
         }
+        if boot_header.version < min_version {
+            return Err(Error::UnsupportedBootProtocolVersion {
+                found: boot_header.version,
+                required: min_version,
+            }
+            .into());
+        }
 
         let mut setup_size = boot_header.setup_sects as usize;
         if setup_size == 0 {
@@ -154,12 +340,23 @@ impl KernelLoader for BzImage {
             return Err(KernelLoaderError::InvalidKernelStartAddress);
         }
 
-        let mem_offset = match kernel_offset {
-            Some(start) => start,
-            None => GuestAddress(u64::from(boot_header.code32_start)),
+        let alignment = relocatable_alignment(&boot_header);
+        let mem_offset = match (kernel_offset, alignment) {
+            (Some(start), Some(align)) if !start.raw_value().is_multiple_of(align) => {
+                return Err(Error::UnalignedKernelStart.into());
+            }
+            (Some(start), _) => start,
+            (None, Some(align)) if boot_header.version >= 0x020a => GuestAddress(
+                boot_header
+                    .pref_address
+                    .checked_next_multiple_of(align)
+                    .ok_or(Error::Overflow)?,
+            ),
+            (None, _) => GuestAddress(u64::from(boot_header.code32_start)),
         };
 
-        boot_header.code32_start = mem_offset.raw_value() as u32;
+        boot_header.code32_start =
+            u32::try_from(mem_offset.raw_value()).map_err(|_| Error::Overflow)?;
 
         let mut loader_result = KernelLoaderResult {
             setup_header: Some(boot_header),
@@ -175,6 +372,7 @@ impl KernelLoader for BzImage {
             .read_exact_from(mem_offset, kernel_image, kernel_size)
             .map_err(|_| Error::ReadBzImageCompressedKernel)?;
 
+        loader_result.bytes_loaded = kernel_size as GuestUsize;
         loader_result.kernel_end = mem_offset
             .raw_value()
             .checked_add(kernel_size as GuestUsize)
@@ -187,6 +385,7 @@ impl KernelLoader for BzImage {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::loader::load_kernel_bytes;
 
     use std::fs::File;
     use std::io::Cursor;
@@ -194,7 +393,7 @@ mod tests {
     use vm_memory::{Address, GuestAddress};
     type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
 
-    const MEM_SIZE: u64 = 0x100_0000;
+    const MEM_SIZE: u64 = 0x200_0000;
 
     fn create_guest_mem() -> GuestMemoryMmap {
         GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), (MEM_SIZE as usize))]).unwrap()
@@ -230,10 +429,10 @@ mod tests {
         let mut highmem_start_address = GuestAddress(0x0);
 
         // load bzImage with good kernel_offset and himem_start setting
-        let mut loader_result = BzImage::load(
+        let mut loader_result = load_kernel_bytes::<BzImage, _>(
             &gm,
             Some(kernel_offset),
-            &mut Cursor::new(&image),
+            &image,
             Some(highmem_start_address),
         )
         .unwrap();
@@ -256,21 +455,21 @@ mod tests {
         );
         assert_eq!(loader_result.setup_header.unwrap().loadflags, 1);
         assert_eq!(loader_result.kernel_end, 0x60D320);
+        assert_eq!(loader_result.bytes_loaded, 0x40D320);
 
         // load bzImage without kernel_offset
-        loader_result = BzImage::load(
-            &gm,
-            None,
-            &mut Cursor::new(&image),
-            Some(highmem_start_address),
-        )
-        .unwrap();
+        loader_result =
+            load_kernel_bytes::<BzImage, _>(&gm, None, &image, Some(highmem_start_address))
+                .unwrap();
         let setup_header = loader_result.setup_header.unwrap();
+        // The kernel is relocatable, it is loaded at its preferred address.
+        let pref_load = { setup_header.pref_address }
+            .next_multiple_of(u64::from(setup_header.kernel_alignment));
 
-        assert_eq!(loader_result.kernel_load.raw_value(), 0x100000);
+        assert_eq!(loader_result.kernel_load.raw_value(), pref_load);
 
         // load bzImage without himem_start
-        loader_result = BzImage::load(&gm, None, &mut Cursor::new(&image), None).unwrap();
+        loader_result = load_kernel_bytes::<BzImage, _>(&gm, None, &image, None).unwrap();
         // Reading the value from an unaligned address is not considered safe.
         assert_eq!(
             0x53726448,
@@ -279,7 +478,7 @@ mod tests {
             // but this is not an issue since this is a test.
             unsafe { std::ptr::addr_of!(setup_header.header).read_unaligned() }
         );
-        assert_eq!(loader_result.kernel_load.raw_value(), 0x100000);
+        assert_eq!(loader_result.kernel_load.raw_value(), pref_load);
 
         // load bzImage with a bad himem setting
         kernel_offset = GuestAddress(0x1000);
@@ -287,16 +486,211 @@ mod tests {
 
         assert_eq!(
             Some(KernelLoaderError::InvalidKernelStartAddress),
-            BzImage::load(
+            load_kernel_bytes::<BzImage, _>(
                 &gm,
                 Some(kernel_offset),
-                &mut Cursor::new(&image),
-                Some(highmem_start_address),
+                &image,
+                Some(highmem_start_address)
             )
             .err()
         );
     }
 
+    #[test]
+    fn test_read_bzimage_header() {
+        // Setup sectors followed by a few bytes of kernel, with only the fields of the setup
+        // header needed here filled in.
+        let mut image = vec![0u8; 0x1400];
+        image[0x202..0x206].copy_from_slice(b"HdrS");
+        image[0x206..0x208].copy_from_slice(&0x20du16.to_le_bytes());
+        image[0x230..0x234].copy_from_slice(&0x20_0000u32.to_le_bytes());
+
+        let setup_header = read_bzimage_header(&mut Cursor::new(&image)).unwrap();
+        assert_eq!({ setup_header.version }, 0x20d);
+        assert_eq!({ setup_header.kernel_alignment }, 0x20_0000);
+
+        // Wrong magic number.
+        image[0x202] = b'h';
+        assert_eq!(
+            Err(KernelLoaderError::Bzimage(Error::InvalidBzImage)),
+            read_bzimage_header(&mut Cursor::new(&image))
+        );
+        // Too short to hold the magic number.
+        image[0x202] = b'H';
+        assert_eq!(
+            Err(KernelLoaderError::Bzimage(Error::InvalidBzImage)),
+            read_bzimage_header(&mut Cursor::new(&image[..0x204]))
+        );
+    }
+
+    // Builds a relocatable bzImage with 4 setup sectors followed by `0xa00` bytes of kernel.
+    fn make_relocatable_bzimage(pref_address: u64) -> Vec<u8> {
+        let mut image = vec![0u8; 0x1400];
+        image[0x202..0x206].copy_from_slice(b"HdrS");
+        image[0x206..0x208].copy_from_slice(&0x20du16.to_le_bytes());
+        image[0x211] = 0x1;
+        image[0x214..0x218].copy_from_slice(&0x10_0000u32.to_le_bytes());
+        image[0x230..0x234].copy_from_slice(&0x20_0000u32.to_le_bytes());
+        image[0x234] = 1;
+        image[0x258..0x260].copy_from_slice(&pref_address.to_le_bytes());
+        image[0xa00..].fill(0xaa);
+        image
+    }
+
+    #[test]
+    fn test_init_size() {
+        let gm = create_guest_mem();
+        let mut image = make_relocatable_bzimage(0x40_0000);
+        image[0x260..0x264].copy_from_slice(&0x80_0000u32.to_le_bytes());
+
+        let loader_result = load_kernel_bytes::<BzImage, _>(&gm, None, &image, None).unwrap();
+        let setup_header = loader_result.setup_header.unwrap();
+        assert_eq!({ setup_header.init_size }, 0x80_0000);
+        // The kernel needs more memory than the size of the image.
+        assert_eq!(loader_result.kernel_end, 0x40_0a00);
+        assert!(
+            loader_result.kernel_load.raw_value() + u64::from(setup_header.init_size)
+                > loader_result.kernel_end
+        );
+        assert_eq!(
+            {
+                read_bzimage_header(&mut Cursor::new(&image))
+                    .unwrap()
+                    .init_size
+            },
+            0x80_0000
+        );
+    }
+
+    #[test]
+    fn test_protected_mode_end() {
+        let gm = create_guest_mem();
+        let mut image = make_relocatable_bzimage(0x40_0000);
+        // The kernel needs more than the `0xa00` bytes it takes in the image.
+        image[0x260..0x264].copy_from_slice(&0x80_0000u32.to_le_bytes());
+        let loader_result = load_kernel_bytes::<BzImage, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x40_0a00);
+        assert_eq!(loader_result.protected_mode_end(), Some(0xc0_0000));
+
+        // A (bogus) `init_size` below the size in the image doesn't shrink it.
+        image[0x260..0x264].copy_from_slice(&0x100u32.to_le_bytes());
+        let loader_result = load_kernel_bytes::<BzImage, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.protected_mode_end(), Some(0x40_0a00));
+
+        assert_eq!(KernelLoaderResult::default().protected_mode_end(), None);
+    }
+
+    #[test]
+    fn test_load_pref_address() {
+        let gm = create_guest_mem();
+        let image = make_relocatable_bzimage(0x2f_0000);
+
+        let loader_result = load_kernel_bytes::<BzImage, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_load, GuestAddress(0x40_0000));
+        assert_eq!(loader_result.kernel_end, 0x40_0a00);
+        assert_eq!(
+            { loader_result.setup_header.unwrap().code32_start },
+            0x40_0000
+        );
+        assert_eq!(gm.read_obj::<u8>(GuestAddress(0x40_0000)).unwrap(), 0xaa);
+
+        // An explicit kernel start is used as long as it is aligned.
+        let loader_result =
+            load_kernel_bytes::<BzImage, _>(&gm, Some(GuestAddress(0x60_0000)), &image, None)
+                .unwrap();
+        assert_eq!(loader_result.kernel_load, GuestAddress(0x60_0000));
+        assert_eq!(
+            Some(KernelLoaderError::Bzimage(Error::UnalignedKernelStart)),
+            load_kernel_bytes::<BzImage, _>(&gm, Some(GuestAddress(0x30_0000)), &image, None).err()
+        );
+
+        // Non relocatable kernels are loaded at `code32_start` by default, or at any explicit
+        // `kernel_offset`.
+        let mut image = image;
+        image[0x234] = 0;
+        let loader_result = load_kernel_bytes::<BzImage, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_load, GuestAddress(0x10_0000));
+        let loader_result =
+            load_kernel_bytes::<BzImage, _>(&gm, Some(GuestAddress(0x30_0000)), &image, None)
+                .unwrap();
+        assert_eq!(loader_result.kernel_load, GuestAddress(0x30_0000));
+    }
+
+    #[test]
+    fn test_min_version() {
+        let gm = create_guest_mem();
+        let mut image = make_relocatable_bzimage(0x40_0000);
+        let kernel_addr = Some(GuestAddress(0x40_0000));
+
+        BzImage::load_with_min_version(&gm, kernel_addr, &mut Cursor::new(&image), None, 0x020d)
+            .unwrap();
+
+        // A boot protocol 2.04 kernel, from before relocatable kernels.
+        image[0x206..0x208].copy_from_slice(&0x204u16.to_le_bytes());
+        image[0xa00..].fill(0xbb);
+        let err = BzImage::load_with_min_version(
+            &gm,
+            kernel_addr,
+            &mut Cursor::new(&image),
+            None,
+            0x0205,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            KernelLoaderError::Bzimage(Error::UnsupportedBootProtocolVersion {
+                found: 0x0204,
+                required: 0x0205
+            })
+        );
+        assert_eq!(
+            Error::UnsupportedBootProtocolVersion {
+                found: 0x0204,
+                required: 0x0205
+            }
+            .to_string(),
+            "Kernel Loader: Unsupported boot protocol version: found 0x0204, required 0x0205"
+        );
+        // Nothing was loaded.
+        assert_eq!(gm.read_obj::<u8>(GuestAddress(0x40_0000)).unwrap(), 0xaa);
+
+        // `load` still accepts it.
+        load_kernel_bytes::<BzImage, _>(&gm, kernel_addr, &image, None).unwrap();
+        assert_eq!(gm.read_obj::<u8>(GuestAddress(0x40_0000)).unwrap(), 0xbb);
+    }
+
+    #[test]
+    fn test_read_kernel_version() {
+        let mut image = make_relocatable_bzimage(0x10_0000);
+        assert_eq!(read_kernel_version(&mut Cursor::new(&image)), Ok(None));
+
+        let version = b"6.1.0-test (test@localhost) #1 SMP\0";
+        image[0x20e..0x210].copy_from_slice(&0x300u16.to_le_bytes());
+        image[0x500..0x500 + version.len()].copy_from_slice(version);
+        assert_eq!(
+            read_kernel_version(&mut Cursor::new(&image)),
+            Ok(Some("6.1.0-test (test@localhost) #1 SMP".to_string()))
+        );
+
+        // Not ASCII.
+        image[0x500] = 0xc3;
+        assert_eq!(
+            read_kernel_version(&mut Cursor::new(&image)),
+            Err(KernelLoaderError::Bzimage(Error::InvalidKernelVersion))
+        );
+        // Not null terminated before the end of the image.
+        let end = image.len() - 4;
+        image[0x20e..0x210].copy_from_slice(&((end - 0x200) as u16).to_le_bytes());
+        assert_eq!(
+            read_kernel_version(&mut Cursor::new(&image)),
+            Err(KernelLoaderError::Bzimage(Error::InvalidKernelVersion))
+        );
+
+        // The version string field only exists since boot protocol 2.00.
+        image[0x206..0x208].copy_from_slice(&0x1ffu16.to_le_bytes());
+        assert_eq!(read_kernel_version(&mut Cursor::new(&image)), Ok(None));
+    }
+
     #[test]
     fn test_invalid_bzimage_underflow() {
         use super::super::super::Error as LoaderError;
@@ -12,17 +12,49 @@
 
 #![cfg(all(feature = "elf", any(target_arch = "x86", target_arch = "x86_64")))]
 
+use std::ffi::CStr;
 use std::fmt;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
 use std::mem;
 use std::result;
 
-use vm_memory::{Address, ByteValued, Bytes, GuestAddress, GuestMemory, GuestUsize};
+use vm_memory::{
+    Address, ByteValued, Bytes, GuestAddress, GuestMemory, GuestMemoryError, GuestMemoryRegion,
+    GuestUsize,
+};
 
 use crate::loader::{Error as KernelLoaderError, KernelLoader, KernelLoaderResult, Result};
 use crate::loader_gen::elf;
 pub use crate::loader_gen::start_info;
 
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf32_Dyn {}
+
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf32_Ehdr {}
+
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf32_Phdr {}
+
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf32_Rel {}
+
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf32_Shdr {}
+
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf32_Sym {}
+
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf64_Dyn {}
+
 // SAFETY: The layout of the structure is fixed and can be initialized by
 // reading its content from byte array.
 unsafe impl ByteValued for elf::Elf64_Ehdr {}
@@ -35,43 +67,378 @@ unsafe impl ByteValued for elf::Elf64_Nhdr {}
 // reading its content from byte array.
 unsafe impl ByteValued for elf::Elf64_Phdr {}
 
-#[derive(Debug, PartialEq, Eq)]
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf64_Rela {}
+
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf64_Shdr {}
+
+// SAFETY: The layout of the structure is fixed and can be initialized by
+// reading its content from byte array.
+unsafe impl ByteValued for elf::Elf64_Sym {}
+
+// `e_version` of all the images, no other version has been defined.
+const EV_CURRENT: u32 = 1;
+
+// `e_machine` of the images the loader accepts by default, 64-bit and 32-bit x86 kernels.
+const NATIVE_MACHINES: [u32; 2] = [elf::EM_X86_64, elf::EM_386];
+
+// Fields of the ELF structures, which are stored in the byte order given by `EI_DATA`.
+trait ElfField: Copy {
+    #[cfg(not(feature = "safe-parse"))]
+    fn swap(self) -> Self;
+    #[cfg(feature = "safe-parse")]
+    fn from_bytes(bytes: &[u8], big_endian: bool) -> Self;
+}
+
+macro_rules! impl_elf_field {
+    ($($type:ty),+) => {
+        $(
+            impl ElfField for $type {
+                #[cfg(not(feature = "safe-parse"))]
+                fn swap(self) -> Self {
+                    self.swap_bytes()
+                }
+
+                #[cfg(feature = "safe-parse")]
+                fn from_bytes(bytes: &[u8], big_endian: bool) -> Self {
+                    // The caller passes exactly `size_of::<$type>()` bytes.
+                    let bytes = bytes.try_into().unwrap();
+                    if big_endian {
+                        <$type>::from_be_bytes(bytes)
+                    } else {
+                        <$type>::from_le_bytes(bytes)
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_elf_field!(u8, u16, u32, u64, i32, i64);
+
+// `e_ident` is a byte array, whatever the byte order of the image.
+impl ElfField for [u8; 16] {
+    #[cfg(not(feature = "safe-parse"))]
+    fn swap(self) -> Self {
+        self
+    }
+
+    #[cfg(feature = "safe-parse")]
+    fn from_bytes(bytes: &[u8], _big_endian: bool) -> Self {
+        bytes.try_into().unwrap()
+    }
+}
+
+// ELF structures read from the image.
+trait ElfStruct: ByteValued {
+    // Converts the fields from the byte order of the image to the native one.
+    #[cfg(not(feature = "safe-parse"))]
+    fn swap_bytes(&mut self);
+    // Parses the structure field by field from `bytes`, without going through `ByteValued`.
+    #[cfg(feature = "safe-parse")]
+    fn parse(bytes: &[u8], big_endian: bool) -> Self;
+}
+
+// Takes all the fields of the structure, in order. The ELF structures have no padding, so each
+// field starts right after the previous one.
+macro_rules! impl_elf_struct {
+    ($type:ty, $($field:ident),+) => {
+        impl ElfStruct for $type {
+            #[cfg(not(feature = "safe-parse"))]
+            fn swap_bytes(&mut self) {
+                $(self.$field = self.$field.swap();)+
+            }
+
+            #[cfg(feature = "safe-parse")]
+            fn parse(bytes: &[u8], big_endian: bool) -> Self {
+                let mut elf_struct = Self::default();
+                let mut offset = 0;
+                $(
+                    let size = mem::size_of_val(&elf_struct.$field);
+                    elf_struct.$field =
+                        ElfField::from_bytes(&bytes[offset..offset + size], big_endian);
+                    offset += size;
+                )+
+                debug_assert_eq!(offset, mem::size_of::<Self>());
+                elf_struct
+            }
+        }
+    };
+}
+
+impl_elf_struct!(
+    elf::Elf32_Ehdr,
+    e_ident,
+    e_type,
+    e_machine,
+    e_version,
+    e_entry,
+    e_phoff,
+    e_shoff,
+    e_flags,
+    e_ehsize,
+    e_phentsize,
+    e_phnum,
+    e_shentsize,
+    e_shnum,
+    e_shstrndx
+);
+impl_elf_struct!(
+    elf::Elf64_Ehdr,
+    e_ident,
+    e_type,
+    e_machine,
+    e_version,
+    e_entry,
+    e_phoff,
+    e_shoff,
+    e_flags,
+    e_ehsize,
+    e_phentsize,
+    e_phnum,
+    e_shentsize,
+    e_shnum,
+    e_shstrndx
+);
+impl_elf_struct!(
+    elf::Elf32_Phdr,
+    p_type,
+    p_offset,
+    p_vaddr,
+    p_paddr,
+    p_filesz,
+    p_memsz,
+    p_flags,
+    p_align
+);
+impl_elf_struct!(
+    elf::Elf64_Phdr,
+    p_type,
+    p_flags,
+    p_offset,
+    p_vaddr,
+    p_paddr,
+    p_filesz,
+    p_memsz,
+    p_align
+);
+impl_elf_struct!(
+    elf::Elf32_Shdr,
+    sh_name,
+    sh_type,
+    sh_flags,
+    sh_addr,
+    sh_offset,
+    sh_size,
+    sh_link,
+    sh_info,
+    sh_addralign,
+    sh_entsize
+);
+impl_elf_struct!(
+    elf::Elf64_Shdr,
+    sh_name,
+    sh_type,
+    sh_flags,
+    sh_addr,
+    sh_offset,
+    sh_size,
+    sh_link,
+    sh_info,
+    sh_addralign,
+    sh_entsize
+);
+impl_elf_struct!(elf::Elf64_Nhdr, n_namesz, n_descsz, n_type);
+impl_elf_struct!(elf::Elf32_Dyn, d_tag, d_val);
+impl_elf_struct!(elf::Elf64_Dyn, d_tag, d_val);
+impl_elf_struct!(elf::Elf32_Rel, r_offset, r_info);
+impl_elf_struct!(elf::Elf64_Rela, r_offset, r_info, r_addend);
+impl_elf_struct!(
+    elf::Elf32_Sym,
+    st_name,
+    st_value,
+    st_size,
+    st_info,
+    st_other,
+    st_shndx
+);
+impl_elf_struct!(
+    elf::Elf64_Sym,
+    st_name,
+    st_info,
+    st_other,
+    st_shndx,
+    st_value,
+    st_size
+);
+
+// Reads an ELF structure from the image, in the byte order given by `e_ident`.
+fn read_elf_struct<T, F>(kernel_image: &mut F, e_ident: &[u8]) -> io::Result<T>
+where
+    T: ElfStruct,
+    F: Read,
+{
+    #[cfg(not(feature = "safe-parse"))]
+    {
+        let mut elf_struct = T::default();
+        kernel_image.read_exact(elf_struct.as_mut_slice())?;
+        if Elf::is_foreign_endian(e_ident) {
+            elf_struct.swap_bytes();
+        }
+        Ok(elf_struct)
+    }
+    #[cfg(feature = "safe-parse")]
+    {
+        // Large enough for any of the ELF structures.
+        let mut buf = [0u8; 64];
+        let bytes = &mut buf[..mem::size_of::<T>()];
+        kernel_image.read_exact(bytes)?;
+        Ok(T::parse(
+            bytes,
+            e_ident[elf::EI_DATA as usize] == elf::ELFDATA2MSB as u8,
+        ))
+    }
+}
+
+#[derive(Debug)]
 /// Elf kernel loader errors.
+#[non_exhaustive]
 pub enum Error {
     /// Invalid alignment.
     Align,
     /// Loaded big endian binary on a little endian platform.
     BigEndianElfOnLittle,
+    /// Invalid ELF class.
+    InvalidElfClass,
     /// Invalid ELF magic number.
     InvalidElfMagicNumber,
     /// Invalid program header size.
     InvalidProgramHeaderSize,
     /// Invalid program header offset.
     InvalidProgramHeaderOffset,
-    /// Invalid program header address.
+    /// Program header table extends past the end of the image.
+    InvalidProgramHeaderCount,
+    /// Program header table starts past the end of the image, e.g. the image is truncated.
+    ProgramHeaderPastEof,
+    /// Invalid section header size.
+    InvalidSectionHeaderSize,
+    /// Section header table extends past the end of the image.
+    InvalidSectionHeaderCount,
+    /// Invalid program header address, e.g. the segment doesn't fit in guest memory.
     InvalidProgramHeaderAddress,
+    /// `PT_LOAD` segments are not sorted by address.
+    UnsortedProgramHeaders,
+    /// Segment load address is not aligned to `p_align`.
+    InvalidSegmentAlignment,
     /// Invalid entry address.
     InvalidEntryAddress,
+    /// A segment ends above the highest allowed kernel end address.
+    InvalidKernelEndAddress,
+    /// The entry point is not within any loaded segment.
+    EntryNotInLoadedSegment,
     /// Overflow occurred during an arithmetic operation.
     Overflow,
+    /// ELF type is neither `ET_EXEC` nor `ET_DYN`.
+    UnsupportedElfType,
+    /// ELF version is not `EV_CURRENT`.
+    UnsupportedElfVersion,
+    /// The image requests a dynamic linker (`PT_INTERP`).
+    DynamicLinkingUnsupported,
+    /// ELF header size does not match the class of the image.
+    InvalidElfHeaderSize,
+    /// The image is for another architecture (`e_machine`).
+    UnsupportedArchitecture,
     /// Unable to read ELF header.
-    ReadElfHeader,
+    ReadElfHeader(io::Error),
     /// Unable to read kernel image.
-    ReadKernelImage,
+    ReadKernelImage {
+        /// Index of the program header of the segment.
+        index: usize,
+        /// Underlying guest memory error.
+        source: GuestMemoryError,
+    },
     /// Unable to read program header.
-    ReadProgramHeader,
+    ReadProgramHeader(io::Error),
+    /// Unable to read section header.
+    ReadSectionHeader(io::Error),
     /// Unable to seek to kernel start.
-    SeekKernelStart,
+    SeekKernelStart {
+        /// Index of the program header of the segment.
+        index: usize,
+        /// Underlying I/O error.
+        source: io::Error,
+    },
     /// Unable to seek to ELF start.
-    SeekElfStart,
+    SeekElfStart(io::Error),
+    /// Unable to seek to ELF end.
+    SeekElfEnd(io::Error),
     /// Unable to seek to program header.
-    SeekProgramHeader,
+    SeekProgramHeader(io::Error),
+    /// Unable to seek to section header.
+    SeekSectionHeader(io::Error),
     /// Unable to seek to note header.
-    SeekNoteHeader,
+    SeekNoteHeader(io::Error),
     /// Unable to read note header.
-    ReadNoteHeader,
+    ReadNoteHeader(io::Error),
     /// Invalid PVH note.
     InvalidPvhNote,
+    /// Unable to zero out the BSS region of a segment.
+    ZeroBss {
+        /// Index of the program header of the segment.
+        index: usize,
+        /// Underlying guest memory error.
+        source: GuestMemoryError,
+    },
+    /// The image ends within the data of a segment.
+    TruncatedSegment {
+        /// Index of the program header of the segment.
+        index: usize,
+        /// Size of the segment data in the image (`p_filesz`).
+        expected: u64,
+        /// Number of bytes of the segment data actually in the image.
+        got: u64,
+    },
+    /// Segment data extends past the end of the image.
+    SegmentPastEof {
+        /// Index of the program header of the segment.
+        index: usize,
+    },
+    /// Segment is not entirely within the requested guest memory region.
+    SegmentOutsideRegion {
+        /// Index of the program header of the segment.
+        index: usize,
+    },
+    /// Guest memory ranges of two segments overlap.
+    OverlappingSegments,
+    /// Segment granularity is not a power of two.
+    InvalidSegmentGranularity,
+    /// Unable to zero out the guest memory span of the kernel.
+    ZeroKernelSpan(GuestMemoryError),
+    /// The image has no non-empty `PT_LOAD` segment.
+    NoLoadableSegments,
+    /// Unable to seek to dynamic section.
+    SeekDynamicSection(io::Error),
+    /// Unable to read dynamic section.
+    ReadDynamicSection(io::Error),
+    /// Relocation table described by the dynamic section is not part of the image.
+    InvalidRelocationTable,
+    /// Unable to seek to relocation table.
+    SeekRelocations(io::Error),
+    /// Unable to read relocation table.
+    ReadRelocations(io::Error),
+    /// Unable to apply a relocation to guest memory.
+    ApplyRelocation(GuestMemoryError),
+    /// Relocation table format or relocation type not supported.
+    UnsupportedRelocation,
+    /// Invalid symbol table.
+    InvalidSymbolTable,
+    /// Unable to seek to symbol table.
+    SeekSymbolTable(io::Error),
+    /// Unable to read symbol table.
+    ReadSymbolTable(io::Error),
 }
 
 impl fmt::Display for Error {
@@ -81,30 +448,163 @@ impl fmt::Display for Error {
             Error::BigEndianElfOnLittle => {
                 "Trying to load big-endian binary on little-endian machine"
             }
+            Error::InvalidElfClass => "Invalid Elf class",
             Error::InvalidElfMagicNumber => "Invalid Elf magic number",
             Error::InvalidProgramHeaderSize => "Invalid program header size",
             Error::InvalidProgramHeaderOffset => "Invalid program header offset",
+            Error::InvalidProgramHeaderCount => "Invalid program header count",
+            Error::ProgramHeaderPastEof => "Program header table starts past the end of the image",
+            Error::InvalidSectionHeaderSize => "Invalid section header size",
+            Error::InvalidSectionHeaderCount => "Invalid section header count",
             Error::InvalidProgramHeaderAddress => "Invalid Program Header Address",
+            Error::UnsortedProgramHeaders => "Program headers are not sorted by address",
+            Error::InvalidSegmentAlignment => "Invalid segment alignment",
             Error::InvalidEntryAddress => "Invalid entry address",
+            Error::InvalidKernelEndAddress => "Invalid kernel end address",
+            Error::EntryNotInLoadedSegment => "Entry point is not in a loaded segment",
             Error::Overflow => "Overflow occurred during an arithmetic operation",
-            Error::ReadElfHeader => "Unable to read elf header",
-            Error::ReadKernelImage => "Unable to read kernel image",
-            Error::ReadProgramHeader => "Unable to read program header",
-            Error::SeekKernelStart => "Unable to seek to kernel start",
-            Error::SeekElfStart => "Unable to seek to elf start",
-            Error::SeekProgramHeader => "Unable to seek to program header",
-            Error::SeekNoteHeader => "Unable to seek to note header",
-            Error::ReadNoteHeader => "Unable to read note header",
+            Error::UnsupportedElfType => "Unsupported Elf type",
+            Error::UnsupportedElfVersion => "Unsupported Elf version",
+            Error::UnsupportedArchitecture => "Unsupported Elf architecture",
+            Error::DynamicLinkingUnsupported => "Dynamically linked images are not supported",
+            Error::InvalidElfHeaderSize => "Invalid Elf header size",
+            Error::ReadElfHeader(_) => "Unable to read elf header",
+            Error::ReadKernelImage { .. } => "Unable to read kernel image",
+            Error::ReadProgramHeader(_) => "Unable to read program header",
+            Error::ReadSectionHeader(_) => "Unable to read section header",
+            Error::SeekKernelStart { .. } => "Unable to seek to kernel start",
+            Error::SeekElfStart(_) => "Unable to seek to elf start",
+            Error::SeekElfEnd(_) => "Unable to seek to elf end",
+            Error::SeekProgramHeader(_) => "Unable to seek to program header",
+            Error::SeekSectionHeader(_) => "Unable to seek to section header",
+            Error::SeekNoteHeader(_) => "Unable to seek to note header",
+            Error::ReadNoteHeader(_) => "Unable to read note header",
             Error::InvalidPvhNote => "Invalid PVH note header",
+            Error::ZeroBss { .. } => "Unable to zero out BSS",
+            Error::TruncatedSegment { .. } => "Kernel image ends within a segment",
+            Error::SegmentPastEof { .. } => "Segment data extends past the end of the image",
+            Error::SegmentOutsideRegion { .. } => "Segment is outside of the guest memory region",
+            Error::OverlappingSegments => "Overlapping segments",
+            Error::InvalidSegmentGranularity => "Segment granularity is not a power of two",
+            Error::ZeroKernelSpan(_) => "Unable to zero out the kernel span",
+            Error::NoLoadableSegments => "No loadable segment",
+            Error::SeekDynamicSection(_) => "Unable to seek to dynamic section",
+            Error::ReadDynamicSection(_) => "Unable to read dynamic section",
+            Error::InvalidRelocationTable => "Invalid relocation table",
+            Error::SeekRelocations(_) => "Unable to seek to relocation table",
+            Error::ReadRelocations(_) => "Unable to read relocation table",
+            Error::ApplyRelocation(_) => "Unable to apply relocation",
+            Error::UnsupportedRelocation => "Unsupported relocation",
+            Error::InvalidSymbolTable => "Invalid symbol table",
+            Error::SeekSymbolTable(_) => "Unable to seek to symbol table",
+            Error::ReadSymbolTable(_) => "Unable to read symbol table",
         };
 
-        write!(f, "Kernel Loader: {}", desc)
+        write!(f, "Kernel Loader: {}", desc)?;
+        if let Some(index) = self.program_header_index() {
+            write!(f, " (program header {})", index)?;
+        }
+        if let Error::TruncatedSegment { expected, got, .. } = self {
+            write!(f, ": expected {:#x} bytes, got {:#x}", expected, got)?;
+        }
+        if let Some(source) = std::error::Error::source(self) {
+            write!(f, ": {}", source)?;
+        }
+        Ok(())
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ReadElfHeader(ref e)
+            | Error::ReadProgramHeader(ref e)
+            | Error::ReadSectionHeader(ref e)
+            | Error::SeekElfStart(ref e)
+            | Error::SeekElfEnd(ref e)
+            | Error::SeekProgramHeader(ref e)
+            | Error::SeekSectionHeader(ref e)
+            | Error::SeekNoteHeader(ref e)
+            | Error::ReadNoteHeader(ref e)
+            | Error::SeekDynamicSection(ref e)
+            | Error::ReadDynamicSection(ref e)
+            | Error::SeekRelocations(ref e)
+            | Error::ReadRelocations(ref e)
+            | Error::SeekSymbolTable(ref e)
+            | Error::ReadSymbolTable(ref e)
+            | Error::SeekKernelStart { source: ref e, .. } => Some(e),
+            Error::ReadKernelImage { source: ref e, .. }
+            | Error::ZeroBss { source: ref e, .. }
+            | Error::ZeroKernelSpan(ref e)
+            | Error::ApplyRelocation(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+impl Error {
+    // Index of the program header of the segment the error is about, if any.
+    fn program_header_index(&self) -> Option<usize> {
+        match self {
+            Error::ReadKernelImage { index, .. }
+            | Error::SeekKernelStart { index, .. }
+            | Error::ZeroBss { index, .. }
+            | Error::TruncatedSegment { index, .. }
+            | Error::SegmentPastEof { index }
+            | Error::SegmentOutsideRegion { index } => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+// The wrapped I/O and guest memory errors can't be compared, so errors are equal when they are
+// the same variant with the same segment details, regardless of their source.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Error::ReadKernelImage { index, .. },
+                Error::ReadKernelImage {
+                    index: other_index, ..
+                },
+            )
+            | (
+                Error::SeekKernelStart { index, .. },
+                Error::SeekKernelStart {
+                    index: other_index, ..
+                },
+            )
+            | (
+                Error::ZeroBss { index, .. },
+                Error::ZeroBss {
+                    index: other_index, ..
+                },
+            )
+            | (Error::SegmentPastEof { index }, Error::SegmentPastEof { index: other_index })
+            | (
+                Error::SegmentOutsideRegion { index },
+                Error::SegmentOutsideRegion { index: other_index },
+            ) => index == other_index,
+            (
+                Error::TruncatedSegment {
+                    index,
+                    expected,
+                    got,
+                },
+                Error::TruncatedSegment {
+                    index: other_index,
+                    expected: other_expected,
+                    got: other_got,
+                },
+            ) => (index, expected, got) == (other_index, other_expected, other_got),
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for Error {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 /// Availability of PVH entry point in the kernel, which allows the VMM
 /// to use the PVH boot protocol to start guests.
 pub enum PvhBootCapability {
@@ -113,15 +613,10 @@ pub enum PvhBootCapability {
     /// PVH entry point is not present
     PvhEntryNotPresent,
     /// PVH entry point is ignored, even if available
+    #[default]
     PvhEntryIgnored,
 }
 
-impl Default for PvhBootCapability {
-    fn default() -> Self {
-        PvhBootCapability::PvhEntryIgnored
-    }
-}
-
 impl fmt::Display for PvhBootCapability {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::PvhBootCapability::*;
@@ -137,367 +632,3259 @@ impl fmt::Display for PvhBootCapability {
     }
 }
 
+// 32-bit headers are widened to their 64-bit counterparts, so the rest of the loader only
+// deals with a single layout.
+impl From<elf::Elf32_Ehdr> for elf::Elf64_Ehdr {
+    fn from(ehdr: elf::Elf32_Ehdr) -> Self {
+        elf::Elf64_Ehdr {
+            e_ident: ehdr.e_ident,
+            e_type: ehdr.e_type,
+            e_machine: ehdr.e_machine,
+            e_version: ehdr.e_version,
+            e_entry: ehdr.e_entry.into(),
+            e_phoff: ehdr.e_phoff.into(),
+            e_shoff: ehdr.e_shoff.into(),
+            e_flags: ehdr.e_flags,
+            e_ehsize: ehdr.e_ehsize,
+            e_phentsize: ehdr.e_phentsize,
+            e_phnum: ehdr.e_phnum,
+            e_shentsize: ehdr.e_shentsize,
+            e_shnum: ehdr.e_shnum,
+            e_shstrndx: ehdr.e_shstrndx,
+        }
+    }
+}
+
+impl From<elf::Elf32_Phdr> for elf::Elf64_Phdr {
+    fn from(phdr: elf::Elf32_Phdr) -> Self {
+        elf::Elf64_Phdr {
+            p_type: phdr.p_type,
+            p_flags: phdr.p_flags,
+            p_offset: phdr.p_offset.into(),
+            p_vaddr: phdr.p_vaddr.into(),
+            p_paddr: phdr.p_paddr.into(),
+            p_filesz: phdr.p_filesz.into(),
+            p_memsz: phdr.p_memsz.into(),
+            p_align: phdr.p_align.into(),
+        }
+    }
+}
+
+impl From<elf::Elf32_Shdr> for elf::Elf64_Shdr {
+    fn from(shdr: elf::Elf32_Shdr) -> Self {
+        elf::Elf64_Shdr {
+            sh_name: shdr.sh_name,
+            sh_type: shdr.sh_type,
+            sh_flags: shdr.sh_flags.into(),
+            sh_addr: shdr.sh_addr.into(),
+            sh_offset: shdr.sh_offset.into(),
+            sh_size: shdr.sh_size.into(),
+            sh_link: shdr.sh_link,
+            sh_info: shdr.sh_info,
+            sh_addralign: shdr.sh_addralign.into(),
+            sh_entsize: shdr.sh_entsize.into(),
+        }
+    }
+}
+
+impl From<elf::Elf32_Sym> for elf::Elf64_Sym {
+    fn from(sym: elf::Elf32_Sym) -> Self {
+        elf::Elf64_Sym {
+            st_name: sym.st_name,
+            st_info: sym.st_info,
+            st_other: sym.st_other,
+            st_shndx: sym.st_shndx,
+            st_value: sym.st_value.into(),
+            st_size: sym.st_size.into(),
+        }
+    }
+}
+
+/// Guest memory location and permissions of a loadable segment, as returned by
+/// [`Elf::parse_segments`](struct.Elf.html#method.parse_segments).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SegmentInfo {
+    /// Guest address at which the segment is loaded.
+    pub guest_addr: GuestAddress,
+    /// Size of the segment in guest memory, including its BSS.
+    pub mem_size: GuestUsize,
+    /// Segment permissions (`p_flags`), a combination of `PF_R`, `PF_W` and `PF_X`.
+    pub flags: u32,
+}
+
+/// Description of what loading a vmlinux elf image would do, as returned by [`parse_elf`].
+///
+/// The addresses are the ones used when the image is loaded without `kernel_offset`.
+#[derive(Clone, Debug)]
+pub struct ParsedElf {
+    /// Entry point of the kernel.
+    pub entry: GuestAddress,
+    /// End of the highest segment in guest memory, including its BSS.
+    pub kernel_end: GuestUsize,
+    /// Index of the program header and program header of each non-empty `PT_LOAD` segment, in
+    /// program header order. Program headers of 32-bit images are widened to the 64-bit layout.
+    pub segments: Vec<(usize, elf::Elf64_Phdr)>,
+    /// Availability of a PVH entry point.
+    pub pvh_boot_cap: PvhBootCapability,
+    // Elf header, widened to the 64-bit layout.
+    ehdr: elf::Elf64_Ehdr,
+    // `PT_DYNAMIC` program header, if any.
+    dynamic_phdr: Option<elf::Elf64_Phdr>,
+    // Size of the image.
+    image_size: u64,
+}
+
+/// Parses a vmlinux elf image without loading it.
+///
+/// The image goes through the validation of [`Elf::load`] which depends neither on guest memory
+/// nor on [`LoadOptions`]: the headers, the notes, the alignment of the segments, their data
+/// being in the image and them not overlapping are checked. `load` then checks the segments
+/// against guest memory and its options, and copies them. Nothing is written anywhere, so this
+/// can e.g. be fuzzed without setting up guest memory.
+///
+/// # Arguments
+///
+/// * `kernel_image` - Input vmlinux image.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// let kernel_image = include_bytes!("test_elf.bin");
+/// let parsed = elf::parse_elf(&mut Cursor::new(&kernel_image)).unwrap();
+/// assert_eq!(parsed.segments.len(), 2);
+/// assert_eq!(parsed.kernel_end, 0x42e);
+/// ```
+///
+/// [`Elf::load`]: struct.Elf.html#method.load
+/// [`LoadOptions`]: struct.LoadOptions.html
+pub fn parse_elf<F>(kernel_image: &mut F) -> Result<ParsedElf>
+where
+    F: Read + Seek,
+{
+    Elf::parse_image(None, None, kernel_image)
+}
+
+/// Where a loadable segment is in the image and in guest memory, as returned by
+/// [`segment_descriptors`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SegmentDescriptor {
+    /// Offset of the segment data in the image.
+    pub file_offset: u64,
+    /// Guest address at which the segment is loaded, without `kernel_offset`.
+    pub guest_addr: GuestAddress,
+    /// Size of the segment data in the image.
+    pub file_size: u64,
+    /// Size of the segment in guest memory, including its BSS.
+    pub mem_size: GuestUsize,
+    /// Segment permissions (`p_flags`), a combination of `PF_R`, `PF_W` and `PF_X`.
+    pub flags: u32,
+}
+
+/// Describes the segments of a vmlinux elf image for the caller to copy them itself.
+///
+/// The image goes through all the validation of [`parse_elf`]. Loading the image amounts to
+/// copying `file_size` bytes from `file_offset` in the image to `guest_addr`, then zeroing out
+/// the rest of the `mem_size` bytes, for each descriptor. This lets a VMM copy the segments
+/// with its own memory API, e.g. through DMA.
+///
+/// # Arguments
+///
+/// * `kernel_image` - Input vmlinux image.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::Cursor;
+/// # use linux_loader::loader::*;
+/// let kernel_image = include_bytes!("test_elf.bin");
+/// let segments = elf::segment_descriptors(&mut Cursor::new(&kernel_image)).unwrap();
+/// assert_eq!(segments[1].file_offset, 0xd0);
+/// ```
+pub fn segment_descriptors<F>(kernel_image: &mut F) -> Result<Vec<SegmentDescriptor>>
+where
+    F: Read + Seek,
+{
+    let parsed = parse_elf(kernel_image)?;
+    Ok(parsed
+        .segments
+        .iter()
+        .map(|(_, phdr)| SegmentDescriptor {
+            file_offset: phdr.p_offset,
+            guest_addr: GuestAddress(Elf::segment_address(&parsed.ehdr, phdr)),
+            file_size: phdr.p_filesz,
+            mem_size: phdr.p_memsz,
+            flags: phdr.p_flags,
+        })
+        .collect())
+}
+
 /// Raw ELF (a.k.a. vmlinux) kernel image support.
+///
+/// Both 32-bit (`ELFCLASS32`) and 64-bit (`ELFCLASS64`) little endian images are supported.
 pub struct Elf;
 
 impl Elf {
-    /// Verifies that magic numbers are present in the Elf header.
-    fn validate_header(ehdr: &elf::Elf64_Ehdr) -> std::result::Result<(), Error> {
-        // Sanity checks
-        if ehdr.e_ident[elf::EI_MAG0 as usize] != elf::ELFMAG0 as u8
-            || ehdr.e_ident[elf::EI_MAG1 as usize] != elf::ELFMAG1
-            || ehdr.e_ident[elf::EI_MAG2 as usize] != elf::ELFMAG2
-            || ehdr.e_ident[elf::EI_MAG3 as usize] != elf::ELFMAG3
-        {
-            return Err(Error::InvalidElfMagicNumber);
-        }
-        if ehdr.e_ident[elf::EI_DATA as usize] != elf::ELFDATA2LSB as u8 {
-            return Err(Error::BigEndianElfOnLittle);
+    /// Reads the Elf header, widening it to the 64-bit layout for 32-bit images.
+    fn read_header<F>(kernel_image: &mut F) -> std::result::Result<elf::Elf64_Ehdr, Error>
+    where
+        F: Read + Seek,
+    {
+        kernel_image
+            .seek(SeekFrom::Start(0))
+            .map_err(Error::SeekElfStart)?;
+
+        let mut ehdr = elf::Elf64_Ehdr::default();
+        kernel_image
+            .read_exact(&mut ehdr.e_ident)
+            .map_err(Error::ReadElfHeader)?;
+        Self::validate_ident(&ehdr.e_ident)?;
+
+        kernel_image
+            .seek(SeekFrom::Start(0))
+            .map_err(Error::SeekElfStart)?;
+        ehdr = if Self::is_elf32(&ehdr) {
+            read_elf_struct::<elf::Elf32_Ehdr, _>(kernel_image, &ehdr.e_ident)
+                .map_err(Error::ReadElfHeader)?
+                .into()
+        } else {
+            read_elf_struct(kernel_image, &ehdr.e_ident).map_err(Error::ReadElfHeader)?
+        };
+
+        Self::validate_header(&ehdr)?;
+        Ok(ehdr)
+    }
+
+    /// Reads the next program header, widening it to the 64-bit layout for 32-bit images.
+    fn read_program_header<F>(
+        ehdr: &elf::Elf64_Ehdr,
+        kernel_image: &mut F,
+    ) -> std::result::Result<elf::Elf64_Phdr, Error>
+    where
+        F: Read,
+    {
+        if Self::is_elf32(ehdr) {
+            read_elf_struct::<elf::Elf32_Phdr, _>(kernel_image, &ehdr.e_ident)
+                .map(elf::Elf64_Phdr::from)
+                .map_err(Error::ReadProgramHeader)
+        } else {
+            read_elf_struct(kernel_image, &ehdr.e_ident).map_err(Error::ReadProgramHeader)
         }
-        if ehdr.e_phentsize as usize != mem::size_of::<elf::Elf64_Phdr>() {
-            return Err(Error::InvalidProgramHeaderSize);
+    }
+
+    /// Reads the whole program header table.
+    fn read_program_headers<F>(
+        ehdr: &elf::Elf64_Ehdr,
+        kernel_image: &mut F,
+    ) -> std::result::Result<Vec<elf::Elf64_Phdr>, Error>
+    where
+        F: Read + Seek,
+    {
+        Self::check_program_headers(ehdr, kernel_image)?;
+
+        // Each header is read from the start of its entry, skipping the extra bytes of entries
+        // larger than the standard structure.
+        let mut phdrs: Vec<elf::Elf64_Phdr> = vec![];
+        for index in 0..ehdr.e_phnum {
+            phdrs.push(Self::read_program_header_at(ehdr, index, kernel_image)?);
         }
-        if (ehdr.e_phoff as usize) < mem::size_of::<elf::Elf64_Ehdr>() {
-            return Err(Error::InvalidProgramHeaderOffset);
+        Ok(phdrs)
+    }
+
+    /// Reads the program header at `index` in the program header table.
+    fn read_program_header_at<F>(
+        ehdr: &elf::Elf64_Ehdr,
+        index: u16,
+        kernel_image: &mut F,
+    ) -> std::result::Result<elf::Elf64_Phdr, Error>
+    where
+        F: Read + Seek,
+    {
+        // Can't overflow, the table is known to end within the image.
+        let offset = ehdr.e_phoff + u64::from(index) * u64::from(ehdr.e_phentsize);
+        kernel_image
+            .seek(SeekFrom::Start(offset))
+            .map_err(Error::SeekProgramHeader)?;
+        Self::read_program_header(ehdr, kernel_image)
+    }
+
+    /// Checks that the image is for `machine`, or for x86 if `machine` is `None`.
+    fn check_machine(
+        ehdr: &elf::Elf64_Ehdr,
+        machine: Option<u16>,
+    ) -> std::result::Result<(), Error> {
+        // The headers of other architectures have the same layout, but their code is garbage
+        // for the guest.
+        let native_machine = match machine {
+            Some(machine) => ehdr.e_machine == machine,
+            None => NATIVE_MACHINES.contains(&u32::from(ehdr.e_machine)),
+        };
+        if !native_machine {
+            return Err(Error::UnsupportedArchitecture);
         }
         Ok(())
     }
-}
 
-impl KernelLoader for Elf {
-    /// Loads a kernel from a vmlinux elf image into guest memory.
+    /// Checks that the program header table ends within the image, returning the image size.
+    fn check_program_headers<F>(
+        ehdr: &elf::Elf64_Ehdr,
+        kernel_image: &mut F,
+    ) -> std::result::Result<u64, Error>
+    where
+        F: Seek,
+    {
+        // Make sure the program header table is within the image before reading it, so a
+        // corrupt `e_phnum` can't make us allocate and read a huge table.
+        let image_size = kernel_image
+            .seek(SeekFrom::End(0))
+            .map_err(Error::SeekElfEnd)?;
+        // Seeking past the end of the image succeeds, tell a table which is entirely missing
+        // apart from a too large one.
+        if ehdr.e_phnum > 0 && ehdr.e_phoff >= image_size {
+            return Err(Error::ProgramHeaderPastEof);
+        }
+        let phdrs_end = u64::from(ehdr.e_phnum)
+            .checked_mul(u64::from(ehdr.e_phentsize))
+            .and_then(|phdrs_size| phdrs_size.checked_add(ehdr.e_phoff))
+            .ok_or(Error::InvalidProgramHeaderCount)?;
+        if phdrs_end > image_size {
+            return Err(Error::InvalidProgramHeaderCount);
+        }
+        Ok(image_size)
+    }
+
+    /// Reads the Elf header of a vmlinux elf image, without loading it.
     ///
-    /// By default, the kernel is loaded into guest memory at offset `phdr.p_paddr` specified
-    /// by the elf image. When used, `kernel_offset` specifies a fixed offset from `phdr.p_paddr`
-    /// at which to load the kernel. If `kernel_offset` is requested, the `pvh_entry_addr` field
-    /// of the result will not be populated.
+    /// The header goes through the same validation as in [`load`](struct.Elf.html#method.load),
+    /// and the header of a 32-bit image is widened to the 64-bit layout. The loader doesn't
+    /// interpret the architecture specific fields, such as `e_flags` (e.g. the float ABI of
+    /// RISC-V images), this lets a VMM check them before booting the kernel.
     ///
     /// # Arguments
     ///
-    /// * `guest_mem`: [`GuestMemory`] to load the kernel in.
-    /// * `kernel_offset`: Offset to be added to default kernel load address in guest memory.
     /// * `kernel_image` - Input vmlinux image.
-    /// * `highmem_start_address`: Address where high memory starts.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// # extern crate vm_memory;
     /// # use std::io::Cursor;
     /// # use linux_loader::loader::*;
-    /// # use vm_memory::{Address, GuestAddress};
-    /// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
-    /// let mem_size: usize = 0x1000000;
-    /// let himem_start = GuestAddress(0x0);
-    /// let kernel_addr = GuestAddress(0x200000);
-    /// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), mem_size)]).unwrap();
-    /// let mut kernel_image = vec![];
-    /// kernel_image.extend_from_slice(include_bytes!("test_elf.bin"));
-    /// elf::Elf::load(
-    ///     &gm,
-    ///     Some(kernel_addr),
-    ///     &mut Cursor::new(&kernel_image),
-    ///     Some(himem_start),
-    /// )
-    /// .unwrap();
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// let ehdr = elf::Elf::parse_header(&mut Cursor::new(&kernel_image)).unwrap();
+    /// assert_eq!(ehdr.e_flags, 0);
     /// ```
-    ///
-    /// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
-    fn load<F, M: GuestMemory>(
-        guest_mem: &M,
-        kernel_offset: Option<GuestAddress>,
-        kernel_image: &mut F,
-        highmem_start_address: Option<GuestAddress>,
-    ) -> Result<KernelLoaderResult>
+    pub fn parse_header<F>(kernel_image: &mut F) -> Result<elf::Elf64_Ehdr>
     where
         F: Read + Seek,
     {
-        kernel_image
-            .seek(SeekFrom::Start(0))
-            .map_err(|_| Error::SeekElfStart)?;
+        Ok(Self::read_header(kernel_image)?)
+    }
 
-        let mut ehdr = elf::Elf64_Ehdr::default();
-        kernel_image
-            .read_exact(ehdr.as_mut_slice())
-            .map_err(|_| Error::ReadElfHeader)?;
+    /// Computes the guest memory span of a vmlinux elf image, without loading it.
+    ///
+    /// Returns the lowest `p_paddr` and the highest `p_paddr + p_memsz` of the `PT_LOAD`
+    /// segments (`p_vaddr` for position independent images), which lets a VMM size the guest
+    /// memory before allocating it. The image goes through the same validation as in
+    /// [`parse_elf`](fn.parse_elf.html).
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel_image` - Input vmlinux image.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::GuestAddress;
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// let (start, end) = elf::Elf::kernel_size(&mut Cursor::new(&kernel_image)).unwrap();
+    /// assert_eq!(start, GuestAddress(0x0));
+    /// assert_eq!(end, 0x42e);
+    /// ```
+    pub fn kernel_size<F>(kernel_image: &mut F) -> Result<(GuestAddress, GuestUsize)>
+    where
+        F: Read + Seek,
+    {
+        let parsed = Self::parse_image(None, None, kernel_image)?;
 
-        // Sanity checks.
-        Self::validate_header(&ehdr)?;
-        if let Some(addr) = highmem_start_address {
-            if (ehdr.e_entry as u64) < addr.raw_value() {
-                return Err(Error::InvalidEntryAddress.into());
-            }
-        }
+        // There is at least one segment.
+        let start = parsed
+            .segments
+            .iter()
+            .map(|(_, phdr)| Self::segment_address(&parsed.ehdr, phdr))
+            .min()
+            .unwrap_or(0);
+        Ok((GuestAddress(start), parsed.kernel_end))
+    }
 
-        let mut loader_result = KernelLoaderResult {
-            kernel_load: match kernel_offset {
-                Some(k_offset) => GuestAddress(
-                    k_offset
-                        .raw_value()
-                        .checked_add(ehdr.e_entry as u64)
-                        .ok_or(Error::Overflow)?,
-                ),
-                None => GuestAddress(ehdr.e_entry as u64),
-            },
-            ..Default::default()
+    /// Describes the loadable segments of a vmlinux elf image, without loading it.
+    ///
+    /// Returns the guest memory location and the permissions of each non-empty `PT_LOAD`
+    /// segment, in program header order, e.g. for a VMM to map the kernel text read-only after
+    /// loading it. The image goes through the same validation as in
+    /// [`parse_elf`](fn.parse_elf.html), `kernel_offset` has the same meaning as in
+    /// [`load`](struct.Elf.html#method.load).
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel_offset` - Offset to be added to default kernel load address in guest memory.
+    /// * `kernel_image` - Input vmlinux image.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use linux_loader::elf::PF_X;
+    /// # use linux_loader::loader::*;
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// let segments = elf::Elf::parse_segments(None, &mut Cursor::new(&kernel_image)).unwrap();
+    /// assert_ne!(segments[0].flags & PF_X, 0);
+    /// ```
+    pub fn parse_segments<F>(
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+    ) -> Result<Vec<SegmentInfo>>
+    where
+        F: Read + Seek,
+    {
+        let parsed = Self::parse_image(kernel_offset, None, kernel_image)?;
+
+        parsed
+            .segments
+            .iter()
+            .map(|(_, phdr)| {
+                Ok(SegmentInfo {
+                    guest_addr: Self::segment_guest_address(kernel_offset, &parsed.ehdr, phdr)?,
+                    mem_size: phdr.p_memsz,
+                    flags: phdr.p_flags,
+                })
+            })
+            .collect()
+    }
+
+    /// Computes the guest memory ranges a vmlinux elf image would be loaded to, without loading
+    /// it.
+    ///
+    /// Returns the start address and the size (`p_memsz`) of every `PT_LOAD` segment
+    /// [`load`](struct.Elf.html#method.load) would write, in program header order, so they can
+    /// be checked against the memory map of a VM before its guest memory exists. The image is
+    /// validated like by [`parse_elf`](fn.parse_elf.html), `kernel_offset` has the same meaning
+    /// as in `load`.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel_image` - Input vmlinux image.
+    /// * `kernel_offset` - Offset to be added to default kernel load address in guest memory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::GuestAddress;
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// let regions = elf::Elf::plan_load(&mut Cursor::new(&kernel_image), None).unwrap();
+    /// assert_eq!(regions[1], (GuestAddress(0x420), 0xe));
+    /// ```
+    pub fn plan_load<F>(
+        kernel_image: &mut F,
+        kernel_offset: Option<GuestAddress>,
+    ) -> Result<Vec<(GuestAddress, GuestUsize)>>
+    where
+        F: Read + Seek,
+    {
+        let parsed = Self::parse_image(kernel_offset, None, kernel_image)?;
+
+        parsed
+            .segments
+            .iter()
+            .map(|(_, phdr)| {
+                Ok((
+                    Self::segment_guest_address(kernel_offset, &parsed.ehdr, phdr)?,
+                    phdr.p_memsz,
+                ))
+            })
+            .collect()
+    }
+
+    /// Reads the section headers of a vmlinux elf image.
+    ///
+    /// The kernel loader ignores the sections, this gives access to them e.g. to locate the
+    /// symbol tables. Headers of 32-bit images are widened to the 64-bit layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel_image` - Input vmlinux image.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// let sections = elf::Elf::parse_sections(&mut Cursor::new(&kernel_image)).unwrap();
+    /// assert_eq!(sections.len(), 5);
+    /// ```
+    pub fn parse_sections<F>(kernel_image: &mut F) -> Result<Vec<elf::Elf64_Shdr>>
+    where
+        F: Read + Seek,
+    {
+        let ehdr = Self::read_header(kernel_image)?;
+        if ehdr.e_shnum == 0 {
+            return Ok(vec![]);
+        }
+
+        let shdr_size = if Self::is_elf32(&ehdr) {
+            mem::size_of::<elf::Elf32_Shdr>()
+        } else {
+            mem::size_of::<elf::Elf64_Shdr>()
         };
+        if ehdr.e_shentsize as usize != shdr_size {
+            return Err(Error::InvalidSectionHeaderSize.into());
+        }
+
+        let image_size = kernel_image
+            .seek(SeekFrom::End(0))
+            .map_err(Error::SeekElfEnd)?;
+        let shdrs_end = u64::from(ehdr.e_shnum)
+            .checked_mul(u64::from(ehdr.e_shentsize))
+            .and_then(|shdrs_size| shdrs_size.checked_add(ehdr.e_shoff))
+            .ok_or(Error::InvalidSectionHeaderCount)?;
+        if shdrs_end > image_size {
+            return Err(Error::InvalidSectionHeaderCount.into());
+        }
 
         kernel_image
-            .seek(SeekFrom::Start(ehdr.e_phoff))
-            .map_err(|_| Error::SeekProgramHeader)?;
+            .seek(SeekFrom::Start(ehdr.e_shoff))
+            .map_err(Error::SeekSectionHeader)?;
 
-        let mut phdrs: Vec<elf::Elf64_Phdr> = vec![];
-        for _ in 0usize..ehdr.e_phnum as usize {
-            let mut phdr = elf::Elf64_Phdr::default();
-            kernel_image
-                .read_exact(phdr.as_mut_slice())
-                .map_err(|_| Error::ReadProgramHeader)?;
-            phdrs.push(phdr);
+        let mut shdrs: Vec<elf::Elf64_Shdr> = vec![];
+        for _ in 0usize..ehdr.e_shnum as usize {
+            let shdr = if Self::is_elf32(&ehdr) {
+                read_elf_struct::<elf::Elf32_Shdr, _>(kernel_image, &ehdr.e_ident)
+                    .map_err(Error::ReadSectionHeader)?
+                    .into()
+            } else {
+                read_elf_struct(kernel_image, &ehdr.e_ident).map_err(Error::ReadSectionHeader)?
+            };
+            shdrs.push(shdr);
         }
+        Ok(shdrs)
+    }
 
-        // Read in each section pointed to by the program headers.
-        for phdr in phdrs {
-            if phdr.p_type != elf::PT_LOAD || phdr.p_filesz == 0 {
-                if phdr.p_type == elf::PT_NOTE {
-                    // The PVH boot protocol currently requires that the kernel is loaded at
-                    // the default kernel load address in guest memory (specified at kernel
-                    // build time by the value of CONFIG_PHYSICAL_START). Therefore, only
-                    // attempt to use PVH if an offset from the default load address has not
-                    // been requested using the kernel_offset parameter.
-                    if let Some(_offset) = kernel_offset {
-                        loader_result.pvh_boot_cap = PvhBootCapability::PvhEntryIgnored;
-                    } else {
-                        // If kernel_offset is not requested, check if PVH entry point is present
-                        loader_result.pvh_boot_cap = parse_elf_note(&phdr, kernel_image)?;
-                    }
-                }
-                continue;
+    /// Looks up the symbol `name` in the symbol table of a vmlinux elf image.
+    ///
+    /// This reads the `SHT_SYMTAB` section and its string table, e.g. to find a buffer or a boot
+    /// structure of the kernel. The value of the symbol is returned as it is in the image, i.e.
+    /// the address the kernel is linked at: it is up to the caller to translate it to a guest
+    /// physical address for kernels linked at a virtual address, or loaded at a `kernel_offset`.
+    /// Returns `None` if the image has no symbol table, e.g. once stripped, or no defined symbol
+    /// called `name`.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel_image` - Input vmlinux image.
+    /// * `name` - Name of the symbol.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::GuestAddress;
+    /// let kernel_image = include_bytes!("test_symbols.bin");
+    /// let addr = elf::Elf::resolve_symbol(&mut Cursor::new(&kernel_image), "cmdline_buf").unwrap();
+    /// assert_eq!(addr, Some(GuestAddress(0x40_0010)));
+    /// ```
+    pub fn resolve_symbol<F>(kernel_image: &mut F, name: &str) -> Result<Option<GuestAddress>>
+    where
+        F: Read + Seek,
+    {
+        let ehdr = Self::read_header(kernel_image)?;
+        let shdrs = Self::parse_sections(kernel_image)?;
+        let symtab = match shdrs.iter().find(|shdr| shdr.sh_type == elf::SHT_SYMTAB) {
+            Some(symtab) => symtab,
+            None => return Ok(None),
+        };
+        let sym_size = if Self::is_elf32(&ehdr) {
+            mem::size_of::<elf::Elf32_Sym>()
+        } else {
+            mem::size_of::<elf::Elf64_Sym>()
+        };
+        if symtab.sh_entsize != sym_size as u64 {
+            return Err(Error::InvalidSymbolTable.into());
+        }
+        let strtab = shdrs
+            .get(symtab.sh_link as usize)
+            .filter(|shdr| shdr.sh_type == elf::SHT_STRTAB)
+            .ok_or(Error::InvalidSymbolTable)?;
+
+        // Both tables must be within the image, so a corrupt size can't make us allocate a huge
+        // string table.
+        let image_size = kernel_image
+            .seek(SeekFrom::End(0))
+            .map_err(Error::SeekElfEnd)?;
+        for table in [symtab, strtab] {
+            let table_end = table
+                .sh_offset
+                .checked_add(table.sh_size)
+                .ok_or(Error::InvalidSymbolTable)?;
+            if table_end > image_size {
+                return Err(Error::InvalidSymbolTable.into());
             }
+        }
 
-            kernel_image
-                .seek(SeekFrom::Start(phdr.p_offset))
-                .map_err(|_| Error::SeekKernelStart)?;
-
-            // if the vmm does not specify where the kernel should be loaded, just
-            // load it to the physical address p_paddr for each segment.
-            let mem_offset = match kernel_offset {
-                Some(k_offset) => k_offset
-                    .checked_add(phdr.p_paddr as u64)
-                    .ok_or(Error::InvalidProgramHeaderAddress)?,
-                None => GuestAddress(phdr.p_paddr as u64),
+        let mut strings = vec![0u8; strtab.sh_size as usize];
+        kernel_image
+            .seek(SeekFrom::Start(strtab.sh_offset))
+            .map_err(Error::SeekSymbolTable)?;
+        kernel_image
+            .read_exact(&mut strings)
+            .map_err(Error::ReadSymbolTable)?;
+
+        kernel_image
+            .seek(SeekFrom::Start(symtab.sh_offset))
+            .map_err(Error::SeekSymbolTable)?;
+        for _ in 0..symtab.sh_size / symtab.sh_entsize {
+            let sym: elf::Elf64_Sym = if Self::is_elf32(&ehdr) {
+                read_elf_struct::<elf::Elf32_Sym, _>(kernel_image, &ehdr.e_ident)
+                    .map_err(Error::ReadSymbolTable)?
+                    .into()
+            } else {
+                read_elf_struct(kernel_image, &ehdr.e_ident).map_err(Error::ReadSymbolTable)?
             };
+            // Undefined symbols have no value.
+            if u32::from(sym.st_shndx) == elf::SHN_UNDEF {
+                continue;
+            }
+            let sym_name = strings
+                .get(sym.st_name as usize..)
+                .and_then(|bytes| CStr::from_bytes_until_nul(bytes).ok())
+                .ok_or(Error::InvalidSymbolTable)?;
+            if sym_name.to_bytes() == name.as_bytes() {
+                return Ok(Some(GuestAddress(sym.st_value)));
+            }
+        }
+        Ok(None)
+    }
 
-            guest_mem
-                .read_exact_from(mem_offset, kernel_image, phdr.p_filesz as usize)
-                .map_err(|_| Error::ReadKernelImage)?;
+    fn is_elf32(ehdr: &elf::Elf64_Ehdr) -> bool {
+        ehdr.e_ident[elf::EI_CLASS as usize] == elf::ELFCLASS32 as u8
+    }
 
-            let kernel_end = mem_offset
-                .raw_value()
-                .checked_add(phdr.p_memsz as GuestUsize)
-                .ok_or(KernelLoaderError::MemoryOverflow)?;
-            loader_result.kernel_end = std::cmp::max(loader_result.kernel_end, kernel_end);
+    /// Returns whether the multi-byte fields of the image need to be byte swapped.
+    #[cfg(not(feature = "safe-parse"))]
+    fn is_foreign_endian(e_ident: &[u8]) -> bool {
+        let native = if cfg!(target_endian = "little") {
+            elf::ELFDATA2LSB
+        } else {
+            elf::ELFDATA2MSB
+        };
+        e_ident[elf::EI_DATA as usize] != native as u8
+    }
+
+    /// Verifies that magic numbers are present in the Elf identification bytes and that the
+    /// class and data encoding are supported.
+    fn validate_ident(e_ident: &[u8]) -> std::result::Result<(), Error> {
+        if e_ident[elf::EI_MAG0 as usize] != elf::ELFMAG0 as u8
+            || e_ident[elf::EI_MAG1 as usize] != elf::ELFMAG1
+            || e_ident[elf::EI_MAG2 as usize] != elf::ELFMAG2
+            || e_ident[elf::EI_MAG3 as usize] != elf::ELFMAG3
+        {
+            return Err(Error::InvalidElfMagicNumber);
+        }
+        // Big endian images are only accepted with the `big-endian` feature.
+        if e_ident[elf::EI_DATA as usize] != elf::ELFDATA2LSB as u8
+            && !(cfg!(feature = "big-endian")
+                && e_ident[elf::EI_DATA as usize] == elf::ELFDATA2MSB as u8)
+        {
+            return Err(Error::BigEndianElfOnLittle);
+        }
+        if e_ident[elf::EI_CLASS as usize] != elf::ELFCLASS32 as u8
+            && e_ident[elf::EI_CLASS as usize] != elf::ELFCLASS64 as u8
+        {
+            return Err(Error::InvalidElfClass);
         }
+        Ok(())
+    }
 
-        // elf image has no setup_header which is defined for bzImage
-        loader_result.setup_header = None;
+    /// Returns the address of a segment relative to the kernel load offset.
+    ///
+    /// Position independent images are laid out by their virtual addresses, their physical
+    /// addresses are usually left at 0.
+    fn segment_address(ehdr: &elf::Elf64_Ehdr, phdr: &elf::Elf64_Phdr) -> u64 {
+        if u32::from(ehdr.e_type) == elf::ET_DYN {
+            phdr.p_vaddr
+        } else {
+            phdr.p_paddr
+        }
+    }
+
+    /// Returns the guest address a segment is loaded to.
+    ///
+    /// If the VMM does not specify where the kernel should be loaded, each segment is loaded
+    /// to its own address, e.g. the physical address `p_paddr`.
+    fn segment_guest_address(
+        kernel_offset: Option<GuestAddress>,
+        ehdr: &elf::Elf64_Ehdr,
+        phdr: &elf::Elf64_Phdr,
+    ) -> std::result::Result<GuestAddress, Error> {
+        let addr = Self::segment_address(ehdr, phdr);
+        match kernel_offset {
+            Some(k_offset) => k_offset
+                .checked_add(addr)
+                .ok_or(Error::InvalidProgramHeaderAddress),
+            None => Ok(GuestAddress(addr)),
+        }
+    }
+
+    /// Verifies that the Elf header is consistent with its class.
+    fn validate_header(ehdr: &elf::Elf64_Ehdr) -> std::result::Result<(), Error> {
+        let (ehdr_size, phdr_size) = if Self::is_elf32(ehdr) {
+            (
+                mem::size_of::<elf::Elf32_Ehdr>(),
+                mem::size_of::<elf::Elf32_Phdr>(),
+            )
+        } else {
+            (
+                mem::size_of::<elf::Elf64_Ehdr>(),
+                mem::size_of::<elf::Elf64_Phdr>(),
+            )
+        };
+        if u32::from(ehdr.e_type) != elf::ET_EXEC && u32::from(ehdr.e_type) != elf::ET_DYN {
+            return Err(Error::UnsupportedElfType);
+        }
+        if ehdr.e_version != EV_CURRENT {
+            return Err(Error::UnsupportedElfVersion);
+        }
+        // Entries may be larger than the standard structure, e.g. with vendor extensions.
+        if (ehdr.e_phentsize as usize) < phdr_size {
+            return Err(Error::InvalidProgramHeaderSize);
+        }
+        // The program header table can't overlap the ELF header of the class of the image, but
+        // may start right after it.
+        if ehdr.e_phoff < ehdr_size as u64 {
+            return Err(Error::InvalidProgramHeaderOffset);
+        }
+        if ehdr.e_ehsize as usize != ehdr_size {
+            return Err(Error::InvalidElfHeaderSize);
+        }
+        Ok(())
+    }
+}
+
+/// Options for [`Elf::load_with_options`](struct.Elf.html#method.load_with_options).
+///
+/// The default options give the same behavior as [`KernelLoader::load`].
+///
+/// [`KernelLoader::load`]: ../trait.KernelLoader.html#tymethod.load
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Address above which no segment may end. Loading fails with `InvalidKernelEndAddress`
+    /// when a segment would cross it, before that segment is copied to guest memory.
+    pub highest_kernel_end: Option<GuestAddress>,
+    /// Check that the entry point, moved by `kernel_offset`, is within the guest memory range
+    /// of a loaded segment. Loading fails with `EntryNotInLoadedSegment` otherwise, before
+    /// anything is written to guest memory.
+    pub check_entry: bool,
+    /// Start address and size of the guest memory range all the segments must be within,
+    /// including their BSS. Loading fails with `SegmentOutsideRegion` when a segment isn't,
+    /// before that segment is copied to guest memory.
+    pub region: Option<(GuestAddress, GuestUsize)>,
+    /// Zero out the whole guest memory span of the loaded segments, from the lowest segment
+    /// start to the highest segment end, before copying them. This clears the gaps between the
+    /// segments, e.g. for the guest memory contents to be deterministic for measured boot.
+    pub zero_span: bool,
+    /// Granularity, e.g. the 4 KiB page size, to round the end of every segment up to. The
+    /// rounding tail is zeroed like the BSS and accounted for in `kernel_end`, so no two segments
+    /// share a granule, e.g. for the guest to apply different permissions to each segment.
+    /// Loading fails with `InvalidSegmentGranularity` when it isn't a power of two, with
+    /// `OverlappingSegments` when two segments share a granule, and with
+    /// `InvalidProgramHeaderAddress` when a rounding tail runs past the end of guest memory.
+    pub segment_granularity: Option<GuestUsize>,
+    /// `e_machine` to accept instead of the x86 ones (`EM_X86_64` and `EM_386`), e.g. to load
+    /// the image of another architecture on purpose. Loading fails with
+    /// `UnsupportedArchitecture` when the image is for another machine, before anything is
+    /// copied to guest memory.
+    pub machine: Option<u16>,
+    /// Copy the data of the segments through a [`VolatileSlice`] of their guest memory
+    /// destination, e.g. for guest memory shared with another process, such as a vhost-user
+    /// backend, which may observe it concurrently. Every segment must then be within a single
+    /// guest memory region, loading fails with `ReadKernelImage` otherwise.
+    ///
+    /// [`VolatileSlice`]: https://docs.rs/vm-memory/latest/vm_memory/volatile_memory/struct.VolatileSlice.html
+    pub volatile_copy: bool,
+    /// Require the `PT_LOAD` segments to be sorted by ascending address (`p_paddr`, or
+    /// `p_vaddr` for position independent images), which the ELF specification requires but
+    /// the loader doesn't otherwise rely on. Loading fails with `UnsortedProgramHeaders` when a
+    /// segment starts below the previous one, before anything is copied to guest memory.
+    pub require_sorted: bool,
+}
+
+impl KernelLoader for Elf {
+    /// Loads a kernel from a vmlinux elf image into guest memory.
+    ///
+    /// By default, the kernel is loaded into guest memory at offset `phdr.p_paddr` specified
+    /// by the elf image. When used, `kernel_offset` specifies a fixed offset from `phdr.p_paddr`
+    /// at which to load the kernel. If `kernel_offset` is requested, the `pvh_entry_addr` field
+    /// of the result will not be populated.
+    ///
+    /// Both executable (`ET_EXEC`) and position independent (`ET_DYN`) images are supported.
+    /// Since all the segments are moved by `kernel_offset`, the returned `kernel_load` entry
+    /// point is `e_entry` moved by the same offset, whatever the type of the image.
+    ///
+    /// The segments of a position independent image are placed at `kernel_offset + p_vaddr`
+    /// instead, and its `R_X86_64_RELATIVE` (or `R_386_RELATIVE` for 32-bit images)
+    /// relocations are applied once the segments are loaded, with `kernel_offset` (0 if not
+    /// given) as base address. Any other relocation fails with `UnsupportedRelocation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `guest_mem`: [`GuestMemory`] to load the kernel in.
+    /// * `kernel_offset`: Offset to be added to default kernel load address in guest memory.
+    /// * `kernel_image` - Input vmlinux image.
+    /// * `highmem_start_address`: Address where high memory starts.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate vm_memory;
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::{Address, GuestAddress};
+    /// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+    /// let mem_size: usize = 0x1000000;
+    /// let himem_start = GuestAddress(0x0);
+    /// let kernel_addr = GuestAddress(0x200000);
+    /// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), mem_size)]).unwrap();
+    /// let mut kernel_image = vec![];
+    /// kernel_image.extend_from_slice(include_bytes!("test_elf.bin"));
+    /// elf::Elf::load(
+    ///     &gm,
+    ///     Some(kernel_addr),
+    ///     &mut Cursor::new(&kernel_image),
+    ///     Some(himem_start),
+    /// )
+    /// .unwrap();
+    /// ```
+    ///
+    /// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+    fn load<F, M: GuestMemory>(
+        guest_mem: &M,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+        highmem_start_address: Option<GuestAddress>,
+    ) -> Result<KernelLoaderResult>
+    where
+        F: Read + Seek,
+    {
+        Self::load_with_options(
+            guest_mem,
+            kernel_offset,
+            kernel_image,
+            highmem_start_address,
+            &LoadOptions::default(),
+        )
+    }
+}
+
+impl Elf {
+    /// Loads a kernel from a vmlinux elf image into guest memory, with extra `options`.
+    ///
+    /// See [`load`](struct.Elf.html#method.load) for the meaning of the other arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate vm_memory;
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use linux_loader::loader::elf::{Elf, LoadOptions};
+    /// # use vm_memory::GuestAddress;
+    /// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+    /// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x100_0000)]).unwrap();
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// let options = LoadOptions {
+    ///     highest_kernel_end: Some(GuestAddress(0x40_0000)),
+    ///     ..Default::default()
+    /// };
+    /// Elf::load_with_options(&gm, None, &mut Cursor::new(&kernel_image), None, &options).unwrap();
+    /// ```
+    pub fn load_with_options<F, M: GuestMemory>(
+        guest_mem: &M,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+        highmem_start_address: Option<GuestAddress>,
+        options: &LoadOptions,
+    ) -> Result<KernelLoaderResult>
+    where
+        F: Read + Seek,
+    {
+        Self::load_segments(
+            guest_mem,
+            kernel_offset,
+            kernel_image,
+            highmem_start_address,
+            options,
+            |_| true,
+            |index, mem_offset, phdr, kernel_image| {
+                if options.volatile_copy {
+                    Self::read_segment_volatile(guest_mem, index, mem_offset, phdr, kernel_image)
+                } else {
+                    Self::read_segment(guest_mem, index, mem_offset, phdr, kernel_image)
+                }
+            },
+        )
+    }
+
+    /// Loads a kernel from a vmlinux elf image already held in memory into guest memory.
+    ///
+    /// Behaves like [`load`](struct.Elf.html#method.load), but the segments are copied to guest
+    /// memory straight from `kernel_image` instead of being read through a [`Read`] + [`Seek`]
+    /// reader, which saves a copy when the image is e.g. memory mapped. The (small) headers are
+    /// still parsed from an in-memory reader over `kernel_image`. Segment data which is not
+    /// entirely in `kernel_image` is reported like with `load`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate vm_memory;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::GuestAddress;
+    /// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+    /// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x100_0000)]).unwrap();
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// let loader_result =
+    ///     elf::Elf::load_from_slice(&gm, Some(GuestAddress(0x20_0000)), kernel_image, None)
+    ///         .unwrap();
+    /// assert_eq!(loader_result.kernel_load, GuestAddress(0x20_0400));
+    /// ```
+    ///
+    /// [`Read`]: https://doc.rust-lang.org/std/io/trait.Read.html
+    /// [`Seek`]: https://doc.rust-lang.org/std/io/trait.Seek.html
+    pub fn load_from_slice<M: GuestMemory>(
+        guest_mem: &M,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &[u8],
+        highmem_start_address: Option<GuestAddress>,
+    ) -> Result<KernelLoaderResult> {
+        Self::load_segments(
+            guest_mem,
+            kernel_offset,
+            &mut Cursor::new(kernel_image),
+            highmem_start_address,
+            &LoadOptions::default(),
+            |_| true,
+            |index, mem_offset, phdr, _| {
+                let segment = usize::try_from(phdr.p_offset)
+                    .ok()
+                    .and_then(|start| {
+                        let end = start.checked_add(usize::try_from(phdr.p_filesz).ok()?)?;
+                        kernel_image.get(start..end)
+                    })
+                    .ok_or(Error::SegmentPastEof { index })?;
+                // Copy straight into the guest memory region when the segment doesn't
+                // straddle regions, `write_slice` then handles the other cases.
+                match guest_mem.get_slice(mem_offset, segment.len()) {
+                    Ok(guest_slice) => guest_slice.copy_from(segment),
+                    Err(_) => guest_mem
+                        .write_slice(segment, mem_offset)
+                        .map_err(|source| Error::ReadKernelImage { index, source })?,
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Loads a kernel from a vmlinux elf image into a single region of guest memory.
+    ///
+    /// Behaves like [`load`](struct.Elf.html#method.load), but fails with `SegmentOutsideRegion`
+    /// when a segment is not entirely within `region`, so that the kernel doesn't straddle
+    /// several regions. `region` is usually looked up in `guest_mem`, e.g. with
+    /// [`GuestMemory::find_region`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate vm_memory;
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::{GuestAddress, GuestMemory};
+    /// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+    /// let gm = GuestMemoryMmap::from_ranges(&[
+    ///     (GuestAddress(0x0), 0x10_0000),
+    ///     (GuestAddress(0x20_0000), 0x10_0000),
+    /// ])
+    /// .unwrap();
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// let kernel_addr = GuestAddress(0x20_0000);
+    /// let region = gm.find_region(kernel_addr).unwrap();
+    /// let loader_result = elf::Elf::load_into_region(
+    ///     &gm,
+    ///     region,
+    ///     Some(kernel_addr),
+    ///     &mut Cursor::new(&kernel_image),
+    ///     None,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(loader_result.kernel_end, 0x20_042e);
+    /// ```
+    ///
+    /// [`GuestMemory::find_region`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html#tymethod.find_region
+    pub fn load_into_region<F, M: GuestMemory>(
+        guest_mem: &M,
+        region: &M::R,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+        highmem_start_address: Option<GuestAddress>,
+    ) -> Result<KernelLoaderResult>
+    where
+        F: Read + Seek,
+    {
+        let options = LoadOptions {
+            region: Some((region.start_addr(), region.len())),
+            ..Default::default()
+        };
+        Self::load_with_options(
+            guest_mem,
+            kernel_offset,
+            kernel_image,
+            highmem_start_address,
+            &options,
+        )
+    }
+
+    /// Loads a kernel from a vmlinux elf image into guest memory, skipping the `PT_LOAD`
+    /// segments for which `filter` returns `false`.
+    ///
+    /// The skipped segments are neither copied to guest memory nor accounted for in
+    /// `kernel_end`. See [`load`](struct.Elf.html#method.load) for the meaning of the other
+    /// arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate vm_memory;
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::GuestAddress;
+    /// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+    /// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x100_0000)]).unwrap();
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// // Only load the segments below 0x400.
+    /// let loader_result = elf::Elf::load_filtered(
+    ///     &gm,
+    ///     None,
+    ///     &mut Cursor::new(&kernel_image),
+    ///     None,
+    ///     |phdr| phdr.p_paddr < 0x400,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(loader_result.kernel_end, 0x1d);
+    /// ```
+    pub fn load_filtered<F, M, P>(
+        guest_mem: &M,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+        highmem_start_address: Option<GuestAddress>,
+        filter: P,
+    ) -> Result<KernelLoaderResult>
+    where
+        F: Read + Seek,
+        M: GuestMemory,
+        P: FnMut(&elf::Elf64_Phdr) -> bool,
+    {
+        Self::load_segments(
+            guest_mem,
+            kernel_offset,
+            kernel_image,
+            highmem_start_address,
+            &LoadOptions::default(),
+            filter,
+            |index, mem_offset, phdr, kernel_image| {
+                Self::read_segment(guest_mem, index, mem_offset, phdr, kernel_image)
+            },
+        )
+    }
+
+    /// Loads a kernel from a vmlinux elf image into guest memory, passing the data of every
+    /// segment through `transform` before it is written to guest memory.
+    ///
+    /// `transform` gets the program header and the bytes of the file backed part of each
+    /// loaded segment, which it may modify in place, e.g. to decrypt them. Each segment is read
+    /// into a temporary buffer first, so this is slower than [`load`](struct.Elf.html#method.load),
+    /// see there for the meaning of the other arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate vm_memory;
+    /// # use std::io::Cursor;
+    /// # use linux_loader::loader::*;
+    /// # use vm_memory::GuestAddress;
+    /// # type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+    /// let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x100_0000)]).unwrap();
+    /// let kernel_image = include_bytes!("test_elf.bin");
+    /// let loader_result = elf::Elf::load_transform(
+    ///     &gm,
+    ///     None,
+    ///     &mut Cursor::new(&kernel_image),
+    ///     None,
+    ///     |_phdr, data| data.iter_mut().for_each(|b| *b ^= 0x5a),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(loader_result.kernel_end, 0x42e);
+    /// ```
+    pub fn load_transform<F, M, T>(
+        guest_mem: &M,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+        highmem_start_address: Option<GuestAddress>,
+        mut transform: T,
+    ) -> Result<KernelLoaderResult>
+    where
+        F: Read + Seek,
+        M: GuestMemory,
+        T: FnMut(&elf::Elf64_Phdr, &mut [u8]),
+    {
+        Self::load_segments(
+            guest_mem,
+            kernel_offset,
+            kernel_image,
+            highmem_start_address,
+            &LoadOptions::default(),
+            |_| true,
+            |index, mem_offset, phdr, kernel_image| {
+                Self::read_segment_transformed(
+                    guest_mem,
+                    index,
+                    mem_offset,
+                    phdr,
+                    kernel_image,
+                    &mut transform,
+                )
+            },
+        )
+    }
+
+    /// Copies the file backed part of a segment from `kernel_image` to guest memory.
+    fn read_segment<F, M>(
+        guest_mem: &M,
+        index: usize,
+        mem_offset: GuestAddress,
+        phdr: &elf::Elf64_Phdr,
+        kernel_image: &mut F,
+    ) -> std::result::Result<(), Error>
+    where
+        F: Read + Seek,
+        M: GuestMemory,
+    {
+        kernel_image
+            .seek(SeekFrom::Start(phdr.p_offset))
+            .map_err(|source| Error::SeekKernelStart { index, source })?;
+        // `usize` may be narrower than the segment size on 32-bit hosts.
+        let count = usize::try_from(phdr.p_filesz).map_err(|_| Error::Overflow)?;
+        guest_mem
+            .read_exact_from(mem_offset, kernel_image, count)
+            .map_err(|source| Self::segment_read_error(index, phdr, kernel_image, source))?;
+        Ok(())
+    }
+
+    /// Copies the file backed part of a segment from `kernel_image` to guest memory, through a
+    /// volatile slice of its destination.
+    fn read_segment_volatile<F, M>(
+        guest_mem: &M,
+        index: usize,
+        mem_offset: GuestAddress,
+        phdr: &elf::Elf64_Phdr,
+        kernel_image: &mut F,
+    ) -> std::result::Result<(), Error>
+    where
+        F: Read + Seek,
+        M: GuestMemory,
+    {
+        // Size of the chunks in which the segment is read and copied to guest memory.
+        const CHUNK_SIZE: usize = 0x1_0000;
+
+        kernel_image
+            .seek(SeekFrom::Start(phdr.p_offset))
+            .map_err(|source| Error::SeekKernelStart { index, source })?;
+        let count = usize::try_from(phdr.p_filesz).map_err(|_| Error::Overflow)?;
+        let slice = guest_mem
+            .get_slice(mem_offset, count)
+            .map_err(|source| Error::ReadKernelImage { index, source })?;
+
+        let mut chunk = vec![0u8; std::cmp::min(CHUNK_SIZE, count)];
+        let mut offset = 0;
+        while offset < count {
+            let len = std::cmp::min(CHUNK_SIZE, count - offset);
+            kernel_image.read_exact(&mut chunk[..len]).map_err(|e| {
+                Self::segment_read_error(index, phdr, kernel_image, GuestMemoryError::IOError(e))
+            })?;
+            // Can't fail, `offset` is below the size of the slice.
+            slice
+                .offset(offset)
+                .map_err(|_| Error::Overflow)?
+                .copy_from(&chunk[..len]);
+            offset += len;
+        }
+        Ok(())
+    }
+
+    /// Reads the file backed part of a segment from `kernel_image`, passes it to `transform`
+    /// and copies the result to guest memory.
+    fn read_segment_transformed<F, M, T>(
+        guest_mem: &M,
+        index: usize,
+        mem_offset: GuestAddress,
+        phdr: &elf::Elf64_Phdr,
+        kernel_image: &mut F,
+        transform: &mut T,
+    ) -> std::result::Result<(), Error>
+    where
+        F: Read + Seek,
+        M: GuestMemory,
+        T: FnMut(&elf::Elf64_Phdr, &mut [u8]),
+    {
+        kernel_image
+            .seek(SeekFrom::Start(phdr.p_offset))
+            .map_err(|source| Error::SeekKernelStart { index, source })?;
+        let count = usize::try_from(phdr.p_filesz).map_err(|_| Error::Overflow)?;
+        let mut segment = vec![0u8; count];
+        kernel_image.read_exact(&mut segment).map_err(|e| {
+            Self::segment_read_error(index, phdr, kernel_image, GuestMemoryError::IOError(e))
+        })?;
+
+        transform(phdr, &mut segment);
+
+        guest_mem
+            .write_slice(&segment, mem_offset)
+            .map_err(|source| Error::ReadKernelImage { index, source })
+    }
+
+    /// Tells a truncated image apart from other failures to read a segment, e.g. of guest
+    /// memory.
+    fn segment_read_error<F>(
+        index: usize,
+        phdr: &elf::Elf64_Phdr,
+        kernel_image: &mut F,
+        source: GuestMemoryError,
+    ) -> Error
+    where
+        F: Seek,
+    {
+        match kernel_image.seek(SeekFrom::End(0)) {
+            Ok(image_size) if image_size < phdr.p_offset.saturating_add(phdr.p_filesz) => {
+                Error::TruncatedSegment {
+                    index,
+                    expected: phdr.p_filesz,
+                    got: image_size.saturating_sub(phdr.p_offset),
+                }
+            }
+            _ => Error::ReadKernelImage { index, source },
+        }
+    }
+
+    /// Validates an elf image and collects the segments to load, see [`parse_elf`].
+    ///
+    /// The PVH note is only looked up when loading at the default kernel load address, i.e.
+    /// without `kernel_offset`.
+    fn parse_image<F>(
+        kernel_offset: Option<GuestAddress>,
+        machine: Option<u16>,
+        kernel_image: &mut F,
+    ) -> Result<ParsedElf>
+    where
+        F: Read + Seek,
+    {
+        let ehdr = Self::read_header(kernel_image)?;
+        Self::check_machine(&ehdr, machine)?;
+        // Without program headers there is nothing to load, whatever `e_phoff` says.
+        if ehdr.e_phnum == 0 {
+            return Err(Error::NoLoadableSegments.into());
+        }
+        let image_size = Self::check_program_headers(&ehdr, kernel_image)?;
+
+        let mut parsed = ParsedElf {
+            entry: GuestAddress(ehdr.e_entry),
+            kernel_end: 0,
+            segments: vec![],
+            pvh_boot_cap: PvhBootCapability::default(),
+            ehdr,
+            dynamic_phdr: None,
+            image_size,
+        };
+        // The entry point is moved along with the segments.
+        if let Some(k_offset) = kernel_offset {
+            k_offset
+                .raw_value()
+                .checked_add(ehdr.e_entry)
+                .ok_or(Error::Overflow)?;
+        }
+        // Guest memory ranges of the segments, segments may come in any order.
+        let mut loaded_ranges: Vec<(u64, u64)> = vec![];
+        // The program headers are read one at a time, the notes are read in between.
+        for index in 0..ehdr.e_phnum {
+            let phdr = Self::read_program_header_at(&ehdr, index, kernel_image)?;
+            if phdr.p_type == elf::PT_DYNAMIC {
+                parsed.dynamic_phdr = Some(phdr);
+            }
+            // Nothing would resolve the dynamic symbols of the image, e.g. a userspace binary.
+            if phdr.p_type == elf::PT_INTERP {
+                return Err(Error::DynamicLinkingUnsupported.into());
+            }
+            // A `PT_LOAD` segment without any data in the file may still need to be zeroed
+            // out (pure BSS segment), only the empty ones are skipped.
+            if phdr.p_type != elf::PT_LOAD || (phdr.p_filesz == 0 && phdr.p_memsz == 0) {
+                if phdr.p_type == elf::PT_NOTE {
+                    // The PVH boot protocol currently requires that the kernel is loaded at
+                    // the default kernel load address in guest memory (specified at kernel
+                    // build time by the value of CONFIG_PHYSICAL_START). Therefore, only
+                    // attempt to use PVH if an offset from the default load address has not
+                    // been requested using the kernel_offset parameter.
+                    if let Some(_offset) = kernel_offset {
+                        parsed.pvh_boot_cap = PvhBootCapability::PvhEntryIgnored;
+                    } else {
+                        // If kernel_offset is not requested, check if PVH entry point is present
+                        parsed.pvh_boot_cap = parse_elf_note(&ehdr, &phdr, kernel_image)?;
+                    }
+                }
+                continue;
+            }
+
+            let index = usize::from(index);
+            let (mem_offset, mem_end) = Self::check_segment(
+                kernel_offset,
+                &ehdr,
+                index,
+                &phdr,
+                image_size,
+                None,
+                &loaded_ranges,
+            )?;
+            loaded_ranges.push((mem_offset.raw_value(), mem_end));
+
+            let segment_end = Self::segment_address(&ehdr, &phdr)
+                .checked_add(phdr.p_memsz)
+                .ok_or(KernelLoaderError::MemoryOverflow)?;
+            parsed.kernel_end = std::cmp::max(parsed.kernel_end, segment_end);
+            parsed.segments.push((index, phdr));
+        }
+
+        // Nothing would be loaded at the entry point.
+        if parsed.segments.is_empty() {
+            return Err(Error::NoLoadableSegments.into());
+        }
+
+        Ok(parsed)
+    }
+
+    /// Checks a `PT_LOAD` segment of an image of `image_size` bytes against the guest memory
+    /// ranges of the segments checked before it, `loaded_ranges`.
+    ///
+    /// Returns the guest memory range the segment is loaded to, with its end rounded up to
+    /// `granularity`.
+    fn check_segment(
+        kernel_offset: Option<GuestAddress>,
+        ehdr: &elf::Elf64_Ehdr,
+        index: usize,
+        phdr: &elf::Elf64_Phdr,
+        image_size: u64,
+        granularity: Option<GuestUsize>,
+        loaded_ranges: &[(u64, u64)],
+    ) -> Result<(GuestAddress, u64)> {
+        let mem_offset = Self::segment_guest_address(kernel_offset, ehdr, phdr)?;
+
+        // A misaligned `kernel_offset` would otherwise only show up as an early guest crash.
+        if phdr.p_align > 1 && mem_offset.raw_value() % phdr.p_align != 0 {
+            return Err(Error::InvalidSegmentAlignment.into());
+        }
+
+        // Report a segment whose data is not entirely in the image upfront, rather than as a
+        // failed read halfway through the loading.
+        if phdr.p_filesz > 0 {
+            if phdr.p_offset >= image_size {
+                return Err(Error::SegmentPastEof { index }.into());
+            }
+            let available = image_size - phdr.p_offset;
+            if phdr.p_filesz > available {
+                return Err(Error::TruncatedSegment {
+                    index,
+                    expected: phdr.p_filesz,
+                    got: available,
+                }
+                .into());
+            }
+        }
+
+        let mut mem_end = mem_offset
+            .raw_value()
+            .checked_add(phdr.p_memsz as GuestUsize)
+            .ok_or(KernelLoaderError::MemoryOverflow)?;
+        if let Some(granularity) = granularity {
+            mem_end = mem_end
+                .checked_next_multiple_of(granularity)
+                .ok_or(KernelLoaderError::MemoryOverflow)?;
+        }
+
+        // Overlapping segments would silently overwrite each other.
+        if loaded_ranges
+            .iter()
+            .any(|&(start, end)| mem_offset.raw_value() < end && start < mem_end)
+        {
+            return Err(Error::OverlappingSegments.into());
+        }
+
+        Ok((mem_offset, mem_end))
+    }
+
+    /// Loads the `PT_LOAD` segments of an elf image accepted by `filter`, using `copy_segment`
+    /// to copy the file backed part of each segment to guest memory.
+    ///
+    /// The headers and notes are all parsed before anything is written to guest memory.
+    fn load_segments<F, M, P, C>(
+        guest_mem: &M,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+        highmem_start_address: Option<GuestAddress>,
+        options: &LoadOptions,
+        mut filter: P,
+        mut copy_segment: C,
+    ) -> Result<KernelLoaderResult>
+    where
+        F: Read + Seek,
+        M: GuestMemory,
+        P: FnMut(&elf::Elf64_Phdr) -> bool,
+        C: FnMut(usize, GuestAddress, &elf::Elf64_Phdr, &mut F) -> std::result::Result<(), Error>,
+    {
+        let parsed = Self::parse_image(kernel_offset, options.machine, kernel_image)?;
+        let ehdr = parsed.ehdr;
+
+        // Sanity checks.
+        if let Some(addr) = highmem_start_address {
+            if ehdr.e_entry < addr.raw_value() {
+                return Err(Error::InvalidEntryAddress.into());
+            }
+        }
+
+        let mut loader_result = KernelLoaderResult {
+            kernel_load: match kernel_offset {
+                Some(k_offset) => GuestAddress(
+                    k_offset
+                        .raw_value()
+                        .checked_add(ehdr.e_entry)
+                        .ok_or(Error::Overflow)?,
+                ),
+                None => GuestAddress(ehdr.e_entry),
+            },
+            pvh_boot_cap: parsed.pvh_boot_cap,
+            ..Default::default()
+        };
+
+        if options
+            .segment_granularity
+            .is_some_and(|granularity| !granularity.is_power_of_two())
+        {
+            return Err(Error::InvalidSegmentGranularity.into());
+        }
+
+        // Guest memory ranges of the segments to load, segments may come in any order.
+        let mut loaded_ranges: Vec<(u64, u64)> = vec![];
+        let mut segments = vec![];
+
+        // All the segments are checked before any of them is written to guest memory. They are
+        // checked again here, as the segments filtered out don't count and the granularity
+        // may make them overlap.
+        for &(index, phdr) in parsed.segments.iter() {
+            if !filter(&phdr) {
+                continue;
+            }
+
+            let (mem_offset, kernel_end) = Self::check_segment(
+                kernel_offset,
+                &ehdr,
+                index,
+                &phdr,
+                parsed.image_size,
+                options.segment_granularity,
+                &loaded_ranges,
+            )?;
+            if options.require_sorted
+                && loaded_ranges
+                    .last()
+                    .is_some_and(|&(start, _)| mem_offset.raw_value() < start)
+            {
+                return Err(Error::UnsortedProgramHeaders.into());
+            }
+
+            // Report a segment running past the end of guest memory upfront, rather than as a
+            // failed guest memory access halfway through the loading. This includes its rounding
+            // tail, which is zeroed too. Segments are not empty, so `segment_end` is at least 1.
+            let segment_end = mem_offset
+                .raw_value()
+                .checked_add(phdr.p_filesz)
+                .ok_or(KernelLoaderError::MemoryOverflow)?
+                .max(kernel_end);
+            if segment_end - 1 > guest_mem.last_addr().raw_value() {
+                return Err(Error::InvalidProgramHeaderAddress.into());
+            }
+            if let Some(highest_kernel_end) = options.highest_kernel_end {
+                if kernel_end > highest_kernel_end.raw_value() {
+                    return Err(Error::InvalidKernelEndAddress.into());
+                }
+            }
+            if let Some((region_start, region_size)) = options.region {
+                // `kernel_end - region_start` can't underflow once `mem_offset` is in the region.
+                if mem_offset < region_start || kernel_end - region_start.raw_value() > region_size
+                {
+                    return Err(Error::SegmentOutsideRegion { index }.into());
+                }
+            }
+            loaded_ranges.push((mem_offset.raw_value(), kernel_end));
+            segments.push((index, phdr, mem_offset, kernel_end));
+        }
+
+        let entry = loader_result.kernel_load.raw_value();
+        if options.check_entry
+            && !loaded_ranges
+                .iter()
+                .any(|&(start, end)| (start..end).contains(&entry))
+        {
+            return Err(Error::EntryNotInLoadedSegment.into());
+        }
+
+        // Clear the gaps between the segments too, the segments themselves are fully written
+        // below.
+        if options.zero_span {
+            if let (Some(start), Some(end)) = (
+                loaded_ranges.iter().map(|&(start, _)| start).min(),
+                loaded_ranges.iter().map(|&(_, end)| end).max(),
+            ) {
+                let span_size = usize::try_from(end - start).map_err(|_| Error::Overflow)?;
+                guest_mem
+                    .read_exact_from(GuestAddress(start), &mut std::io::repeat(0), span_size)
+                    .map_err(Error::ZeroKernelSpan)?;
+            }
+        }
+
+        for (index, phdr, mem_offset, kernel_end) in segments {
+            if phdr.p_filesz > 0 {
+                copy_segment(index, mem_offset, &phdr, kernel_image)?;
+            }
+
+            // The part of the segment not backed by the file (the BSS), along with the
+            // rounding tail, must be zero-initialized, guest memory may hold stale data.
+            let bss_start = mem_offset
+                .checked_add(phdr.p_filesz)
+                .ok_or(KernelLoaderError::MemoryOverflow)?;
+            if kernel_end > bss_start.raw_value() {
+                let bss_size = usize::try_from(kernel_end - bss_start.raw_value())
+                    .map_err(|_| Error::Overflow)?;
+                guest_mem
+                    .read_exact_from(bss_start, &mut std::io::repeat(0), bss_size)
+                    .map_err(|source| Error::ZeroBss { index, source })?;
+            }
+
+            #[cfg(feature = "log")]
+            log::debug!(
+                "Loaded ELF segment {}: file offset {:#x}, guest address {:#x}, filesz {:#x}, memsz {:#x}",
+                index,
+                phdr.p_offset,
+                mem_offset.raw_value(),
+                phdr.p_filesz,
+                phdr.p_memsz
+            );
+
+            loader_result.kernel_end = std::cmp::max(loader_result.kernel_end, kernel_end);
+            loader_result.bytes_loaded = loader_result
+                .bytes_loaded
+                .checked_add(phdr.p_filesz)
+                .ok_or(KernelLoaderError::MemoryOverflow)?;
+        }
+
+        if let Some(dynamic_phdr) = parsed
+            .dynamic_phdr
+            .filter(|_| u32::from(ehdr.e_type) == elf::ET_DYN)
+        {
+            let base = kernel_offset.map_or(0, |k_offset| k_offset.raw_value());
+            Self::apply_relocations(
+                guest_mem,
+                &ehdr,
+                &dynamic_phdr,
+                base,
+                &loaded_ranges,
+                kernel_image,
+            )?;
+        }
+
+        // elf image has no setup_header which is defined for bzImage
+        loader_result.setup_header = None;
+
+        #[cfg(feature = "log")]
+        log::info!(
+            "Loaded ELF image: entry {:#x}, kernel end {:#x}",
+            loader_result.kernel_load.raw_value(),
+            loader_result.kernel_end
+        );
+
+        Ok(loader_result)
+    }
+}
+
+// Location of a relocation table, as given by the dynamic section.
+#[derive(Default)]
+struct RelocationTable {
+    // Virtual address of the table.
+    addr: Option<u64>,
+    // Size of the table, in bytes.
+    size: u64,
+    // Size of a table entry, in bytes.
+    entry_size: u64,
+}
+
+impl Elf {
+    /// Applies the relocations of a position independent image loaded at `base`.
+    ///
+    /// Only relocations to the guest memory ranges in `loaded_ranges` are applied, the others
+    /// target segments which were not loaded.
+    fn apply_relocations<F, M>(
+        guest_mem: &M,
+        ehdr: &elf::Elf64_Ehdr,
+        dynamic_phdr: &elf::Elf64_Phdr,
+        base: u64,
+        loaded_ranges: &[(u64, u64)],
+        kernel_image: &mut F,
+    ) -> std::result::Result<(), Error>
+    where
+        F: Read + Seek,
+        M: GuestMemory,
+    {
+        let (rel, rela) = Self::read_dynamic_section(ehdr, dynamic_phdr, kernel_image)?;
+        let phdrs = Self::read_program_headers(ehdr, kernel_image)?;
+        let is_elf32 = Self::is_elf32(ehdr);
+        let big_endian = ehdr.e_ident[elf::EI_DATA as usize] == elf::ELFDATA2MSB as u8;
+
+        // x86_64 images only use `Elf64_Rela` entries and x86 ones `Elf32_Rel` entries.
+        let (table, entry_size, relative_type) = match (u32::from(ehdr.e_machine), is_elf32) {
+            (elf::EM_X86_64, false) if rel.addr.is_none() => (
+                rela,
+                mem::size_of::<elf::Elf64_Rela>(),
+                elf::R_X86_64_RELATIVE,
+            ),
+            (elf::EM_386, true) if rela.addr.is_none() => {
+                (rel, mem::size_of::<elf::Elf32_Rel>(), elf::R_386_RELATIVE)
+            }
+            _ if rel.addr.is_none() && rela.addr.is_none() => return Ok(()),
+            _ => return Err(Error::UnsupportedRelocation),
+        };
+        let table_addr = match table.addr {
+            Some(table_addr) => table_addr,
+            None => return Ok(()),
+        };
+        if table.entry_size != entry_size as u64 {
+            return Err(Error::UnsupportedRelocation);
+        }
+        let table_offset = Self::file_offset(&phdrs, table_addr, table.size)
+            .ok_or(Error::InvalidRelocationTable)?;
+
+        kernel_image
+            .seek(SeekFrom::Start(table_offset))
+            .map_err(Error::SeekRelocations)?;
+        for _ in 0..table.size / table.entry_size {
+            let (r_offset, r_type, r_addend) = if is_elf32 {
+                let rel: elf::Elf32_Rel =
+                    read_elf_struct(kernel_image, &ehdr.e_ident).map_err(Error::ReadRelocations)?;
+                (u64::from(rel.r_offset), rel.r_info & 0xff, None)
+            } else {
+                let rela: elf::Elf64_Rela =
+                    read_elf_struct(kernel_image, &ehdr.e_ident).map_err(Error::ReadRelocations)?;
+                (rela.r_offset, rela.r_info as u32, Some(rela.r_addend))
+            };
+            // `R_X86_64_NONE` and `R_386_NONE` are both 0.
+            if r_type == elf::R_X86_64_NONE {
+                continue;
+            }
+            if r_type != relative_type {
+                return Err(Error::UnsupportedRelocation);
+            }
+
+            let addr = base.checked_add(r_offset).ok_or(Error::Overflow)?;
+            let size = if is_elf32 { 4 } else { 8 };
+            if !loaded_ranges
+                .iter()
+                .any(|&(start, end)| start <= addr && addr.saturating_add(size) <= end)
+            {
+                continue;
+            }
+            let addr = GuestAddress(addr);
+            match r_addend {
+                // The addend of `Elf32_Rel` relocations is the value already in place.
+                None => {
+                    let value: [u8; 4] =
+                        guest_mem.read_obj(addr).map_err(Error::ApplyRelocation)?;
+                    let value = if big_endian {
+                        u32::from_be_bytes(value)
+                    } else {
+                        u32::from_le_bytes(value)
+                    };
+                    let value = u32::try_from(base)
+                        .ok()
+                        .and_then(|base| value.checked_add(base))
+                        .ok_or(Error::Overflow)?;
+                    let value = if big_endian {
+                        value.to_be_bytes()
+                    } else {
+                        value.to_le_bytes()
+                    };
+                    guest_mem
+                        .write_obj(value, addr)
+                        .map_err(Error::ApplyRelocation)?;
+                }
+                Some(r_addend) => {
+                    let value = base.wrapping_add(r_addend as u64);
+                    let value = if big_endian {
+                        value.to_be_bytes()
+                    } else {
+                        value.to_le_bytes()
+                    };
+                    guest_mem
+                        .write_obj(value, addr)
+                        .map_err(Error::ApplyRelocation)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the `Elf32_Rel` and `Elf64_Rela` relocation tables described by the dynamic
+    /// section.
+    fn read_dynamic_section<F>(
+        ehdr: &elf::Elf64_Ehdr,
+        dynamic_phdr: &elf::Elf64_Phdr,
+        kernel_image: &mut F,
+    ) -> std::result::Result<(RelocationTable, RelocationTable), Error>
+    where
+        F: Read + Seek,
+    {
+        let entry_size = if Self::is_elf32(ehdr) {
+            mem::size_of::<elf::Elf32_Dyn>()
+        } else {
+            mem::size_of::<elf::Elf64_Dyn>()
+        };
+
+        kernel_image
+            .seek(SeekFrom::Start(dynamic_phdr.p_offset))
+            .map_err(Error::SeekDynamicSection)?;
+        let mut rel = RelocationTable::default();
+        let mut rela = RelocationTable::default();
+        for _ in 0..dynamic_phdr.p_filesz / entry_size as u64 {
+            let (d_tag, d_val) = if Self::is_elf32(ehdr) {
+                let dyn_entry: elf::Elf32_Dyn = read_elf_struct(kernel_image, &ehdr.e_ident)
+                    .map_err(Error::ReadDynamicSection)?;
+                (i64::from(dyn_entry.d_tag), u64::from(dyn_entry.d_val))
+            } else {
+                let dyn_entry: elf::Elf64_Dyn = read_elf_struct(kernel_image, &ehdr.e_ident)
+                    .map_err(Error::ReadDynamicSection)?;
+                (dyn_entry.d_tag, dyn_entry.d_val)
+            };
+            match u32::try_from(d_tag) {
+                Ok(elf::DT_NULL) => break,
+                Ok(elf::DT_REL) => rel.addr = Some(d_val),
+                Ok(elf::DT_RELSZ) => rel.size = d_val,
+                Ok(elf::DT_RELENT) => rel.entry_size = d_val,
+                Ok(elf::DT_RELA) => rela.addr = Some(d_val),
+                Ok(elf::DT_RELASZ) => rela.size = d_val,
+                Ok(elf::DT_RELAENT) => rela.entry_size = d_val,
+                Ok(elf::DT_RELR) => return Err(Error::UnsupportedRelocation),
+                _ => {}
+            }
+        }
+        Ok((rel, rela))
+    }
+
+    /// Returns the file offset of the `size` bytes at virtual address `vaddr`, if they are all
+    /// in the file backed part of a single `PT_LOAD` segment.
+    fn file_offset(phdrs: &[elf::Elf64_Phdr], vaddr: u64, size: u64) -> Option<u64> {
+        let end = vaddr.checked_add(size)?;
+        phdrs
+            .iter()
+            .find(|phdr| {
+                phdr.p_type == elf::PT_LOAD
+                    && phdr.p_vaddr <= vaddr
+                    && phdr
+                        .p_vaddr
+                        .checked_add(phdr.p_filesz)
+                        .is_some_and(|segment_end| end <= segment_end)
+            })
+            .and_then(|phdr| phdr.p_offset.checked_add(vaddr - phdr.p_vaddr))
+    }
+}
+
+// Size of string "Xen", including the terminating NULL.
+const PVH_NOTE_STR_SZ: usize = 4;
+
+/// Examines a supplied elf program header of type `PT_NOTE` to determine if it contains an entry
+/// of type `XEN_ELFNOTE_PHYS32_ENTRY` (0x12). Notes of this type encode a physical 32-bit entry
+/// point address into the kernel, which is used when launching guests in 32-bit (protected) mode
+/// with paging disabled, as described by the PVH boot protocol.
+/// Returns the encoded entry point address, or `None` if no `XEN_ELFNOTE_PHYS32_ENTRY` entries
+/// are found in the note header.
+fn parse_elf_note<F>(
+    ehdr: &elf::Elf64_Ehdr,
+    phdr: &elf::Elf64_Phdr,
+    kernel_image: &mut F,
+) -> Result<PvhBootCapability>
+where
+    F: Read + Seek,
+{
+    // Type of note header that encodes a 32-bit entry point address to boot a guest kernel using
+    // the PVH boot protocol.
+    const XEN_ELFNOTE_PHYS32_ENTRY: u32 = 18;
+
+    // Seek to the beginning of the note segment.
+    kernel_image
+        .seek(SeekFrom::Start(phdr.p_offset))
+        .map_err(Error::SeekNoteHeader)?;
+
+    // Now that the segment has been found, we must locate an ELF note with the correct type that
+    // encodes the PVH entry point if there is one.
+    let mut nhdr: elf::Elf64_Nhdr = Default::default();
+    let mut read_size: usize = 0;
+    let nhdr_sz = mem::size_of::<elf::Elf64_Nhdr>();
+
+    while read_size < phdr.p_filesz as usize {
+        nhdr = read_elf_struct(kernel_image, &ehdr.e_ident).map_err(Error::ReadNoteHeader)?;
+
+        // Check if the note header's name and type match the ones specified by the PVH ABI.
+        if nhdr.n_type == XEN_ELFNOTE_PHYS32_ENTRY && nhdr.n_namesz as usize == PVH_NOTE_STR_SZ {
+            let mut buf = [0u8; PVH_NOTE_STR_SZ];
+            kernel_image
+                .read_exact(&mut buf)
+                .map_err(Error::ReadNoteHeader)?;
+            if buf == [b'X', b'e', b'n', b'\0'] {
+                break;
+            }
+        }
+
+        // Skip the note header plus the size of its fields (with alignment).
+        let namesz_aligned = align_up(u64::from(nhdr.n_namesz), phdr.p_align)?;
+        let descsz_aligned = align_up(u64::from(nhdr.n_descsz), phdr.p_align)?;
+
+        // `namesz` and `descsz` are both `u32`s. We need to also verify for overflow, to be sure
+        // we do not lose information.
+        if namesz_aligned > u32::MAX.into() || descsz_aligned > u32::MAX.into() {
+            return Err(Error::Overflow.into());
+        }
+
+        read_size = read_size
+            .checked_add(nhdr_sz) // Skip the ELF_NOTE known sized fields.
+            // Safe to truncate or change the type to `usize` (4 or 8 bytes depending on the
+            // architecture 32/64 bits) since we validated that we do not lose information.
+            .and_then(|read_size| read_size.checked_add(namesz_aligned as usize))
+            .and_then(|read_size| read_size.checked_add(descsz_aligned as usize))
+            .ok_or(Error::Overflow)?;
+
+        kernel_image
+            // The conversion here does not truncate, since `read_size` is of `usize` type, which
+            // can be at maximum 8 bytes long.
+            .seek(SeekFrom::Start(phdr.p_offset + read_size as u64))
+            .map_err(Error::SeekNoteHeader)?;
+    }
+
+    if read_size >= phdr.p_filesz as usize {
+        // PVH ELF note not found, nothing else to do.
+        return Ok(PvhBootCapability::PvhEntryNotPresent);
+    }
+
+    // Otherwise the correct note type was found.
+    // The note header struct has already been read, so we can seek from the current position and
+    // just skip the name field contents.
+    kernel_image
+        .seek(SeekFrom::Current(
+            // Safe conversion since it is not losing data.
+            align_up(u64::from(nhdr.n_namesz), phdr.p_align)? as i64 - PVH_NOTE_STR_SZ as i64,
+        ))
+        .map_err(Error::SeekNoteHeader)?;
+
+    // The PVH entry point is a 32-bit address, so the descriptor field must be capable of storing
+    // all such addresses.
+    if (nhdr.n_descsz as usize) < mem::size_of::<u32>() {
+        return Err(Error::InvalidPvhNote.into());
+    }
+
+    let mut pvh_addr_bytes = [0; mem::size_of::<u32>()];
+
+    // Read 32-bit address stored in the PVH note descriptor field.
+    kernel_image
+        .read_exact(&mut pvh_addr_bytes)
+        .map_err(Error::ReadNoteHeader)?;
+
+    let pvh_addr = if ehdr.e_ident[elf::EI_DATA as usize] == elf::ELFDATA2MSB as u8 {
+        u32::from_be_bytes(pvh_addr_bytes)
+    } else {
+        u32::from_le_bytes(pvh_addr_bytes)
+    };
+    Ok(PvhBootCapability::PvhEntryPresent(GuestAddress(
+        pvh_addr.into(),
+    )))
+}
+
+/// Align address upwards. Adapted from x86_64 crate:
+/// https://docs.rs/x86_64/latest/x86_64/addr/fn.align_up.html
+///
+/// Returns the smallest x with alignment `align` so that x >= addr if the alignment is a power of
+/// 2, or an error otherwise.
+fn align_up(addr: u64, align: u64) -> result::Result<u64, Error> {
+    if !align.is_power_of_two() {
+        return Err(Error::Align);
+    }
+    let align_mask = align - 1;
+    if addr & align_mask == 0 {
+        Ok(addr) // already aligned
+    } else {
+        // Safe to unchecked add because this can be at maximum `2^64` - 1, which is not
+        // overflowing.
+        Ok((addr | align_mask) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::load_kernel_bytes;
+    use std::io::Cursor;
+    use vm_memory::{Address, GuestAddress};
+    type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+
+    const MEM_SIZE: u64 = 0x100_0000;
+
+    fn create_guest_mem() -> GuestMemoryMmap {
+        GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), (MEM_SIZE as usize))]).unwrap()
+    }
+
+    fn make_elf_bin() -> Vec<u8> {
+        let mut v = Vec::new();
+        v.extend_from_slice(include_bytes!("test_elf.bin"));
+        v
+    }
+
+    fn make_elfnote() -> Vec<u8> {
+        include_bytes!("test_elfnote.bin").to_vec()
+    }
+
+    fn make_dummy_elfnote() -> Vec<u8> {
+        include_bytes!("test_dummy_note.bin").to_vec()
+    }
+
+    fn make_invalid_pvh_note() -> Vec<u8> {
+        include_bytes!("test_invalid_pvh_note.bin").to_vec()
+    }
+
+    fn make_bad_align() -> Vec<u8> {
+        include_bytes!("test_bad_align.bin").to_vec()
+    }
+
+    fn make_elf32_bin() -> Vec<u8> {
+        include_bytes!("test_elf32.bin").to_vec()
+    }
+
+    // `_start` and `cmdline_buf` (global) and `local_counter` (local) at 0x400000, 0x400010 and
+    // 0x400020, from a small assembly file linked with `ld -N -Ttext=0x400000`.
+    fn make_symbols_bin() -> Vec<u8> {
+        include_bytes!("test_symbols.bin").to_vec()
+    }
+
+    // Reader failing its `fail_read`-th read or `fail_seek`-th seek, counting from 0, to reach
+    // the error paths `Cursor` never takes.
+    struct FaultyReader<R> {
+        inner: R,
+        reads: usize,
+        seeks: usize,
+        fail_read: Option<usize>,
+        fail_seek: Option<usize>,
+    }
+
+    impl<R> FaultyReader<R> {
+        fn new(inner: R) -> Self {
+            FaultyReader {
+                inner,
+                reads: 0,
+                seeks: 0,
+                fail_read: None,
+                fail_seek: None,
+            }
+        }
+
+        fn fail_read(mut self, n: usize) -> Self {
+            self.fail_read = Some(n);
+            self
+        }
+
+        fn fail_seek(mut self, n: usize) -> Self {
+            self.fail_seek = Some(n);
+            self
+        }
+    }
+
+    impl<R: Read> Read for FaultyReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.reads += 1;
+            if self.fail_read == Some(self.reads - 1) {
+                return Err(io::Error::other("injected read failure"));
+            }
+            self.inner.read(buf)
+        }
+    }
+
+    impl<R: Seek> Seek for FaultyReader<R> {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.seeks += 1;
+            if self.fail_seek == Some(self.seeks - 1) {
+                return Err(io::Error::other("injected seek failure"));
+            }
+            self.inner.seek(pos)
+        }
+    }
+
+    // Builds 64-bit little endian images from an Elf header and a list of segments, for the
+    // tests which need a specific layout. The program header table follows the Elf header and
+    // the data of the segments follows the table.
+    struct ElfBuilder {
+        ehdr: elf::Elf64_Ehdr,
+        segments: Vec<(elf::Elf64_Phdr, Vec<u8>)>,
+    }
+
+    impl ElfBuilder {
+        fn new() -> Self {
+            let mut ehdr = elf::Elf64_Ehdr::default();
+            ehdr.e_ident[..4].copy_from_slice(b"\x7fELF");
+            ehdr.e_ident[elf::EI_CLASS as usize] = elf::ELFCLASS64 as u8;
+            ehdr.e_ident[elf::EI_DATA as usize] = elf::ELFDATA2LSB as u8;
+            // EI_VERSION, EV_CURRENT.
+            ehdr.e_ident[6] = 1;
+            ehdr.e_type = elf::ET_EXEC as u16;
+            ehdr.e_machine = elf::EM_X86_64 as u16;
+            ehdr.e_version = 1;
+            ehdr.e_ehsize = mem::size_of::<elf::Elf64_Ehdr>() as u16;
+            ElfBuilder {
+                ehdr,
+                segments: vec![],
+            }
+        }
+
+        fn entry(mut self, e_entry: u64) -> Self {
+            self.ehdr.e_entry = e_entry;
+            self
+        }
+
+        // Adds a segment of type `p_type` loaded at `p_paddr`, holding `data` followed by
+        // `p_memsz - data.len()` bytes of BSS.
+        fn segment(mut self, p_type: u32, p_paddr: u64, data: &[u8], p_memsz: u64) -> Self {
+            let phdr = elf::Elf64_Phdr {
+                p_type,
+                p_flags: elf::PF_R,
+                p_vaddr: p_paddr,
+                p_paddr,
+                p_filesz: data.len() as u64,
+                p_memsz,
+                p_align: 1,
+                ..Default::default()
+            };
+            self.segments.push((phdr, data.to_vec()));
+            self
+        }
+
+        fn build(&self) -> Vec<u8> {
+            self.build_with(|_, _| {})
+        }
+
+        // Lays out the image, then lets `f` alter the headers before they are written out.
+        fn build_with<F>(&self, f: F) -> Vec<u8>
+        where
+            F: FnOnce(&mut elf::Elf64_Ehdr, &mut [elf::Elf64_Phdr]),
+        {
+            let mut ehdr = self.ehdr;
+            ehdr.e_phoff = mem::size_of::<elf::Elf64_Ehdr>() as u64;
+            ehdr.e_phentsize = mem::size_of::<elf::Elf64_Phdr>() as u16;
+            ehdr.e_phnum = self.segments.len() as u16;
+
+            let mut offset =
+                ehdr.e_phoff + (self.segments.len() * ehdr.e_phentsize as usize) as u64;
+            let mut phdrs: Vec<elf::Elf64_Phdr> = vec![];
+            for (phdr, data) in &self.segments {
+                phdrs.push(elf::Elf64_Phdr {
+                    p_offset: offset,
+                    ..*phdr
+                });
+                offset += data.len() as u64;
+            }
+            f(&mut ehdr, &mut phdrs);
+
+            let mut image = ehdr.as_slice().to_vec();
+            for phdr in &phdrs {
+                image.extend_from_slice(phdr.as_slice());
+            }
+            for (_, data) in &self.segments {
+                image.extend_from_slice(data);
+            }
+            image
+        }
+    }
+
+    #[test]
+    fn test_elf_builder() {
+        let gm = create_guest_mem();
+        let image = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x1000, &[0x90; 0x10], 0x20)
+            .segment(elf::PT_LOAD, 0x2000, &[0xaa; 0x8], 0x8)
+            .build();
+
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_load, GuestAddress(0x1000));
+        assert_eq!(loader_result.kernel_end, 0x2008);
+        assert_eq!(loader_result.bytes_loaded, 0x18);
+        let mut buf = [0u8; 0x8];
+        gm.read_slice(&mut buf, GuestAddress(0x2000)).unwrap();
+        assert_eq!(buf, [0xaa; 0x8]);
+    }
+
+    #[test]
+    fn test_load_elf() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let kernel_addr = GuestAddress(0x200000);
+        let mut highmem_start_address = GuestAddress(0x0);
+        let mut loader_result = Elf::load(
+            &gm,
+            Some(kernel_addr),
+            &mut Cursor::new(&image),
+            Some(highmem_start_address),
+        )
+        .unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x200400);
+        // Sum of the file sizes of the two PT_LOAD segments.
+        assert_eq!(loader_result.bytes_loaded, 0x1d + 0xe);
+
+        loader_result = Elf::load(&gm, Some(kernel_addr), &mut Cursor::new(&image), None).unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x200400);
+
+        loader_result = Elf::load(
+            &gm,
+            None,
+            &mut Cursor::new(&image),
+            Some(highmem_start_address),
+        )
+        .unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x400);
+
+        highmem_start_address = GuestAddress(0xa00000);
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidEntryAddress)),
+            Elf::load(
+                &gm,
+                None,
+                &mut Cursor::new(&image),
+                Some(highmem_start_address)
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn test_load_elf_bss() {
+        let gm = create_guest_mem();
+        let mut image = make_elf_bin();
+        // Grow p_memsz of the second (data) segment, loaded at 0x420 with
+        // 0xe bytes in the file, so it has a 0xf2 bytes long BSS.
+        image[0xa0..0xa8].copy_from_slice(&0x100u64.to_le_bytes());
+        let bss_start = GuestAddress(0x42e);
+        gm.write_slice(&[0xffu8; 0xf2], bss_start).unwrap();
+
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x520);
+        assert_eq!(loader_result.bytes_loaded, 0x1d + 0xe);
+
+        let mut bss = [0xffu8; 0xf2];
+        gm.read_slice(&mut bss, bss_start).unwrap();
+        assert!(bss.iter().all(|&b| b == 0));
+
+        // The BSS must fit in guest memory.
+        image[0xa0..0xa8].copy_from_slice(&MEM_SIZE.to_le_bytes());
+        assert!(matches!(
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None),
+            Err(KernelLoaderError::Elf(Error::InvalidProgramHeaderAddress))
+        ));
+        // Including when it lands in a hole of guest memory.
+        image[0xa0..0xa8].copy_from_slice(&0x1000u64.to_le_bytes());
+        let gm = GuestMemoryMmap::from_ranges(&[
+            (GuestAddress(0x0), 0x1000),
+            (GuestAddress(0x2000), 0x1000),
+        ])
+        .unwrap();
+        assert!(matches!(
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None),
+            Err(KernelLoaderError::Elf(Error::ZeroBss { index: 1, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_load_elf_pure_bss() {
+        let gm = create_guest_mem();
+        let mut image = make_elf_bin();
+        // Turn the second (data) segment, loaded at 0x420, into a 0x100 bytes
+        // long segment with no data in the file.
+        image[0x98..0xa0].copy_from_slice(&0u64.to_le_bytes());
+        image[0xa0..0xa8].copy_from_slice(&0x100u64.to_le_bytes());
+        let bss_start = GuestAddress(0x420);
+        gm.write_slice(&[0xffu8; 0x100], bss_start).unwrap();
+
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x520);
+        assert_eq!(loader_result.bytes_loaded, 0x1d);
+
+        let mut bss = [0xffu8; 0x100];
+        gm.read_slice(&mut bss, bss_start).unwrap();
+        assert!(bss.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_overlapping_segments() {
+        let gm = create_guest_mem();
+        // The second segment starts inside the BSS of the first one, which covers
+        // [0x0, 0x20).
+        let image = ElfBuilder::new()
+            .segment(elf::PT_LOAD, 0x0, &[0x90; 0x10], 0x20)
+            .segment(elf::PT_LOAD, 0x1c, &[0xaa; 0x10], 0x10)
+            .build();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::OverlappingSegments)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+        // The image is rejected without loading it too.
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::OverlappingSegments)),
+            parse_elf(&mut Cursor::new(&image)).err()
+        );
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::OverlappingSegments)),
+            Elf::kernel_size(&mut Cursor::new(&image)).err()
+        );
+
+        // Adjacent segments are fine: the first segment ends right where the
+        // second one starts.
+        let image = ElfBuilder::new()
+            .segment(elf::PT_LOAD, 0x0, &[0x90; 0x10], 0x20)
+            .segment(elf::PT_LOAD, 0x20, &[0xaa; 0x10], 0x10)
+            .build();
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x30);
+    }
+
+    #[test]
+    fn test_unsorted_segments() {
+        let gm = create_guest_mem();
+        // The highest segment comes first in the program header table.
+        let image = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x3000, &[0xaa; 0x10], 0x100)
+            .segment(elf::PT_LOAD, 0x1000, &[0x90; 0x10], 0x10)
+            .build();
+
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x3100);
+        let loader_result =
+            load_kernel_bytes::<Elf, _>(&gm, Some(GuestAddress(0x10_0000)), &image, None).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x10_3100);
+    }
+
+    #[test]
+    fn test_parse_segments() {
+        let image = make_elf_bin();
+        let segments = Elf::parse_segments(None, &mut Cursor::new(&image)).unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                SegmentInfo {
+                    guest_addr: GuestAddress(0x0),
+                    mem_size: 0x1d,
+                    flags: elf::PF_R | elf::PF_X,
+                },
+                SegmentInfo {
+                    guest_addr: GuestAddress(0x420),
+                    mem_size: 0xe,
+                    flags: elf::PF_R | elf::PF_W,
+                },
+            ]
+        );
+
+        let segments =
+            Elf::parse_segments(Some(GuestAddress(0x20_0000)), &mut Cursor::new(&image)).unwrap();
+        assert_eq!(segments[1].guest_addr, GuestAddress(0x20_0420));
+        // The image is validated like by `load`.
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::Overflow)),
+            Elf::parse_segments(Some(GuestAddress(u64::MAX)), &mut Cursor::new(&image)).err()
+        );
+    }
+
+    #[test]
+    fn test_segment_past_guest_memory() {
+        let image = make_elf_bin();
+        // The last segment ends at 0x42e.
+        let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x428)]).unwrap();
+        gm.write_slice(&[0xff; 0x1d], GuestAddress(0x0)).unwrap();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderAddress)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+        // Nothing was written, not even the segments which fit.
+        let mut buf = [0u8; 0x1d];
+        gm.read_slice(&mut buf, GuestAddress(0x0)).unwrap();
+        assert_eq!(buf, [0xff; 0x1d]);
+
+        // Only the BSS of the last segment is past the end of guest memory.
+        let bss = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x1000, &[0xaa; 0x10], 0x1000)
+            .build();
+        let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x1800)]).unwrap();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderAddress)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &bss, None).err()
+        );
+
+        // Right up to the end of guest memory.
+        let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x42e)]).unwrap();
+        assert_eq!(
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None)
+                .unwrap()
+                .kernel_end,
+            0x42e
+        );
+        let gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x2000)]).unwrap();
+        assert!(load_kernel_bytes::<Elf, _>(&gm, None, &bss, None).is_ok());
+    }
+
+    #[test]
+    fn test_load_filtered() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        gm.write_slice(&[0xffu8; 0xe], GuestAddress(0x420)).unwrap();
+
+        // Skip the second (data) segment.
+        let loader_result = Elf::load_filtered(&gm, None, &mut Cursor::new(&image), None, |phdr| {
+            phdr.p_flags & elf::PF_W == 0
+        })
+        .unwrap();
+        assert_eq!(loader_result.kernel_end, 0x1d);
+        assert_eq!(loader_result.bytes_loaded, 0x1d);
+
+        let mut buf = [0u8; 0x1d];
+        gm.read_slice(&mut buf, GuestAddress(0)).unwrap();
+        assert_eq!(buf, image[0xb0..0xcd]);
+        let mut buf = [0u8; 0xe];
+        gm.read_slice(&mut buf, GuestAddress(0x420)).unwrap();
+        assert!(buf.iter().all(|&b| b == 0xff));
+    }
+
+    #[test]
+    fn test_read_elf_struct() {
+        // The headers are parsed the same way with and without `safe-parse`.
+        let image = make_elf_bin();
+        let ehdr = Elf::read_header(&mut Cursor::new(&image)).unwrap();
+        let mut expected = elf::Elf64_Ehdr::default();
+        expected.as_mut_slice().copy_from_slice(&image[..0x40]);
+        assert_eq!(ehdr.as_slice(), expected.as_slice());
+        assert_eq!(ehdr.e_entry, 0x400);
+        assert_eq!(ehdr.e_phoff, 0x40);
+        assert_eq!(ehdr.e_phnum, 2);
+
+        let phdr: elf::Elf64_Phdr =
+            read_elf_struct(&mut Cursor::new(&image[0x78..]), &ehdr.e_ident).unwrap();
+        assert_eq!(phdr.p_type, elf::PT_LOAD);
+        assert_eq!(phdr.p_flags, elf::PF_R | elf::PF_W);
+        assert_eq!(phdr.p_offset, 0xd0);
+        assert_eq!(phdr.p_vaddr, 0x420);
+        assert_eq!(phdr.p_paddr, 0x420);
+        assert_eq!(phdr.p_filesz, 0xe);
+        assert_eq!(phdr.p_memsz, 0xe);
+        assert_eq!(phdr.p_align, 0x10);
+
+        let image = make_elf32_bin();
+        let ehdr = Elf::read_header(&mut Cursor::new(&image)).unwrap();
+        let ehdr32: elf::Elf32_Ehdr =
+            read_elf_struct(&mut Cursor::new(&image), &ehdr.e_ident).unwrap();
+        assert_eq!(
+            ehdr32.as_slice(),
+            &image[..mem::size_of::<elf::Elf32_Ehdr>()]
+        );
+        assert_eq!(ehdr.e_entry, u64::from(ehdr32.e_entry));
+
+        // Structures are read in the byte order of the image.
+        let mut be_ident = ehdr.e_ident;
+        be_ident[elf::EI_DATA as usize] = elf::ELFDATA2MSB as u8;
+        let nhdr: elf::Elf64_Nhdr = read_elf_struct(
+            &mut Cursor::new(&[0, 0, 0, 4, 0, 0, 0, 8, 0, 0, 0, 18]),
+            &be_ident,
+        )
+        .unwrap();
+        assert_eq!((nhdr.n_namesz, nhdr.n_descsz, nhdr.n_type), (4, 8, 18));
+    }
+
+    #[test]
+    fn test_read_program_header_at() {
+        for image in [make_elf_bin(), make_elf32_bin()] {
+            let mut kernel_image = Cursor::new(&image);
+            let ehdr = Elf::read_header(&mut kernel_image).unwrap();
+            let phdrs = Elf::read_program_headers(&ehdr, &mut kernel_image).unwrap();
+            assert_eq!(phdrs.len(), ehdr.e_phnum as usize);
+
+            // Read them backwards, each read seeks to its header.
+            for index in (0..ehdr.e_phnum).rev() {
+                let phdr = Elf::read_program_header_at(&ehdr, index, &mut kernel_image).unwrap();
+                assert_eq!(phdr.as_slice(), phdrs[index as usize].as_slice());
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_from_slice() {
+        for image in [make_elf_bin(), make_elf32_bin()] {
+            for kernel_offset in [None, Some(GuestAddress(0x20_0000))] {
+                let gm = create_guest_mem();
+                let gm_slice = create_guest_mem();
+                let expected =
+                    load_kernel_bytes::<Elf, _>(&gm, kernel_offset, &image, None).unwrap();
+                let loader_result =
+                    Elf::load_from_slice(&gm_slice, kernel_offset, &image, None).unwrap();
+                assert_eq!(loader_result, expected);
+
+                let start = kernel_offset.unwrap_or(GuestAddress(0));
+                let mut buf = [0u8; 0x1000];
+                let mut buf_slice = [0u8; 0x1000];
+                gm.read_slice(&mut buf, start).unwrap();
+                gm_slice.read_slice(&mut buf_slice, start).unwrap();
+                assert_eq!(buf, buf_slice);
+            }
+        }
+
+        // A segment straddling two guest memory regions.
+        let gm = GuestMemoryMmap::from_ranges(&[
+            (GuestAddress(0x0), 0x1000),
+            (GuestAddress(0x1000), 0x1000),
+        ])
+        .unwrap();
+        let data: Vec<u8> = (0..0x20).collect();
+        let image = ElfBuilder::new()
+            .entry(0xff0)
+            .segment(elf::PT_LOAD, 0xff0, &data, 0x20)
+            .build();
+        Elf::load_from_slice(&gm, None, &image, None).unwrap();
+        let mut buf = [0u8; 0x20];
+        gm.read_slice(&mut buf, GuestAddress(0xff0)).unwrap();
+        assert_eq!(&buf[..], &data[..]);
+
+        // The second segment starts where the image ends.
+        let gm = create_guest_mem();
+        let mut image = make_elf_bin();
+        let len = image.len() as u64;
+        image[0x80..0x88].copy_from_slice(&len.to_le_bytes());
+        assert!(matches!(
+            Elf::load_from_slice(&gm, None, &image, None),
+            Err(KernelLoaderError::Elf(Error::SegmentPastEof { index: 1 }))
+        ));
+    }
+
+    #[test]
+    fn test_load_elf32() {
+        let gm = create_guest_mem();
+        let image = make_elf32_bin();
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x400);
+        assert_eq!(loader_result.kernel_end, 0x40d);
+
+        // The only segment sits at offset 0x54 in the file.
+        let mut buf = [0u8; 0xd];
+        gm.read_slice(&mut buf, GuestAddress(0x400)).unwrap();
+        assert_eq!(buf, image[0x54..0x61]);
+
+        let loader_result =
+            load_kernel_bytes::<Elf, _>(&gm, Some(GuestAddress(0x200000)), &image, None).unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x200400);
+        assert_eq!(loader_result.kernel_end, 0x20040d);
+    }
+
+    #[test]
+    fn test_bad_elf_class() {
+        let gm = create_guest_mem();
+        let mut bad_image = make_elf_bin();
+        bad_image[0x4] = 3;
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidElfClass)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &bad_image, None).err()
+        );
+
+        // A 64-bit image parsed as 32-bit has the wrong program header size.
+        bad_image[0x4] = 1;
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderSize)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &bad_image, None).err()
+        );
+    }
+
+    #[test]
+    fn test_program_header_size() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let expected = load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+
+        // Move the program header table to the end of the image, with 8 bytes of padding
+        // after each header.
+        let phdr_size = mem::size_of::<elf::Elf64_Phdr>();
+        let mut padded_image = image.clone();
+        padded_image[0x20..0x28].copy_from_slice(&(image.len() as u64).to_le_bytes());
+        padded_image[0x36..0x38].copy_from_slice(&(phdr_size as u16 + 8).to_le_bytes());
+        for phdr in image[0x40..0x40 + 2 * phdr_size].chunks(phdr_size) {
+            padded_image.extend_from_slice(phdr);
+            padded_image.extend_from_slice(&[0xff; 8]);
+        }
+        assert_eq!(
+            load_kernel_bytes::<Elf, _>(&gm, None, &padded_image, None).unwrap(),
+            expected
+        );
+        assert_eq!(
+            Elf::parse_segments(None, &mut Cursor::new(&padded_image)).unwrap(),
+            Elf::parse_segments(None, &mut Cursor::new(&image)).unwrap()
+        );
+
+        // Entries smaller than the standard structure are rejected.
+        let mut bad_image = image;
+        bad_image[0x36..0x38].copy_from_slice(&(phdr_size as u16 - 8).to_le_bytes());
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderSize)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &bad_image, None).err()
+        );
+    }
+
+    #[test]
+    fn test_error_source() {
+        use std::error::Error as _;
+
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let err = match load_kernel_bytes::<Elf, _>(&gm, None, &image[..0x10], None) {
+            Err(KernelLoaderError::Elf(e)) => e,
+            res => panic!("unexpected result: {:?}", res),
+        };
+        assert!(matches!(err, Error::ReadElfHeader(_)));
+
+        let source = err.source().unwrap();
+        assert_eq!(
+            source.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(
+            err.to_string(),
+            format!("Kernel Loader: Unable to read elf header: {}", source)
+        );
+
+        // The source is not taken into account when comparing errors.
+        assert_eq!(
+            err,
+            Error::ReadElfHeader(io::Error::from(io::ErrorKind::Other))
+        );
+        assert!(Error::InvalidPvhNote.source().is_none());
+    }
+
+    #[test]
+    fn test_plan_load() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let kernel_addr = GuestAddress(0x20_0000);
+
+        for kernel_offset in [None, Some(kernel_addr)] {
+            let regions = Elf::plan_load(&mut Cursor::new(&image), kernel_offset).unwrap();
+            let segments = Elf::parse_segments(kernel_offset, &mut Cursor::new(&image)).unwrap();
+            assert_eq!(
+                regions,
+                segments
+                    .iter()
+                    .map(|segment| (segment.guest_addr, segment.mem_size))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        // The planned regions are exactly the ones written by `load`.
+        let regions = Elf::plan_load(&mut Cursor::new(&image), Some(kernel_addr)).unwrap();
+        assert_eq!(
+            regions,
+            vec![(kernel_addr, 0x1d), (kernel_addr.unchecked_add(0x420), 0xe)]
+        );
+        gm.write_slice(&[0xff; 0x1000], kernel_addr).unwrap();
+        let loader_result =
+            load_kernel_bytes::<Elf, _>(&gm, Some(kernel_addr), &image, None).unwrap();
+        let mut buf = [0u8; 0x1000];
+        gm.read_slice(&mut buf, kernel_addr).unwrap();
+        for (offset, byte) in buf.iter().enumerate() {
+            let addr = kernel_addr.unchecked_add(offset as u64);
+            let planned = regions
+                .iter()
+                .any(|&(start, size)| addr >= start && addr.raw_value() < start.raw_value() + size);
+            assert_eq!(planned, *byte != 0xff, "offset {:#x}", offset);
+        }
+        assert_eq!(
+            loader_result.kernel_end,
+            regions
+                .iter()
+                .map(|&(start, size)| start.raw_value() + size)
+                .max()
+                .unwrap()
+        );
+
+        // Images `load` rejects are rejected too.
+        let overlapping = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x1000, &[0xaa; 0x10], 0x100)
+            .segment(elf::PT_LOAD, 0x1080, &[0xbb; 0x10], 0x10)
+            .build();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::OverlappingSegments)),
+            Elf::plan_load(&mut Cursor::new(&overlapping), None).err()
+        );
+    }
+
+    #[test]
+    fn test_load_transform() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let kernel_addr = GuestAddress(0x20_0000);
+        let mut seen = vec![];
+
+        let loader_result = Elf::load_transform(
+            &gm,
+            Some(kernel_addr),
+            &mut Cursor::new(&image),
+            None,
+            |phdr, data| {
+                seen.push((phdr.p_offset, data.len()));
+                data.iter_mut().for_each(|b| *b ^= 0xff);
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            loader_result,
+            load_kernel_bytes::<Elf, _>(&gm, Some(kernel_addr), &image, None).unwrap()
+        );
+        assert_eq!(seen, vec![(0xb0, 0x1d), (0xd0, 0xe)]);
+
+        Elf::load_transform(
+            &gm,
+            Some(kernel_addr),
+            &mut Cursor::new(&image),
+            None,
+            |_, data| data.iter_mut().for_each(|b| *b ^= 0xff),
+        )
+        .unwrap();
+        for (file_offset, addr, size) in [(0xb0, 0x0, 0x1d), (0xd0, 0x420, 0xe)] {
+            let mut buf = vec![0u8; size];
+            gm.read_slice(&mut buf, kernel_addr.unchecked_add(addr))
+                .unwrap();
+            let expected: Vec<u8> = image[file_offset..file_offset + size]
+                .iter()
+                .map(|b| b ^ 0xff)
+                .collect();
+            assert_eq!(buf, expected);
+        }
+
+        // Truncated images are reported like with `load`.
+        assert!(matches!(
+            Elf::load_transform(&gm, None, &mut Cursor::new(&image[..0xc0]), None, |_, _| ()),
+            Err(KernelLoaderError::Elf(Error::TruncatedSegment {
+                index: 0,
+                expected: 0x1d,
+                got: 0x10
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_truncated_segment() {
+        let gm = create_guest_mem();
+        // The first segment is at [0xb0, 0xcd) in the image.
+        let mut image = make_elf_bin();
+        image.truncate(0xc0);
+        let err = match load_kernel_bytes::<Elf, _>(&gm, None, &image, None) {
+            Err(KernelLoaderError::Elf(e)) => e,
+            res => panic!("unexpected result: {:?}", res),
+        };
+        assert!(matches!(
+            err,
+            Error::TruncatedSegment {
+                index: 0,
+                expected: 0x1d,
+                got: 0x10
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Kernel Loader: Kernel image ends within a segment (program header 0): expected \
+             0x1d bytes, got 0x10"
+        );
+
+        // Nothing at all of the segment is in the image.
+        image.truncate(0xb0);
+        assert!(matches!(
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None),
+            Err(KernelLoaderError::Elf(Error::SegmentPastEof { index: 0 }))
+        ));
+    }
+
+    #[test]
+    fn test_public_elf_definitions() {
+        // The ELF definitions are available to callers as `linux_loader::elf`.
+        use crate::elf::{
+            Elf64_Ehdr, Elf64_Phdr, ELFMAG0, ELFMAG1, ELFMAG2, ELFMAG3, EM_X86_64, PT_LOAD,
+        };
+
+        let image = make_elf_bin();
+        let ehdr: Elf64_Ehdr = Elf::parse_header(&mut Cursor::new(&image)).unwrap();
+        assert_eq!(
+            ehdr.e_ident[..4],
+            [ELFMAG0 as u8, ELFMAG1, ELFMAG2, ELFMAG3]
+        );
+        assert_eq!(u32::from(ehdr.e_machine), EM_X86_64);
+        let parsed = parse_elf(&mut Cursor::new(&image)).unwrap();
+        assert!(parsed
+            .segments
+            .iter()
+            .all(|(_, phdr): &(usize, Elf64_Phdr)| phdr.p_type == PT_LOAD));
+    }
+
+    #[test]
+    fn test_segment_offset_past_eof() {
+        let gm = create_guest_mem();
+        let mut image = make_elf_bin();
+        // Point the data of the second segment way past the end of the image.
+        let offset = image.len() as u64 + 0x1000;
+        image[0x80..0x88].copy_from_slice(&offset.to_le_bytes());
+
+        assert!(matches!(
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None),
+            Err(KernelLoaderError::Elf(Error::SegmentPastEof { index: 1 }))
+        ));
+        assert!(matches!(
+            Elf::load_from_slice(&gm, None, &image, None),
+            Err(KernelLoaderError::Elf(Error::SegmentPastEof { index: 1 }))
+        ));
+
+        // The check happens before anything is written, the first segment isn't loaded either.
+        let mut buf = [0xffu8; 0x42e];
+        gm.read_slice(&mut buf, GuestAddress(0)).unwrap();
+        assert!(buf.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_error_segment_index() {
+        use std::error::Error as _;
+
+        let image = make_elf_bin();
+        // The first segment fits right below a hole in guest memory, the second
+        // one, at 0x420 in the image, is in the hole.
+        let gm = GuestMemoryMmap::from_ranges(&[
+            (GuestAddress(0x0), 0x400),
+            (GuestAddress(0x1000), 0x1000),
+        ])
+        .unwrap();
+        let err = match load_kernel_bytes::<Elf, _>(&gm, None, &image, None) {
+            Err(KernelLoaderError::Elf(e)) => e,
+            res => panic!("unexpected result: {:?}", res),
+        };
+        assert!(matches!(err, Error::ReadKernelImage { index: 1, .. }));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Kernel Loader: Unable to read kernel image (program header 1): {}",
+                err.source().unwrap()
+            )
+        );
+
+        // Errors are compared by their segment details, but not by their source.
+        assert_eq!(
+            Error::SegmentPastEof { index: 0 },
+            Error::SegmentPastEof { index: 0 }
+        );
+        assert_ne!(
+            Error::SegmentPastEof { index: 0 },
+            Error::SegmentPastEof { index: 1 }
+        );
+        let truncated = Error::TruncatedSegment {
+            index: 0,
+            expected: 0x1d,
+            got: 0x10,
+        };
+        assert_ne!(
+            truncated,
+            Error::TruncatedSegment {
+                index: 0,
+                expected: 0x1d,
+                got: 0x11,
+            }
+        );
+        let seek_error = |index, kind| Error::SeekKernelStart {
+            index,
+            source: io::Error::from(kind),
+        };
+        assert_eq!(
+            seek_error(1, io::ErrorKind::Other),
+            seek_error(1, io::ErrorKind::UnexpectedEof)
+        );
+        assert_ne!(
+            seek_error(0, io::ErrorKind::Other),
+            seek_error(1, io::ErrorKind::Other)
+        );
+    }
+
+    #[test]
+    fn test_elf_type() {
+        let gm = create_guest_mem();
+        let kernel_addr = GuestAddress(0x200000);
+        let mut image = make_elf_bin();
+
+        // ET_DYN images are relocated like ET_EXEC ones.
+        image[0x10] = elf::ET_DYN as u8;
+        let loader_result =
+            load_kernel_bytes::<Elf, _>(&gm, Some(kernel_addr), &image, None).unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x200400);
+        assert_eq!(loader_result.kernel_end, 0x20042e);
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x400);
+
+        // ET_REL
+        image[0x10] = 1;
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsupportedElfType)),
+            load_kernel_bytes::<Elf, _>(&gm, Some(kernel_addr), &image, None).err()
+        );
+    }
+
+    #[test]
+    fn test_load_volatile() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let kernel_addr = GuestAddress(0x20_0000);
+        gm.write_slice(&[0xff; 0x42e], kernel_addr).unwrap();
+        let options = LoadOptions {
+            volatile_copy: true,
+            ..Default::default()
+        };
 
-        Ok(loader_result)
-    }
-}
+        let loader_result = Elf::load_with_options(
+            &gm,
+            Some(kernel_addr),
+            &mut Cursor::new(&image),
+            None,
+            &options,
+        )
+        .unwrap();
+        let mut volatile = [0u8; 0x42e];
+        gm.read_slice(&mut volatile, kernel_addr).unwrap();
 
-// Size of string "Xen", including the terminating NULL.
-const PVH_NOTE_STR_SZ: usize = 4;
+        gm.write_slice(&[0xff; 0x42e], kernel_addr).unwrap();
+        assert_eq!(
+            loader_result,
+            load_kernel_bytes::<Elf, _>(&gm, Some(kernel_addr), &image, None).unwrap()
+        );
+        let mut expected = [0u8; 0x42e];
+        gm.read_slice(&mut expected, kernel_addr).unwrap();
+        assert_eq!(volatile, expected);
+        assert_eq!(volatile[..0x1d], image[0xb0..0xcd]);
+        assert_eq!(volatile[0x420..], image[0xd0..0xde]);
 
-/// Examines a supplied elf program header of type `PT_NOTE` to determine if it contains an entry
-/// of type `XEN_ELFNOTE_PHYS32_ENTRY` (0x12). Notes of this type encode a physical 32-bit entry
-/// point address into the kernel, which is used when launching guests in 32-bit (protected) mode
-/// with paging disabled, as described by the PVH boot protocol.
-/// Returns the encoded entry point address, or `None` if no `XEN_ELFNOTE_PHYS32_ENTRY` entries
-/// are found in the note header.
-fn parse_elf_note<F>(phdr: &elf::Elf64_Phdr, kernel_image: &mut F) -> Result<PvhBootCapability>
-where
-    F: Read + Seek,
-{
-    // Type of note header that encodes a 32-bit entry point address to boot a guest kernel using
-    // the PVH boot protocol.
-    const XEN_ELFNOTE_PHYS32_ENTRY: u32 = 18;
+        // Segments larger than a copy chunk.
+        let data: Vec<u8> = (0..0x2_0010u32).map(|i| i as u8).collect();
+        let big = ElfBuilder::new()
+            .entry(0x10_0000)
+            .segment(elf::PT_LOAD, 0x10_0000, &data, data.len() as u64)
+            .build();
+        Elf::load_with_options(&gm, None, &mut Cursor::new(&big), None, &options).unwrap();
+        let mut buf = vec![0u8; data.len()];
+        gm.read_slice(&mut buf, GuestAddress(0x10_0000)).unwrap();
+        assert_eq!(buf, data);
 
-    // Seek to the beginning of the note segment.
-    kernel_image
-        .seek(SeekFrom::Start(phdr.p_offset))
-        .map_err(|_| Error::SeekNoteHeader)?;
+        // A segment can't span several guest memory regions.
+        let gm = GuestMemoryMmap::from_ranges(&[
+            (GuestAddress(0x0), 0x10),
+            (GuestAddress(0x10), 0x1000),
+        ])
+        .unwrap();
+        assert!(matches!(
+            Elf::load_with_options(&gm, None, &mut Cursor::new(&image), None, &options),
+            Err(KernelLoaderError::Elf(Error::ReadKernelImage {
+                index: 0,
+                ..
+            }))
+        ));
+        assert!(load_kernel_bytes::<Elf, _>(&gm, None, &image, None).is_ok());
+    }
 
-    // Now that the segment has been found, we must locate an ELF note with the correct type that
-    // encodes the PVH entry point if there is one.
-    let mut nhdr: elf::Elf64_Nhdr = Default::default();
-    let mut read_size: usize = 0;
-    let nhdr_sz = mem::size_of::<elf::Elf64_Nhdr>();
+    #[test]
+    fn test_require_sorted() {
+        let gm = create_guest_mem();
+        let unsorted = ElfBuilder::new()
+            .entry(0x2000)
+            .segment(elf::PT_LOAD, 0x2000, &[0xaa; 0x10], 0x10)
+            .segment(elf::PT_LOAD, 0x1000, &[0xbb; 0x10], 0x10)
+            .build();
+        let strict = LoadOptions {
+            require_sorted: true,
+            ..Default::default()
+        };
 
-    while read_size < phdr.p_filesz as usize {
-        kernel_image
-            .read_exact(nhdr.as_mut_slice())
-            .map_err(|_| Error::ReadNoteHeader)?;
+        // Accepted by default.
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &unsorted, None).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x2010);
+        assert_eq!(gm.read_obj::<u8>(GuestAddress(0x1000)).unwrap(), 0xbb);
 
-        // Check if the note header's name and type match the ones specified by the PVH ABI.
-        if nhdr.n_type == XEN_ELFNOTE_PHYS32_ENTRY && nhdr.n_namesz as usize == PVH_NOTE_STR_SZ {
-            let mut buf = [0u8; PVH_NOTE_STR_SZ];
-            kernel_image
-                .read_exact(&mut buf)
-                .map_err(|_| Error::ReadNoteHeader)?;
-            if buf == [b'X', b'e', b'n', b'\0'] {
-                break;
-            }
-        }
+        gm.write_slice(&[0xff; 0x10], GuestAddress(0x2000)).unwrap();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsortedProgramHeaders)),
+            Elf::load_with_options(&gm, None, &mut Cursor::new(&unsorted), None, &strict).err()
+        );
+        // Nothing was loaded.
+        assert_eq!(gm.read_obj::<u8>(GuestAddress(0x2000)).unwrap(), 0xff);
 
-        // Skip the note header plus the size of its fields (with alignment).
-        let namesz_aligned = align_up(u64::from(nhdr.n_namesz), phdr.p_align)?;
-        let descsz_aligned = align_up(u64::from(nhdr.n_descsz), phdr.p_align)?;
+        let sorted = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x1000, &[0xaa; 0x10], 0x10)
+            .segment(elf::PT_LOAD, 0x2000, &[0xbb; 0x10], 0x10)
+            .build();
+        assert!(
+            Elf::load_with_options(&gm, None, &mut Cursor::new(&sorted), None, &strict).is_ok()
+        );
+        assert!(Elf::load_with_options(
+            &gm,
+            None,
+            &mut Cursor::new(&make_elf_bin()),
+            None,
+            &strict
+        )
+        .is_ok());
+    }
 
-        // `namesz` and `descsz` are both `u32`s. We need to also verify for overflow, to be sure
-        // we do not lose information.
-        if namesz_aligned > u32::MAX.into() || descsz_aligned > u32::MAX.into() {
-            return Err(Error::Overflow.into());
-        }
+    #[test]
+    fn test_elf_machine() {
+        const EM_AARCH64: u16 = 183;
 
-        read_size = read_size
-            .checked_add(nhdr_sz) // Skip the ELF_NOTE known sized fields.
-            // Safe to truncate or change the type to `usize` (4 or 8 bytes depending on the
-            // architecture 32/64 bits) since we validated that we do not lose information.
-            .and_then(|read_size| read_size.checked_add(namesz_aligned as usize))
-            .and_then(|read_size| read_size.checked_add(descsz_aligned as usize))
-            .ok_or(Error::Overflow)?;
+        let gm = create_guest_mem();
+        let mut image = make_elf_bin();
+        image[0x12..0x14].copy_from_slice(&EM_AARCH64.to_le_bytes());
+        gm.write_slice(&[0xff; 0x1d], GuestAddress(0x0)).unwrap();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsupportedArchitecture)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+        // Nothing was loaded.
+        let mut buf = [0u8; 0x1d];
+        gm.read_slice(&mut buf, GuestAddress(0x0)).unwrap();
+        assert_eq!(buf, [0xff; 0x1d]);
+        // The image is rejected without loading it too.
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsupportedArchitecture)),
+            parse_elf(&mut Cursor::new(&image)).err()
+        );
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsupportedArchitecture)),
+            Elf::plan_load(&mut Cursor::new(&image), None).err()
+        );
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsupportedArchitecture)),
+            segment_descriptors(&mut Cursor::new(&image)).err()
+        );
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsupportedArchitecture)),
+            Elf::kernel_size(&mut Cursor::new(&image)).err()
+        );
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsupportedArchitecture)),
+            Elf::parse_segments(None, &mut Cursor::new(&image)).err()
+        );
 
-        kernel_image
-            // The conversion here does not truncate, since `read_size` is of `usize` type, which
-            // can be at maximum 8 bytes long.
-            .seek(SeekFrom::Start(phdr.p_offset + read_size as u64))
-            .map_err(|_| Error::SeekNoteHeader)?;
-    }
+        // Loading it on purpose.
+        let options = LoadOptions {
+            machine: Some(EM_AARCH64),
+            ..Default::default()
+        };
+        let loader_result =
+            Elf::load_with_options(&gm, None, &mut Cursor::new(&image), None, &options).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x42e);
+        // Then native images aren't accepted anymore.
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsupportedArchitecture)),
+            Elf::load_with_options(&gm, None, &mut Cursor::new(&make_elf_bin()), None, &options)
+                .err()
+        );
 
-    if read_size >= phdr.p_filesz as usize {
-        // PVH ELF note not found, nothing else to do.
-        return Ok(PvhBootCapability::PvhEntryNotPresent);
+        // 32-bit images are native too.
+        assert!(load_kernel_bytes::<Elf, _>(&gm, None, &make_elf32_bin(), None).is_ok());
     }
 
-    // Otherwise the correct note type was found.
-    // The note header struct has already been read, so we can seek from the current position and
-    // just skip the name field contents.
-    kernel_image
-        .seek(SeekFrom::Current(
-            // Safe conversion since it is not losing data.
-            align_up(u64::from(nhdr.n_namesz), phdr.p_align)? as i64 - PVH_NOTE_STR_SZ as i64,
-        ))
-        .map_err(|_| Error::SeekNoteHeader)?;
+    #[test]
+    fn test_elf_version() {
+        let gm = create_guest_mem();
+        for (mut image, e_ehsize_offset) in [(make_elf_bin(), 0x34), (make_elf32_bin(), 0x28)] {
+            image[0x14..0x18].copy_from_slice(&2u32.to_le_bytes());
+            assert_eq!(
+                Some(KernelLoaderError::Elf(Error::UnsupportedElfVersion)),
+                load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+            );
+            image[0x14..0x18].copy_from_slice(&EV_CURRENT.to_le_bytes());
+            assert!(load_kernel_bytes::<Elf, _>(&gm, None, &image, None).is_ok());
 
-    // The PVH entry point is a 32-bit address, so the descriptor field must be capable of storing
-    // all such addresses.
-    if (nhdr.n_descsz as usize) < mem::size_of::<u32>() {
-        return Err(Error::InvalidPvhNote.into());
+            image[e_ehsize_offset] += 1;
+            assert_eq!(
+                Some(KernelLoaderError::Elf(Error::InvalidElfHeaderSize)),
+                load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+            );
+        }
     }
 
-    let mut pvh_addr_bytes = [0; mem::size_of::<u32>()];
+    #[test]
+    fn test_interp() {
+        let gm = create_guest_mem();
+        let image = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_INTERP, 0x0, b"/lib64/ld-linux-x86-64.so.2\0", 0x1c)
+            .segment(elf::PT_LOAD, 0x1000, &[0x90; 0x10], 0x10)
+            .build();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::DynamicLinkingUnsupported)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+        assert!(matches!(
+            parse_elf(&mut Cursor::new(&image)),
+            Err(KernelLoaderError::Elf(Error::DynamicLinkingUnsupported))
+        ));
+    }
 
-    // Read 32-bit address stored in the PVH note descriptor field.
-    kernel_image
-        .read_exact(&mut pvh_addr_bytes)
-        .map_err(|_| Error::ReadNoteHeader)?;
+    #[test]
+    fn test_load_pie() {
+        let gm = create_guest_mem();
+        let image = include_bytes!("test_pie.bin").to_vec();
 
-    Ok(PvhBootCapability::PvhEntryPresent(GuestAddress(
-        u32::from_le_bytes(pvh_addr_bytes).into(),
-    )))
-}
+        for base in [0x20_0000, 0x30_0000] {
+            let loader_result =
+                load_kernel_bytes::<Elf, _>(&gm, Some(GuestAddress(base)), &image, None).unwrap();
+            assert_eq!(loader_result.kernel_load.raw_value(), base + 0x178);
+            assert_eq!(loader_result.kernel_end, base + 0x288);
+            // `ptr` holds the relocated address of `_start`.
+            let ptr: u64 = gm.read_obj(GuestAddress(base + 0x280)).unwrap();
+            assert_eq!(ptr, base + 0x178);
+        }
 
-/// Align address upwards. Adapted from x86_64 crate:
-/// https://docs.rs/x86_64/latest/x86_64/addr/fn.align_up.html
-///
-/// Returns the smallest x with alignment `align` so that x >= addr if the alignment is a power of
-/// 2, or an error otherwise.
-fn align_up(addr: u64, align: u64) -> result::Result<u64, Error> {
-    if !align.is_power_of_two() {
-        return Err(Error::Align);
+        // Only `R_X86_64_RELATIVE` relocations are supported.
+        let mut image = image;
+        image[0x168] = 1;
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::UnsupportedRelocation)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
     }
-    let align_mask = align - 1;
-    if addr & align_mask == 0 {
-        Ok(addr) // already aligned
-    } else {
-        // Safe to unchecked add because this can be at maximum `2^64` - 1, which is not
-        // overflowing.
-        Ok((addr | align_mask) + 1)
+
+    #[test]
+    fn test_truncated_header() {
+        // Headers are read with `read_exact`, a short image must not yield a partially
+        // initialized header.
+        let gm = create_guest_mem();
+        for image in [make_elf_bin(), make_elf32_bin()] {
+            for len in [0, 0x10, 0x33] {
+                assert!(matches!(
+                    load_kernel_bytes::<Elf, _>(&gm, None, &image[..len], None),
+                    Err(KernelLoaderError::Elf(Error::ReadElfHeader(_)))
+                ));
+            }
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::Cursor;
-    use vm_memory::{Address, GuestAddress};
-    type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+    #[test]
+    fn test_kernel_size() {
+        // Segments are at [0x0, 0x1d) and [0x420, 0x42e).
+        let image = make_elf_bin();
+        assert_eq!(
+            Elf::kernel_size(&mut Cursor::new(&image)).unwrap(),
+            (GuestAddress(0x0), 0x42e)
+        );
 
-    const MEM_SIZE: u64 = 0x100_0000;
+        let image = make_elf32_bin();
+        assert_eq!(
+            Elf::kernel_size(&mut Cursor::new(&image)).unwrap(),
+            (GuestAddress(0x400), 0x40d)
+        );
 
-    fn create_guest_mem() -> GuestMemoryMmap {
-        GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), (MEM_SIZE as usize))]).unwrap()
-    }
+        // The only PT_LOAD segment maps the whole image.
+        let image = make_elfnote();
+        assert_eq!(
+            Elf::kernel_size(&mut Cursor::new(&image)).unwrap(),
+            (GuestAddress(0x40_0000), 0x40_027c)
+        );
 
-    fn make_elf_bin() -> Vec<u8> {
-        let mut v = Vec::new();
-        v.extend_from_slice(include_bytes!("test_elf.bin"));
-        v
+        let mut bad_image = make_elf_bin();
+        bad_image[0x1] = 0x33;
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidElfMagicNumber)),
+            Elf::kernel_size(&mut Cursor::new(&bad_image)).err()
+        );
     }
 
-    fn make_elfnote() -> Vec<u8> {
-        include_bytes!("test_elfnote.bin").to_vec()
-    }
+    #[test]
+    fn test_parse_sections() {
+        // null, .shstrtab, .text, .data and .note
+        let image = make_elf_bin();
+        let sections = Elf::parse_sections(&mut Cursor::new(&image)).unwrap();
+        assert_eq!(sections.len(), 5);
+        assert_eq!(sections[0].sh_type, 0);
+        // .text is loaded at the entry point.
+        assert_eq!(sections[2].sh_addr, 0x400);
+        assert_eq!(sections[2].sh_size, 0x1d);
 
-    fn make_dummy_elfnote() -> Vec<u8> {
-        include_bytes!("test_dummy_note.bin").to_vec()
-    }
+        // null, .text, .data and .shstrtab
+        let image = make_elf32_bin();
+        let sections = Elf::parse_sections(&mut Cursor::new(&image)).unwrap();
+        assert_eq!(sections.len(), 4);
+        assert_eq!(sections[1].sh_addr, 0x400);
 
-    fn make_invalid_pvh_note() -> Vec<u8> {
-        include_bytes!("test_invalid_pvh_note.bin").to_vec()
-    }
+        let mut bad_image = make_elf_bin();
+        bad_image[0x3a] = 0x20;
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidSectionHeaderSize)),
+            Elf::parse_sections(&mut Cursor::new(&bad_image)).err()
+        );
 
-    fn make_bad_align() -> Vec<u8> {
-        include_bytes!("test_bad_align.bin").to_vec()
+        let mut bad_image = make_elf_bin();
+        bad_image[0x3c] = 0x6;
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidSectionHeaderCount)),
+            Elf::parse_sections(&mut Cursor::new(&bad_image)).err()
+        );
     }
 
     #[test]
-    fn test_load_elf() {
+    fn test_injected_failures() {
         let gm = create_guest_mem();
         let image = make_elf_bin();
-        let kernel_addr = GuestAddress(0x200000);
-        let mut highmem_start_address = GuestAddress(0x0);
-        let mut loader_result = Elf::load(
-            &gm,
-            Some(kernel_addr),
-            &mut Cursor::new(&image),
-            Some(highmem_start_address),
-        )
-        .unwrap();
-        assert_eq!(loader_result.kernel_load.raw_value(), 0x200400);
+        let load = |mut kernel_image: FaultyReader<Cursor<&Vec<u8>>>| {
+            Elf::load(&gm, None, &mut kernel_image, None).err()
+        };
 
-        loader_result = Elf::load(&gm, Some(kernel_addr), &mut Cursor::new(&image), None).unwrap();
-        assert_eq!(loader_result.kernel_load.raw_value(), 0x200400);
+        // The ELF header (in two reads), the two program headers and the two segments are read
+        // in that order, each after seeking to it. The image size is looked up once.
+        let mut kernel_image = FaultyReader::new(Cursor::new(&image));
+        Elf::load(&gm, None, &mut kernel_image, None).unwrap();
+        assert_eq!((kernel_image.reads, kernel_image.seeks), (6, 7));
 
-        loader_result = Elf::load(
-            &gm,
-            None,
-            &mut Cursor::new(&image),
-            Some(highmem_start_address),
-        )
-        .unwrap();
-        assert_eq!(loader_result.kernel_load.raw_value(), 0x400);
+        for n in 0..6 {
+            let err = load(FaultyReader::new(Cursor::new(&image)).fail_read(n));
+            let expected = match n {
+                0 | 1 => matches!(err, Some(KernelLoaderError::Elf(Error::ReadElfHeader(_)))),
+                2 | 3 => matches!(
+                    err,
+                    Some(KernelLoaderError::Elf(Error::ReadProgramHeader(_)))
+                ),
+                4 => matches!(
+                    err,
+                    Some(KernelLoaderError::Elf(Error::ReadKernelImage {
+                        index: 0,
+                        ..
+                    }))
+                ),
+                _ => matches!(
+                    err,
+                    Some(KernelLoaderError::Elf(Error::ReadKernelImage {
+                        index: 1,
+                        ..
+                    }))
+                ),
+            };
+            assert!(expected, "read {}: {:?}", n, err);
+        }
 
-        highmem_start_address = GuestAddress(0xa00000);
+        for n in 0..7 {
+            let err = load(FaultyReader::new(Cursor::new(&image)).fail_seek(n));
+            let expected = match n {
+                0 | 1 => matches!(err, Some(KernelLoaderError::Elf(Error::SeekElfStart(_)))),
+                2 => matches!(err, Some(KernelLoaderError::Elf(Error::SeekElfEnd(_)))),
+                3 | 4 => matches!(
+                    err,
+                    Some(KernelLoaderError::Elf(Error::SeekProgramHeader(_)))
+                ),
+                5 => matches!(
+                    err,
+                    Some(KernelLoaderError::Elf(Error::SeekKernelStart {
+                        index: 0,
+                        ..
+                    }))
+                ),
+                _ => matches!(
+                    err,
+                    Some(KernelLoaderError::Elf(Error::SeekKernelStart {
+                        index: 1,
+                        ..
+                    }))
+                ),
+            };
+            assert!(expected, "seek {}: {:?}", n, err);
+        }
+
+        // The underlying error is kept.
+        let err = load(FaultyReader::new(Cursor::new(&image)).fail_read(4)).unwrap();
+        assert!(err.is_read_error());
         assert_eq!(
-            Some(KernelLoaderError::Elf(Error::InvalidEntryAddress)),
-            Elf::load(
-                &gm,
-                None,
-                &mut Cursor::new(&image),
-                Some(highmem_start_address)
-            )
-            .err()
+            err.to_string(),
+            "Kernel Loader: failed to load ELF kernel image"
+        );
+        assert_eq!(
+            std::error::Error::source(&err).unwrap().to_string(),
+            "Kernel Loader: Unable to read kernel image (program header 0): Guest memory error: injected read failure"
+        );
+    }
+
+    #[test]
+    fn test_resolve_symbol() {
+        let image = make_symbols_bin();
+        let resolve = |image: &[u8], name| Elf::resolve_symbol(&mut Cursor::new(image), name);
+        assert_eq!(resolve(&image, "_start"), Ok(Some(GuestAddress(0x40_0000))));
+        assert_eq!(
+            resolve(&image, "cmdline_buf"),
+            Ok(Some(GuestAddress(0x40_0010)))
+        );
+        assert_eq!(
+            resolve(&image, "local_counter"),
+            Ok(Some(GuestAddress(0x40_0020)))
+        );
+        assert_eq!(resolve(&image, "cmdline"), Ok(None));
+        // No symbol table at all.
+        assert_eq!(resolve(&make_elf_bin(), "_start"), Ok(None));
+
+        // Symbol 7, `cmdline_buf`, made undefined.
+        let mut bad_image = image.clone();
+        bad_image[0x156..0x158].copy_from_slice(&0u16.to_le_bytes());
+        assert_eq!(resolve(&bad_image, "cmdline_buf"), Ok(None));
+
+        // String table (section 4) running past the end of the image.
+        let mut bad_image = image.clone();
+        bad_image[0x2e8..0x2f0].copy_from_slice(&0x1000u64.to_le_bytes());
+        assert_eq!(
+            resolve(&bad_image, "cmdline_buf"),
+            Err(KernelLoaderError::Elf(Error::InvalidSymbolTable))
+        );
+        // Symbol table linked to a section which is not a string table.
+        let mut bad_image = image;
+        bad_image[0x2b0..0x2b4].copy_from_slice(&1u32.to_le_bytes());
+        assert_eq!(
+            resolve(&bad_image, "cmdline_buf"),
+            Err(KernelLoaderError::Elf(Error::InvalidSymbolTable))
         );
     }
 
@@ -505,14 +3892,16 @@ mod tests {
     fn test_bad_magic_number() {
         let gm = create_guest_mem();
         let kernel_addr = GuestAddress(0x0);
-        let mut bad_image = make_elf_bin();
-        bad_image[0x1] = 0x33;
+        let bad_image = ElfBuilder::new()
+            .segment(elf::PT_LOAD, 0x0, &[0x90; 0x10], 0x10)
+            .build_with(|ehdr, _| ehdr.e_ident[elf::EI_MAG1 as usize] = 0x33);
         assert_eq!(
             Some(KernelLoaderError::Elf(Error::InvalidElfMagicNumber)),
-            Elf::load(&gm, Some(kernel_addr), &mut Cursor::new(&bad_image), None).err()
+            load_kernel_bytes::<Elf, _>(&gm, Some(kernel_addr), &bad_image, None).err()
         );
     }
 
+    #[cfg(not(feature = "big-endian"))]
     #[test]
     fn test_bad_endian() {
         // Only little endian is supported.
@@ -522,7 +3911,62 @@ mod tests {
         bad_image[0x5] = 2;
         assert_eq!(
             Some(KernelLoaderError::Elf(Error::BigEndianElfOnLittle)),
-            Elf::load(&gm, Some(kernel_addr), &mut Cursor::new(&bad_image), None).err()
+            load_kernel_bytes::<Elf, _>(&gm, Some(kernel_addr), &bad_image, None).err()
+        );
+    }
+
+    // Byte swaps consecutive fields of `sizes` bytes, starting at `offset`.
+    #[cfg(feature = "big-endian")]
+    fn swap_fields(image: &mut [u8], mut offset: usize, sizes: &[usize]) {
+        for size in sizes {
+            image[offset..offset + size].reverse();
+            offset += size;
+        }
+    }
+
+    #[cfg(feature = "big-endian")]
+    #[test]
+    fn test_load_big_endian() {
+        const EHDR_FIELDS: [usize; 13] = [2, 2, 4, 8, 8, 8, 4, 2, 2, 2, 2, 2, 2];
+        const PHDR_FIELDS: [usize; 8] = [4, 4, 8, 8, 8, 8, 8, 8];
+        const SHDR_FIELDS: [usize; 10] = [4, 4, 8, 8, 8, 8, 4, 4, 8, 8];
+
+        // Convert the headers of the little endian test image.
+        let image = make_elf_bin();
+        let mut be_image = image.clone();
+        be_image[0x5] = elf::ELFDATA2MSB as u8;
+        swap_fields(&mut be_image, 0x10, &EHDR_FIELDS);
+        for phdr in 0..2 {
+            swap_fields(&mut be_image, 0x40 + phdr * 0x38, &PHDR_FIELDS);
+        }
+        for shdr in 0..5 {
+            swap_fields(&mut be_image, 0x140 + shdr * 0x40, &SHDR_FIELDS);
+        }
+
+        let gm = create_guest_mem();
+        let be_gm = create_guest_mem();
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        let be_loader_result = load_kernel_bytes::<Elf, _>(&be_gm, None, &be_image, None).unwrap();
+        assert_eq!(be_loader_result, loader_result);
+
+        // The segments are copied verbatim.
+        let mut buf = [0u8; 0x42e];
+        let mut be_buf = [0u8; 0x42e];
+        gm.read_slice(&mut buf, GuestAddress(0)).unwrap();
+        be_gm.read_slice(&mut be_buf, GuestAddress(0)).unwrap();
+        assert_eq!(be_buf, buf);
+
+        let sections = Elf::parse_sections(&mut Cursor::new(&image)).unwrap();
+        let be_sections = Elf::parse_sections(&mut Cursor::new(&be_image)).unwrap();
+        for (be_shdr, shdr) in be_sections.iter().zip(sections.iter()) {
+            assert_eq!(be_shdr.as_slice(), shdr.as_slice());
+        }
+
+        // Any other data encoding is still rejected.
+        be_image[0x5] = 3;
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::BigEndianElfOnLittle)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &be_image, None).err()
         );
     }
 
@@ -531,32 +3975,108 @@ mod tests {
         // Program header has to be past the end of the elf header.
         let gm = create_guest_mem();
         let kernel_addr = GuestAddress(0x0);
-        let mut bad_image = make_elf_bin();
-        bad_image[0x20] = 0x10;
+        let bad_image = ElfBuilder::new()
+            .segment(elf::PT_LOAD, 0x0, &[0x90; 0x10], 0x10)
+            .build_with(|ehdr, _| ehdr.e_phoff = 0x10);
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderOffset)),
+            load_kernel_bytes::<Elf, _>(&gm, Some(kernel_addr), &bad_image, None).err()
+        );
+    }
+
+    #[test]
+    fn test_phoff_boundary() {
+        let gm = create_guest_mem();
+        // The program header table right after the ELF header is the usual layout.
+        let image = ElfBuilder::new()
+            .segment(elf::PT_LOAD, 0x0, &[0x90; 0x10], 0x10)
+            .build();
+        assert_eq!(
+            Elf::parse_header(&mut Cursor::new(&image)).unwrap().e_phoff,
+            mem::size_of::<elf::Elf64_Ehdr>() as u64
+        );
+        load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        let bad_image = ElfBuilder::new()
+            .segment(elf::PT_LOAD, 0x0, &[0x90; 0x10], 0x10)
+            .build_with(|ehdr, _| ehdr.e_phoff = 63);
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderOffset)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &bad_image, None).err()
+        );
+
+        // Same for 32-bit images, with their smaller ELF header.
+        let mut image = make_elf32_bin();
+        assert_eq!(
+            image[0x1c..0x20],
+            (mem::size_of::<elf::Elf32_Ehdr>() as u32).to_le_bytes()
+        );
+        load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+        image[0x1c..0x20].copy_from_slice(&51u32.to_le_bytes());
         assert_eq!(
             Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderOffset)),
-            Elf::load(&gm, Some(kernel_addr), &mut Cursor::new(&bad_image), None).err()
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+    }
+
+    #[test]
+    fn test_bad_phnum() {
+        let gm = create_guest_mem();
+        let mut bad_image = make_elf_bin();
+        // The image only has room for a handful of program headers.
+        bad_image[0x38..0x3a].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderCount)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &bad_image, None).err()
+        );
+
+        // Truncated right before the end of the table.
+        let mut bad_image = make_elf_bin();
+        bad_image.truncate(0x40 + 2 * mem::size_of::<elf::Elf64_Phdr>() - 1);
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderCount)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &bad_image, None).err()
+        );
+    }
+
+    #[test]
+    fn test_phdrs_past_eof() {
+        let gm = create_guest_mem();
+        // Truncated right after the ELF header, before the program header table.
+        let mut bad_image = make_elf_bin();
+        bad_image.truncate(0x40);
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::ProgramHeaderPastEof)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &bad_image, None).err()
+        );
+
+        // `e_phoff` points past the end of a complete image.
+        let mut bad_image = make_elf_bin();
+        let image_len = bad_image.len() as u64;
+        bad_image[0x20..0x28].copy_from_slice(&image_len.to_le_bytes());
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::ProgramHeaderPastEof)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &bad_image, None).err()
         );
+        assert!(matches!(
+            parse_elf(&mut Cursor::new(&bad_image)),
+            Err(KernelLoaderError::Elf(Error::ProgramHeaderPastEof))
+        ));
     }
 
     #[test]
     fn test_load_pvh() {
         let gm = create_guest_mem();
         let pvhnote_image = make_elfnote();
-        let loader_result = Elf::load(&gm, None, &mut Cursor::new(&pvhnote_image), None).unwrap();
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &pvhnote_image, None).unwrap();
         assert_eq!(
             loader_result.pvh_boot_cap,
             PvhBootCapability::PvhEntryPresent(GuestAddress(0x1e1fe1f))
         );
 
         // Verify that PVH is ignored when kernel_start is requested
-        let loader_result = Elf::load(
-            &gm,
-            Some(GuestAddress(0x0020_0000)),
-            &mut Cursor::new(&pvhnote_image),
-            None,
-        )
-        .unwrap();
+        let loader_result =
+            load_kernel_bytes::<Elf, _>(&gm, Some(GuestAddress(0x0020_0000)), &pvhnote_image, None)
+                .unwrap();
         assert_eq!(
             loader_result.pvh_boot_cap,
             PvhBootCapability::PvhEntryIgnored
@@ -567,20 +4087,81 @@ mod tests {
     fn test_dummy_elfnote() {
         let gm = create_guest_mem();
         let dummynote_image = make_dummy_elfnote();
-        let loader_result = Elf::load(&gm, None, &mut Cursor::new(&dummynote_image), None).unwrap();
+        let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, &dummynote_image, None).unwrap();
         assert_eq!(
             loader_result.pvh_boot_cap,
             PvhBootCapability::PvhEntryNotPresent
         );
     }
 
+    #[test]
+    fn test_no_loadable_segments() {
+        let gm = create_guest_mem();
+        let mut image = make_dummy_elfnote();
+        // Only keep the second program header, a PT_NOTE.
+        image[0x20..0x28].copy_from_slice(&0x78u64.to_le_bytes());
+        image[0x38..0x3a].copy_from_slice(&1u16.to_le_bytes());
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::NoLoadableSegments)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+
+        // Empty PT_LOAD segments don't count, a pure BSS one does.
+        let image = ElfBuilder::new()
+            .entry(0x400)
+            .segment(elf::PT_LOAD, 0x400, &[], 0)
+            .segment(elf::PT_LOAD, 0x800, &[], 0)
+            .build();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::NoLoadableSegments)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::NoLoadableSegments)),
+            parse_elf(&mut Cursor::new(&image)).err()
+        );
+        let image = ElfBuilder::new()
+            .entry(0x400)
+            .segment(elf::PT_LOAD, 0x400, &[], 0)
+            .segment(elf::PT_LOAD, 0x800, &[], 0x10)
+            .build();
+        assert_eq!(
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None)
+                .unwrap()
+                .kernel_end,
+            0x810
+        );
+    }
+
+    #[test]
+    fn test_no_program_headers() {
+        let gm = create_guest_mem();
+        let mut image = make_elf_bin();
+        image[0x38..0x3a].copy_from_slice(&0u16.to_le_bytes());
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::NoLoadableSegments)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+
+        // The program header table is not looked at, even if it can't be in the image.
+        image[0x20..0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::NoLoadableSegments)),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::NoLoadableSegments)),
+            parse_elf(&mut Cursor::new(&image)).err()
+        );
+    }
+
     #[test]
     fn test_bad_elfnote() {
         let gm = create_guest_mem();
         let badnote_image = make_invalid_pvh_note();
         assert_eq!(
             Some(KernelLoaderError::Elf(Error::InvalidPvhNote)),
-            Elf::load(&gm, None, &mut Cursor::new(&badnote_image), None).err()
+            load_kernel_bytes::<Elf, _>(&gm, None, &badnote_image, None).err()
         );
     }
 
@@ -590,23 +4171,532 @@ mod tests {
         let bad_align_image = make_bad_align();
         assert_eq!(
             Some(KernelLoaderError::Elf(Error::Align)),
-            Elf::load(&gm, None, &mut Cursor::new(&bad_align_image), None).err()
+            load_kernel_bytes::<Elf, _>(&gm, None, &bad_align_image, None).err()
         );
     }
 
     #[test]
-    fn test_overflow_loadaddr() {
+    fn test_bad_segment_alignment() {
+        // Both segments of the image are 16 bytes aligned.
         let gm = create_guest_mem();
         let image = make_elf_bin();
         assert_eq!(
-            Some(KernelLoaderError::Elf(Error::Overflow)),
-            Elf::load(
+            Some(KernelLoaderError::Elf(Error::InvalidSegmentAlignment)),
+            load_kernel_bytes::<Elf, _>(&gm, Some(GuestAddress(0x200008)), &image, None).err()
+        );
+        assert!(
+            load_kernel_bytes::<Elf, _>(&gm, Some(GuestAddress(0x200010)), &image, None).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_highest_kernel_end() {
+        // The image spans [0x200000, 0x20042e) when loaded at 0x200000.
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        let kernel_addr = GuestAddress(0x200000);
+
+        let mut options = LoadOptions {
+            highest_kernel_end: Some(GuestAddress(0x20042e)),
+            ..Default::default()
+        };
+        let loader_result = Elf::load_with_options(
+            &gm,
+            Some(kernel_addr),
+            &mut Cursor::new(&image),
+            None,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(loader_result.kernel_end, 0x20042e);
+
+        options.highest_kernel_end = Some(GuestAddress(0x20042d));
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidKernelEndAddress)),
+            Elf::load_with_options(
+                &gm,
+                Some(kernel_addr),
+                &mut Cursor::new(&image),
+                None,
+                &options
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn test_check_entry() {
+        let gm = create_guest_mem();
+        let kernel_addr = GuestAddress(0x20_0000);
+        let options = LoadOptions {
+            check_entry: true,
+            ..Default::default()
+        };
+        let load = |image: &[u8]| {
+            Elf::load_with_options(
                 &gm,
-                Some(GuestAddress(u64::MAX)),
+                Some(kernel_addr),
+                &mut Cursor::new(image),
+                None,
+                &options,
+            )
+        };
+
+        // At the start of the first segment, [0x400, 0x41d).
+        let image = ElfBuilder::new()
+            .entry(0x400)
+            .segment(elf::PT_LOAD, 0x400, &[0x90; 0x1d], 0x1d)
+            .segment(elf::PT_LOAD, 0x420, &[0x90; 0xe], 0xe)
+            .build();
+        let loader_result = load(&image).unwrap();
+        assert_eq!(loader_result.kernel_load, GuestAddress(0x20_0400));
+
+        // Right past the end of the second segment, [0x420, 0x42e).
+        let image = ElfBuilder::new()
+            .entry(0x42e)
+            .segment(elf::PT_LOAD, 0x400, &[0x90; 0x1d], 0x1d)
+            .segment(elf::PT_LOAD, 0x420, &[0x90; 0xe], 0xe)
+            .build();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::EntryNotInLoadedSegment)),
+            load(&image).err()
+        );
+        // Nothing is written to guest memory, not even the span to zero out.
+        let stale_gm = create_guest_mem();
+        stale_gm
+            .write_slice(&[0xaa; 0x2e], GuestAddress(0x20_0400))
+            .unwrap();
+        let stale_options = LoadOptions {
+            zero_span: true,
+            ..options
+        };
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::EntryNotInLoadedSegment)),
+            Elf::load_with_options(
+                &stale_gm,
+                Some(kernel_addr),
                 &mut Cursor::new(&image),
-                None
+                None,
+                &stale_options,
             )
             .err()
         );
+        let mut buf = [0u8; 0x2e];
+        stale_gm
+            .read_slice(&mut buf, GuestAddress(0x20_0400))
+            .unwrap();
+        assert_eq!(buf, [0xaa; 0x2e]);
+        // The check is not done by default.
+        assert!(load_kernel_bytes::<Elf, _>(&gm, Some(kernel_addr), &image, None).is_ok());
+
+        // In the BSS of the second segment.
+        let image = ElfBuilder::new()
+            .entry(0x42e)
+            .segment(elf::PT_LOAD, 0x420, &[0x90; 0xe], 0x20)
+            .build();
+        assert!(load(&image).is_ok());
+    }
+
+    #[test]
+    fn test_load_into_region() {
+        let gm = GuestMemoryMmap::from_ranges(&[
+            (GuestAddress(0x0), 0x1000),
+            (GuestAddress(0x1000), 0x1000),
+        ])
+        .unwrap();
+        let load = |image: &[u8], region_addr| {
+            let region = gm.find_region(GuestAddress(region_addr)).unwrap();
+            Elf::load_into_region(&gm, region, None, &mut Cursor::new(image), None)
+        };
+
+        // The end of the BSS is right at the end of the first region.
+        let image = ElfBuilder::new()
+            .entry(0xf00)
+            .segment(elf::PT_LOAD, 0xf00, &[0x90; 0x10], 0x100)
+            .build();
+        assert_eq!(load(&image, 0x0).unwrap().kernel_end, 0x1000);
+        assert!(matches!(
+            load(&image, 0x1000),
+            Err(KernelLoaderError::Elf(Error::SegmentOutsideRegion {
+                index: 0
+            }))
+        ));
+
+        // The second segment crosses the boundary between both regions.
+        let image = ElfBuilder::new()
+            .entry(0x1800)
+            .segment(elf::PT_LOAD, 0x1800, &[0x90; 0x10], 0x10)
+            .segment(elf::PT_LOAD, 0xff0, &[0x90; 0x20], 0x20)
+            .build();
+        assert!(matches!(
+            load(&image, 0x1000),
+            Err(KernelLoaderError::Elf(Error::SegmentOutsideRegion {
+                index: 1
+            }))
+        ));
+        // It is loaded fine without the region check.
+        assert!(load_kernel_bytes::<Elf, _>(&gm, None, &image, None).is_ok());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        // `readelf -h` reports `Flags: 0x0` for both images.
+        let image = make_elf_bin();
+        let ehdr = Elf::parse_header(&mut Cursor::new(&image)).unwrap();
+        assert_eq!(ehdr.e_flags, 0);
+        assert_eq!(ehdr.e_entry, 0x400);
+
+        let mut image = make_elf32_bin();
+        let ehdr = Elf::parse_header(&mut Cursor::new(&image)).unwrap();
+        assert_eq!(ehdr.e_flags, 0);
+
+        // `e_flags` is at 0x24 in 32-bit headers and 0x30 in 64-bit ones.
+        image[0x24..0x28].copy_from_slice(&0x5u32.to_le_bytes());
+        let ehdr = Elf::parse_header(&mut Cursor::new(&image)).unwrap();
+        assert_eq!(ehdr.e_flags, 0x5);
+        let mut image = make_elf_bin();
+        image[0x30..0x34].copy_from_slice(&0x5u32.to_le_bytes());
+        let ehdr = Elf::parse_header(&mut Cursor::new(&image)).unwrap();
+        assert_eq!(ehdr.e_flags, 0x5);
+
+        image[0x0] = 0x0;
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidElfMagicNumber)),
+            Elf::parse_header(&mut Cursor::new(&image)).err()
+        );
+    }
+
+    #[test]
+    fn test_segment_descriptors() {
+        let gm = create_guest_mem();
+        let bss_image = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x1000, &[0x90; 0x10], 0x20)
+            .segment(elf::PT_LOAD, 0x2000, &[0xaa; 0x8], 0x8)
+            .build();
+        for image in [make_elf_bin(), make_elf32_bin(), bss_image].iter() {
+            let segments = segment_descriptors(&mut Cursor::new(image)).unwrap();
+            assert!(!segments.is_empty());
+            load_kernel_bytes::<Elf, _>(&gm, None, image, None).unwrap();
+            // Copying the segments by hand gives the same guest memory contents.
+            for segment in segments {
+                let mut expected = vec![0u8; segment.mem_size as usize];
+                let file_offset = segment.file_offset as usize;
+                let file_size = segment.file_size as usize;
+                expected[..file_size].copy_from_slice(&image[file_offset..file_offset + file_size]);
+                let mut loaded = vec![0xffu8; segment.mem_size as usize];
+                gm.read_slice(&mut loaded, segment.guest_addr).unwrap();
+                assert_eq!(loaded, expected);
+            }
+        }
+
+        assert_eq!(
+            segment_descriptors(&mut Cursor::new(&make_elf_bin())).unwrap()[0],
+            SegmentDescriptor {
+                file_offset: 0xb0,
+                guest_addr: GuestAddress(0x0),
+                file_size: 0x1d,
+                mem_size: 0x1d,
+                flags: elf::PF_R | elf::PF_X,
+            }
+        );
+        assert!(matches!(
+            segment_descriptors(&mut Cursor::new(&make_invalid_pvh_note())),
+            Err(KernelLoaderError::Elf(Error::InvalidPvhNote))
+        ));
+    }
+
+    #[test]
+    fn test_parse_elf() {
+        let gm = create_guest_mem();
+        let images = [
+            make_elf_bin(),
+            make_elf32_bin(),
+            make_elfnote(),
+            include_bytes!("test_pie.bin").to_vec(),
+        ];
+        for image in images.iter() {
+            let parsed = parse_elf(&mut Cursor::new(image)).unwrap();
+            let loader_result = load_kernel_bytes::<Elf, _>(&gm, None, image, None).unwrap();
+            assert_eq!(parsed.kernel_end, loader_result.kernel_end);
+            assert_eq!(parsed.entry, loader_result.kernel_load);
+            assert_eq!(parsed.pvh_boot_cap, loader_result.pvh_boot_cap);
+            assert_eq!(
+                parsed
+                    .segments
+                    .iter()
+                    .map(|(_, phdr)| phdr.p_filesz)
+                    .sum::<u64>(),
+                loader_result.bytes_loaded
+            );
+        }
+
+        let parsed = parse_elf(&mut Cursor::new(&make_elf_bin())).unwrap();
+        assert_eq!(
+            parsed
+                .segments
+                .iter()
+                .map(|(index, phdr)| (*index, phdr.p_paddr, phdr.p_memsz))
+                .collect::<Vec<_>>(),
+            [(0, 0x0, 0x1d), (1, 0x420, 0xe)]
+        );
+
+        // The validation errors are the same.
+        let image = ElfBuilder::new().build();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::NoLoadableSegments)),
+            parse_elf(&mut Cursor::new(&image)).err()
+        );
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidPvhNote)),
+            parse_elf(&mut Cursor::new(&make_invalid_pvh_note())).err()
+        );
+    }
+
+    #[test]
+    fn test_segment_size_above_u32() {
+        // Sizes are handled as `u64` and not truncated to `usize`, which is only 32-bit wide on
+        // 32-bit hosts.
+        let gm = create_guest_mem();
+        let size = 0x1_0000_0010;
+        let image = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x1000, &[0x90; 0x10], size)
+            .build();
+
+        let parsed = parse_elf(&mut Cursor::new(&image)).unwrap();
+        assert_eq!(parsed.kernel_end, 0x1000 + size);
+        assert_eq!(
+            Elf::kernel_size(&mut Cursor::new(&image)).unwrap(),
+            (GuestAddress(0x1000), 0x1000 + size)
+        );
+        // Truncated to 32 bits, the segment would fit in guest memory.
+        assert!(matches!(
+            Elf::load_from_slice(&gm, None, &image, None),
+            Err(KernelLoaderError::Elf(Error::InvalidProgramHeaderAddress))
+        ));
+
+        // Or in the image.
+        let image = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x1000, &[0x90; 0x10], 0x10)
+            .build_with(|_, phdrs| {
+                phdrs[0].p_filesz = size;
+                phdrs[0].p_memsz = size;
+            });
+        assert!(matches!(
+            Elf::load_from_slice(&gm, None, &image, None),
+            Err(KernelLoaderError::Elf(Error::TruncatedSegment {
+                index: 0,
+                expected: 0x1_0000_0010,
+                got: 0x10,
+            }))
+        ));
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::TruncatedSegment {
+                index: 0,
+                expected: 0x1_0000_0010,
+                got: 0x10,
+            })),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+    }
+
+    #[test]
+    fn test_zero_span() {
+        let gm = create_guest_mem();
+        let kernel_addr = GuestAddress(0x20_0000);
+        let image = make_elf_bin();
+        let fill = |gm: &GuestMemoryMmap| {
+            gm.write_slice(&[0xa5u8; 0x1000], GuestAddress(0x1f_f800))
+                .unwrap();
+        };
+        let read = |gm: &GuestMemoryMmap, offset, len| {
+            let mut buf = vec![0u8; len];
+            gm.read_slice(&mut buf, kernel_addr.unchecked_add(offset))
+                .unwrap();
+            buf
+        };
+        let mut options = LoadOptions::default();
+
+        // The segments span [0x0, 0x1d) and [0x420, 0x42e), the gap is left as it is by default.
+        fill(&gm);
+        Elf::load_with_options(
+            &gm,
+            Some(kernel_addr),
+            &mut Cursor::new(&image),
+            None,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(read(&gm, 0x1d, 0x403), vec![0xa5; 0x403]);
+
+        options.zero_span = true;
+        fill(&gm);
+        let loader_result = Elf::load_with_options(
+            &gm,
+            Some(kernel_addr),
+            &mut Cursor::new(&image),
+            None,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(loader_result.kernel_end, 0x20_042e);
+        assert_eq!(read(&gm, 0x1d, 0x403), vec![0; 0x403]);
+        // The segments are loaded, and the memory around the span is untouched.
+        assert_eq!(read(&gm, 0x0, 0x1d), image[0xb0..0xcd].to_vec());
+        assert_eq!(read(&gm, 0x420, 0xe), image[0xd0..0xde].to_vec());
+        assert_eq!(read(&gm, 0x42e, 0x10), vec![0xa5; 0x10]);
+        let mut before = [0u8; 0x10];
+        gm.read_slice(&mut before, GuestAddress(0x1f_fff0)).unwrap();
+        assert_eq!(before, [0xa5; 0x10]);
+    }
+
+    #[test]
+    fn test_segment_granularity() {
+        let gm = create_guest_mem();
+        gm.write_slice(&[0xa5u8; 0x4000], GuestAddress(0x0))
+            .unwrap();
+        let image = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x1000, &[0x90; 0x10], 0x20)
+            .segment(elf::PT_LOAD, 0x2000, &[0xaa; 0x8], 0x8)
+            .build();
+        let mut options = LoadOptions {
+            segment_granularity: Some(0x1000),
+            ..Default::default()
+        };
+
+        let loader_result =
+            Elf::load_with_options(&gm, None, &mut Cursor::new(&image), None, &options).unwrap();
+        assert_eq!(loader_result.kernel_end, 0x3000);
+        assert_eq!(loader_result.bytes_loaded, 0x18);
+        // Both the BSS and the rounding tail of each segment are zeroed out.
+        let mut buf = vec![0xffu8; 0x2000];
+        gm.read_slice(&mut buf, GuestAddress(0x1000)).unwrap();
+        assert_eq!(buf[..0x10], [0x90; 0x10]);
+        assert!(buf[0x10..0x1000].iter().all(|&b| b == 0));
+        assert_eq!(buf[0x1000..0x1008], [0xaa; 0x8]);
+        assert!(buf[0x1008..].iter().all(|&b| b == 0));
+        let mut buf = [0u8; 0x10];
+        gm.read_slice(&mut buf, GuestAddress(0x3000)).unwrap();
+        assert_eq!(buf, [0xa5; 0x10]);
+
+        // The rounded segments share a granule.
+        let shared_image = ElfBuilder::new()
+            .entry(0x1000)
+            .segment(elf::PT_LOAD, 0x1000, &[0x90; 0x10], 0x20)
+            .segment(elf::PT_LOAD, 0x1800, &[0xaa; 0x8], 0x8)
+            .build();
+        assert!(load_kernel_bytes::<Elf, _>(&gm, None, &shared_image, None).is_ok());
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::OverlappingSegments)),
+            Elf::load_with_options(&gm, None, &mut Cursor::new(&shared_image), None, &options)
+                .err()
+        );
+
+        // The rounding tail of the last segment ends right at the end of guest memory.
+        let small_gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x3000)]).unwrap();
+        let loader_result =
+            Elf::load_with_options(&small_gm, None, &mut Cursor::new(&image), None, &options)
+                .unwrap();
+        assert_eq!(loader_result.kernel_end, 0x3000);
+        // It is past the end of guest memory, nothing is written.
+        let small_gm = GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), 0x2800)]).unwrap();
+        small_gm
+            .write_slice(&[0xa5u8; 0x2800], GuestAddress(0x0))
+            .unwrap();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidProgramHeaderAddress)),
+            Elf::load_with_options(&small_gm, None, &mut Cursor::new(&image), None, &options).err()
+        );
+        let mut buf = vec![0u8; 0x2800];
+        small_gm.read_slice(&mut buf, GuestAddress(0x0)).unwrap();
+        assert!(buf.iter().all(|&b| b == 0xa5));
+
+        options.segment_granularity = Some(0x1800);
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::InvalidSegmentGranularity)),
+            Elf::load_with_options(&gm, None, &mut Cursor::new(&image), None, &options).err()
+        );
+    }
+
+    #[test]
+    fn test_overflow_loadaddr() {
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        assert_eq!(
+            Some(KernelLoaderError::Elf(Error::Overflow)),
+            load_kernel_bytes::<Elf, _>(&gm, Some(GuestAddress(u64::MAX)), &image, None).err()
+        );
+    }
+
+    #[test]
+    fn test_kernel_end_overflow() {
+        let gm = create_guest_mem();
+        let mut image = make_elf_bin();
+        // Move the second segment right below the end of the address space, so
+        // that its end wraps around.
+        image[0x90..0x98].copy_from_slice(&0xffff_ffff_ffff_fff0u64.to_le_bytes());
+        image[0xa0..0xa8].copy_from_slice(&0x20u64.to_le_bytes());
+        assert_eq!(
+            Some(KernelLoaderError::MemoryOverflow),
+            load_kernel_bytes::<Elf, _>(&gm, None, &image, None).err()
+        );
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_load_logging() {
+        use std::cell::RefCell;
+
+        // Records the messages logged by the current thread, tests run in parallel.
+        struct CaptureLogger;
+
+        thread_local! {
+            static RECORDS: RefCell<Vec<(log::Level, String)>> = const { RefCell::new(Vec::new()) };
+        }
+
+        impl log::Log for CaptureLogger {
+            fn enabled(&self, _metadata: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                RECORDS.with(|records| {
+                    records
+                        .borrow_mut()
+                        .push((record.level(), record.args().to_string()))
+                });
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: CaptureLogger = CaptureLogger;
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let gm = create_guest_mem();
+        let image = make_elf_bin();
+        load_kernel_bytes::<Elf, _>(&gm, None, &image, None).unwrap();
+
+        let records = RECORDS.with(|records| records.take());
+        let segments: Vec<&String> = records
+            .iter()
+            .filter(|(level, _)| *level == log::Level::Debug)
+            .map(|(_, message)| message)
+            .collect();
+        assert_eq!(
+            segments,
+            [
+                "Loaded ELF segment 0: file offset 0xb0, guest address 0x0, filesz 0x1d, memsz 0x1d",
+                "Loaded ELF segment 1: file offset 0xd0, guest address 0x420, filesz 0xe, memsz 0xe",
+            ]
+        );
+        assert!(records.contains(&(
+            log::Level::Info,
+            "Loaded ELF image: entry 0x400, kernel end 0x42e".to_string()
+        )));
     }
 }
@@ -16,3 +16,6 @@ pub mod elf;
 
 #[cfg(feature = "bzimage")]
 pub mod bzimage;
+
+#[cfg(feature = "multiboot")]
+pub mod multiboot;
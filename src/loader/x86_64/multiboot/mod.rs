@@ -0,0 +1,384 @@
+// SPDX-License-Identifier: Apache-2.0 AND BSD-3-Clause
+
+//! Traits and structs for loading multiboot (v1) kernels into guest memory.
+
+#![cfg(all(
+    feature = "multiboot",
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
+
+use vm_memory::{Address, Bytes, GuestAddress, GuestMemory};
+
+use super::super::{Error as KernelLoaderError, KernelLoader, KernelLoaderResult, Result};
+use super::elf::Elf;
+
+// Magic number identifying the multiboot header.
+const MULTIBOOT_HEADER_MAGIC: u32 = 0x1bad_b002;
+// The header must be contained in the first 8192 bytes of the image, 32-bit aligned.
+const MULTIBOOT_SEARCH: usize = 8192;
+const MULTIBOOT_HEADER_ALIGN: usize = 4;
+// Flag telling that the address fields of the header (the "a.out kludge") are valid.
+const MULTIBOOT_AOUT_KLUDGE: u32 = 1 << 16;
+// Size of the magic, flags and checksum fields.
+const MULTIBOOT_HEADER_SIZE: usize = 12;
+// Size of the header including the address fields.
+const MULTIBOOT_AOUT_HEADER_SIZE: usize = 32;
+
+#[derive(Debug, PartialEq, Eq)]
+/// Multiboot kernel loader errors.
+#[non_exhaustive]
+pub enum Error {
+    /// Invalid address fields in the multiboot header.
+    InvalidLoadAddress,
+    /// Multiboot header not found.
+    InvalidMultibootHeader,
+    /// Multiboot header checksum mismatch.
+    InvalidMultibootChecksum,
+    /// Overflow occurred during an arithmetic operation.
+    Overflow,
+    /// Unable to read kernel image.
+    ReadKernelImage,
+    /// Unable to read multiboot header.
+    ReadMultibootHeader,
+    /// Unable to seek to kernel image end.
+    SeekKernelEnd,
+    /// Unable to seek to kernel start.
+    SeekKernelStart,
+    /// Unable to seek to multiboot header.
+    SeekMultibootHeader,
+    /// Unable to zero out the BSS.
+    ZeroBss,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let desc = match self {
+            Error::InvalidLoadAddress => "Invalid multiboot load address",
+            Error::InvalidMultibootHeader => "Invalid multiboot header",
+            Error::InvalidMultibootChecksum => "Invalid multiboot header checksum",
+            Error::Overflow => "Overflow occurred during an arithmetic operation",
+            Error::ReadKernelImage => "Unable to read kernel image",
+            Error::ReadMultibootHeader => "Unable to read multiboot header",
+            Error::SeekKernelEnd => "Unable to seek to kernel end",
+            Error::SeekKernelStart => "Unable to seek to kernel start",
+            Error::SeekMultibootHeader => "Unable to seek to multiboot header",
+            Error::ZeroBss => "Unable to zero out BSS",
+        };
+
+        write!(f, "Kernel Loader: {}", desc)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Multiboot header, as found in the image.
+struct MultibootHeader {
+    // Offset of the header in the image.
+    offset: u64,
+    flags: u32,
+    header_addr: u32,
+    load_addr: u32,
+    load_end_addr: u32,
+    bss_end_addr: u32,
+    entry_addr: u32,
+}
+
+/// Multiboot (v1) kernel image support.
+///
+/// Images carrying the load addresses in their multiboot header (the `a.out kludge`) are loaded
+/// as a flat binary, other images are loaded as ELF.
+pub struct Multiboot;
+
+impl Multiboot {
+    /// Looks for the multiboot header in the first 8 KiB of the image.
+    fn find_header<F>(kernel_image: &mut F) -> std::result::Result<MultibootHeader, Error>
+    where
+        F: Read + Seek,
+    {
+        kernel_image
+            .seek(SeekFrom::Start(0))
+            .map_err(|_| Error::SeekMultibootHeader)?;
+        let mut buf = Vec::with_capacity(MULTIBOOT_SEARCH);
+        kernel_image
+            .by_ref()
+            .take(MULTIBOOT_SEARCH as u64)
+            .read_to_end(&mut buf)
+            .map_err(|_| Error::ReadMultibootHeader)?;
+
+        let field = |offset: usize| {
+            buf.get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .unwrap_or(0)
+        };
+
+        let offset = (0..buf.len().saturating_sub(MULTIBOOT_HEADER_SIZE - 1))
+            .step_by(MULTIBOOT_HEADER_ALIGN)
+            .find(|&offset| field(offset) == MULTIBOOT_HEADER_MAGIC)
+            .ok_or(Error::InvalidMultibootHeader)?;
+
+        let flags = field(offset + 4);
+        let checksum = field(offset + 8);
+        if MULTIBOOT_HEADER_MAGIC
+            .wrapping_add(flags)
+            .wrapping_add(checksum)
+            != 0
+        {
+            return Err(Error::InvalidMultibootChecksum);
+        }
+
+        if flags & MULTIBOOT_AOUT_KLUDGE != 0 && offset + MULTIBOOT_AOUT_HEADER_SIZE > buf.len() {
+            return Err(Error::InvalidMultibootHeader);
+        }
+
+        Ok(MultibootHeader {
+            offset: offset as u64,
+            flags,
+            header_addr: field(offset + 12),
+            load_addr: field(offset + 16),
+            load_end_addr: field(offset + 20),
+            bss_end_addr: field(offset + 24),
+            entry_addr: field(offset + 28),
+        })
+    }
+}
+
+impl KernelLoader for Multiboot {
+    /// Loads a multiboot kernel image into guest memory.
+    ///
+    /// When the `a.out kludge` flag is set in the multiboot header, the image is loaded at
+    /// `load_addr`, the BSS up to `bss_end_addr` is zeroed out and `entry_addr` is returned as
+    /// the entry point. Otherwise the image must be an ELF image and is loaded by
+    /// [`Elf`](../elf/struct.Elf.html).
+    ///
+    /// # Arguments
+    ///
+    /// * `guest_mem`: [`GuestMemory`] to load the kernel in.
+    /// * `kernel_offset`: Offset to be added to the addresses of the image in guest memory.
+    /// * `kernel_image` - Input multiboot image.
+    /// * `highmem_start_address`: Address where high memory starts.
+    ///
+    /// [`GuestMemory`]: https://docs.rs/vm-memory/latest/vm_memory/guest_memory/trait.GuestMemory.html
+    fn load<F, M: GuestMemory>(
+        guest_mem: &M,
+        kernel_offset: Option<GuestAddress>,
+        kernel_image: &mut F,
+        highmem_start_address: Option<GuestAddress>,
+    ) -> Result<KernelLoaderResult>
+    where
+        F: Read + Seek,
+    {
+        let header = Self::find_header(kernel_image)?;
+        if header.flags & MULTIBOOT_AOUT_KLUDGE == 0 {
+            return Elf::load(
+                guest_mem,
+                kernel_offset,
+                kernel_image,
+                highmem_start_address,
+            );
+        }
+
+        if let Some(addr) = highmem_start_address {
+            if u64::from(header.load_addr) < addr.raw_value() {
+                return Err(KernelLoaderError::InvalidKernelStartAddress);
+            }
+        }
+
+        // The header is loaded along with the rest of the image, which tells where the
+        // loaded part starts in the file.
+        let file_offset = header
+            .header_addr
+            .checked_sub(header.load_addr)
+            .and_then(|header_offset| header.offset.checked_sub(header_offset.into()))
+            .ok_or(Error::InvalidLoadAddress)?;
+        let image_size = kernel_image
+            .seek(SeekFrom::End(0))
+            .map_err(|_| Error::SeekKernelEnd)?;
+        // A zero `load_end_addr` means the image is loaded up to the end of the file.
+        let load_size = if header.load_end_addr == 0 {
+            image_size - file_offset
+        } else {
+            let load_size = header
+                .load_end_addr
+                .checked_sub(header.load_addr)
+                .ok_or(Error::InvalidLoadAddress)?;
+            u64::from(load_size)
+        };
+        // A zero `bss_end_addr` means there is no BSS.
+        let bss_size = if header.bss_end_addr == 0 {
+            0
+        } else {
+            u64::from(header.bss_end_addr)
+                .checked_sub(u64::from(header.load_addr) + load_size)
+                .ok_or(Error::InvalidLoadAddress)?
+        };
+
+        let offset = kernel_offset.map_or(0, |offset| offset.raw_value());
+        let mem_offset = GuestAddress(
+            offset
+                .checked_add(header.load_addr.into())
+                .ok_or(Error::Overflow)?,
+        );
+        let mut loader_result = KernelLoaderResult {
+            kernel_load: GuestAddress(
+                offset
+                    .checked_add(header.entry_addr.into())
+                    .ok_or(Error::Overflow)?,
+            ),
+            ..Default::default()
+        };
+
+        kernel_image
+            .seek(SeekFrom::Start(file_offset))
+            .map_err(|_| Error::SeekKernelStart)?;
+        let count = usize::try_from(load_size).map_err(|_| Error::Overflow)?;
+        guest_mem
+            .read_exact_from(mem_offset, kernel_image, count)
+            .map_err(|_| Error::ReadKernelImage)?;
+
+        let bss_start = mem_offset
+            .checked_add(load_size)
+            .ok_or(KernelLoaderError::MemoryOverflow)?;
+        let bss_count = usize::try_from(bss_size).map_err(|_| Error::Overflow)?;
+        guest_mem
+            .read_exact_from(bss_start, &mut io::repeat(0), bss_count)
+            .map_err(|_| Error::ZeroBss)?;
+
+        loader_result.bytes_loaded = load_size;
+        loader_result.kernel_end = bss_start
+            .raw_value()
+            .checked_add(bss_size)
+            .ok_or(KernelLoaderError::MemoryOverflow)?;
+
+        Ok(loader_result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::load_kernel_bytes;
+    use vm_memory::{Address, GuestAddress};
+    type GuestMemoryMmap = vm_memory::GuestMemoryMmap<()>;
+
+    const MEM_SIZE: u64 = 0x100_0000;
+
+    fn create_guest_mem() -> GuestMemoryMmap {
+        GuestMemoryMmap::from_ranges(&[(GuestAddress(0x0), (MEM_SIZE as usize))]).unwrap()
+    }
+
+    fn make_header(flags: u32, addrs: &[u32]) -> Vec<u8> {
+        let mut header = vec![];
+        for field in [
+            MULTIBOOT_HEADER_MAGIC,
+            flags,
+            0u32.wrapping_sub(MULTIBOOT_HEADER_MAGIC)
+                .wrapping_sub(flags),
+        ]
+        .iter()
+        .chain(addrs)
+        {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        header
+    }
+
+    // A 4 KiB image loaded at 1 MiB with its header at offset 0x100, the first 2 KiB are loaded
+    // and followed by a 2 KiB BSS.
+    fn make_aout_kludge_bin() -> Vec<u8> {
+        let mut image: Vec<u8> = (0..0x1000).map(|i| (i % 0xff) as u8 + 1).collect();
+        let header = make_header(
+            MULTIBOOT_AOUT_KLUDGE,
+            &[0x10_0100, 0x10_0000, 0x10_0800, 0x10_1000, 0x10_0200],
+        );
+        image[0x100..0x100 + header.len()].copy_from_slice(&header);
+        image
+    }
+
+    #[test]
+    fn test_load_aout_kludge() {
+        let gm = create_guest_mem();
+        let image = make_aout_kludge_bin();
+        gm.write_slice(&[0xff; 0x800], GuestAddress(0x10_0800))
+            .unwrap();
+
+        let loader_result = load_kernel_bytes::<Multiboot, _>(&gm, None, &image, None).unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x10_0200);
+        assert_eq!(loader_result.kernel_end, 0x10_1000);
+        assert_eq!(loader_result.bytes_loaded, 0x800);
+
+        let mut buf = vec![0xffu8; 0x1000];
+        gm.read_slice(&mut buf, GuestAddress(0x10_0000)).unwrap();
+        assert_eq!(buf[..0x800], image[..0x800]);
+        assert!(buf[0x800..].iter().all(|&b| b == 0));
+
+        let loader_result =
+            load_kernel_bytes::<Multiboot, _>(&gm, Some(GuestAddress(0x20_0000)), &image, None)
+                .unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x30_0200);
+        assert_eq!(loader_result.kernel_end, 0x30_1000);
+
+        assert_eq!(
+            Some(KernelLoaderError::InvalidKernelStartAddress),
+            load_kernel_bytes::<Multiboot, _>(&gm, None, &image, Some(GuestAddress(0x20_0000)))
+                .err()
+        );
+    }
+
+    #[test]
+    fn test_bad_load_addr() {
+        let gm = create_guest_mem();
+        let mut image = make_aout_kludge_bin();
+        // load_end_addr below load_addr.
+        image[0x114..0x118].copy_from_slice(&0x0f_0000u32.to_le_bytes());
+        assert_eq!(
+            Some(KernelLoaderError::Multiboot(Error::InvalidLoadAddress)),
+            load_kernel_bytes::<Multiboot, _>(&gm, None, &image, None).err()
+        );
+    }
+
+    #[test]
+    fn test_load_elf() {
+        let gm = create_guest_mem();
+        let mut image = include_bytes!("../elf/test_elf.bin").to_vec();
+        image.extend_from_slice(&make_header(0, &[]));
+
+        let loader_result =
+            load_kernel_bytes::<Multiboot, _>(&gm, Some(GuestAddress(0x20_0000)), &image, None)
+                .unwrap();
+        assert_eq!(loader_result.kernel_load.raw_value(), 0x20_0400);
+    }
+
+    #[test]
+    fn test_bad_header() {
+        let gm = create_guest_mem();
+
+        let mut image = make_aout_kludge_bin();
+        image[0x108] ^= 0x1;
+        assert_eq!(
+            Some(KernelLoaderError::Multiboot(
+                Error::InvalidMultibootChecksum
+            )),
+            load_kernel_bytes::<Multiboot, _>(&gm, None, &image, None).err()
+        );
+
+        // The header must be 32-bit aligned.
+        let mut image = vec![0u8; 0x200];
+        let header = make_header(0, &[]);
+        image[0x102..0x102 + header.len()].copy_from_slice(&header);
+        assert_eq!(
+            Some(KernelLoaderError::Multiboot(Error::InvalidMultibootHeader)),
+            load_kernel_bytes::<Multiboot, _>(&gm, None, &image, None).err()
+        );
+
+        // The header must be in the first 8 KiB.
+        let mut image = vec![0u8; MULTIBOOT_SEARCH];
+        image.extend_from_slice(&header);
+        assert_eq!(
+            Some(KernelLoaderError::Multiboot(Error::InvalidMultibootHeader)),
+            load_kernel_bytes::<Multiboot, _>(&gm, None, &image, None).err()
+        );
+    }
+}
@@ -10,8 +10,6 @@
 //! Bindgen autogenerated structs for boot parameters.
 
 #![cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-// Keep this until https://github.com/rust-lang/rust-bindgen/issues/1651 is fixed.
-#![cfg_attr(test, allow(deref_nullptr, unaligned_references))]
 
 mod x86_64;
 pub use x86_64::*;
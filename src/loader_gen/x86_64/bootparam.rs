@@ -228,7 +228,7 @@ fn bindgen_test_layout___kernel_fd_set() {
         concat!("Alignment of ", stringify!(__kernel_fd_set))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__kernel_fd_set>())).fds_bits as *const _ as usize },
+        ::std::mem::offset_of!(__kernel_fd_set, fds_bits),
         0usize,
         concat!(
             "Offset of field: ",
@@ -278,7 +278,7 @@ fn bindgen_test_layout___kernel_fsid_t() {
         concat!("Alignment of ", stringify!(__kernel_fsid_t))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__kernel_fsid_t>())).val as *const _ as usize },
+        ::std::mem::offset_of!(__kernel_fsid_t, val),
         0usize,
         concat!(
             "Offset of field: ",
@@ -360,7 +360,7 @@ fn bindgen_test_layout_screen_info() {
         concat!("Alignment of ", stringify!(screen_info))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).orig_x as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, orig_x),
         0usize,
         concat!(
             "Offset of field: ",
@@ -370,7 +370,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).orig_y as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, orig_y),
         1usize,
         concat!(
             "Offset of field: ",
@@ -380,7 +380,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).ext_mem_k as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, ext_mem_k),
         2usize,
         concat!(
             "Offset of field: ",
@@ -390,7 +390,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).orig_video_page as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, orig_video_page),
         4usize,
         concat!(
             "Offset of field: ",
@@ -400,7 +400,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).orig_video_mode as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, orig_video_mode),
         6usize,
         concat!(
             "Offset of field: ",
@@ -410,7 +410,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).orig_video_cols as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, orig_video_cols),
         7usize,
         concat!(
             "Offset of field: ",
@@ -420,7 +420,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).flags as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, flags),
         8usize,
         concat!(
             "Offset of field: ",
@@ -430,7 +430,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).unused2 as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, unused2),
         9usize,
         concat!(
             "Offset of field: ",
@@ -440,7 +440,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).orig_video_ega_bx as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, orig_video_ega_bx),
         10usize,
         concat!(
             "Offset of field: ",
@@ -450,7 +450,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).unused3 as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, unused3),
         12usize,
         concat!(
             "Offset of field: ",
@@ -460,7 +460,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).orig_video_lines as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, orig_video_lines),
         14usize,
         concat!(
             "Offset of field: ",
@@ -470,7 +470,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).orig_video_isVGA as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, orig_video_isVGA),
         15usize,
         concat!(
             "Offset of field: ",
@@ -480,7 +480,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).orig_video_points as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, orig_video_points),
         16usize,
         concat!(
             "Offset of field: ",
@@ -490,7 +490,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).lfb_width as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, lfb_width),
         18usize,
         concat!(
             "Offset of field: ",
@@ -500,7 +500,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).lfb_height as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, lfb_height),
         20usize,
         concat!(
             "Offset of field: ",
@@ -510,7 +510,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).lfb_depth as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, lfb_depth),
         22usize,
         concat!(
             "Offset of field: ",
@@ -520,7 +520,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).lfb_base as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, lfb_base),
         24usize,
         concat!(
             "Offset of field: ",
@@ -530,7 +530,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).lfb_size as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, lfb_size),
         28usize,
         concat!(
             "Offset of field: ",
@@ -540,7 +540,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).cl_magic as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, cl_magic),
         32usize,
         concat!(
             "Offset of field: ",
@@ -550,7 +550,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).cl_offset as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, cl_offset),
         34usize,
         concat!(
             "Offset of field: ",
@@ -560,7 +560,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).lfb_linelength as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, lfb_linelength),
         36usize,
         concat!(
             "Offset of field: ",
@@ -570,7 +570,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).red_size as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, red_size),
         38usize,
         concat!(
             "Offset of field: ",
@@ -580,7 +580,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).red_pos as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, red_pos),
         39usize,
         concat!(
             "Offset of field: ",
@@ -590,7 +590,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).green_size as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, green_size),
         40usize,
         concat!(
             "Offset of field: ",
@@ -600,7 +600,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).green_pos as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, green_pos),
         41usize,
         concat!(
             "Offset of field: ",
@@ -610,7 +610,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).blue_size as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, blue_size),
         42usize,
         concat!(
             "Offset of field: ",
@@ -620,7 +620,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).blue_pos as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, blue_pos),
         43usize,
         concat!(
             "Offset of field: ",
@@ -630,7 +630,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).rsvd_size as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, rsvd_size),
         44usize,
         concat!(
             "Offset of field: ",
@@ -640,7 +640,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).rsvd_pos as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, rsvd_pos),
         45usize,
         concat!(
             "Offset of field: ",
@@ -650,7 +650,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).vesapm_seg as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, vesapm_seg),
         46usize,
         concat!(
             "Offset of field: ",
@@ -660,7 +660,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).vesapm_off as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, vesapm_off),
         48usize,
         concat!(
             "Offset of field: ",
@@ -670,7 +670,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).pages as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, pages),
         50usize,
         concat!(
             "Offset of field: ",
@@ -680,7 +680,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).vesa_attributes as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, vesa_attributes),
         52usize,
         concat!(
             "Offset of field: ",
@@ -690,7 +690,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).capabilities as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, capabilities),
         54usize,
         concat!(
             "Offset of field: ",
@@ -700,7 +700,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>())).ext_lfb_base as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, ext_lfb_base),
         58usize,
         concat!(
             "Offset of field: ",
@@ -710,7 +710,7 @@ fn bindgen_test_layout_screen_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<screen_info>()))._reserved as *const _ as usize },
+        ::std::mem::offset_of!(screen_info, _reserved),
         62usize,
         concat!(
             "Offset of field: ",
@@ -748,7 +748,7 @@ fn bindgen_test_layout_apm_bios_info() {
         concat!("Alignment of ", stringify!(apm_bios_info))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<apm_bios_info>())).version as *const _ as usize },
+        ::std::mem::offset_of!(apm_bios_info, version),
         0usize,
         concat!(
             "Offset of field: ",
@@ -758,7 +758,7 @@ fn bindgen_test_layout_apm_bios_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<apm_bios_info>())).cseg as *const _ as usize },
+        ::std::mem::offset_of!(apm_bios_info, cseg),
         2usize,
         concat!(
             "Offset of field: ",
@@ -768,7 +768,7 @@ fn bindgen_test_layout_apm_bios_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<apm_bios_info>())).offset as *const _ as usize },
+        ::std::mem::offset_of!(apm_bios_info, offset),
         4usize,
         concat!(
             "Offset of field: ",
@@ -778,7 +778,7 @@ fn bindgen_test_layout_apm_bios_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<apm_bios_info>())).cseg_16 as *const _ as usize },
+        ::std::mem::offset_of!(apm_bios_info, cseg_16),
         8usize,
         concat!(
             "Offset of field: ",
@@ -788,7 +788,7 @@ fn bindgen_test_layout_apm_bios_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<apm_bios_info>())).dseg as *const _ as usize },
+        ::std::mem::offset_of!(apm_bios_info, dseg),
         10usize,
         concat!(
             "Offset of field: ",
@@ -798,7 +798,7 @@ fn bindgen_test_layout_apm_bios_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<apm_bios_info>())).flags as *const _ as usize },
+        ::std::mem::offset_of!(apm_bios_info, flags),
         12usize,
         concat!(
             "Offset of field: ",
@@ -808,7 +808,7 @@ fn bindgen_test_layout_apm_bios_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<apm_bios_info>())).cseg_len as *const _ as usize },
+        ::std::mem::offset_of!(apm_bios_info, cseg_len),
         14usize,
         concat!(
             "Offset of field: ",
@@ -818,7 +818,7 @@ fn bindgen_test_layout_apm_bios_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<apm_bios_info>())).cseg_16_len as *const _ as usize },
+        ::std::mem::offset_of!(apm_bios_info, cseg_16_len),
         16usize,
         concat!(
             "Offset of field: ",
@@ -828,7 +828,7 @@ fn bindgen_test_layout_apm_bios_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<apm_bios_info>())).dseg_len as *const _ as usize },
+        ::std::mem::offset_of!(apm_bios_info, dseg_len),
         18usize,
         concat!(
             "Offset of field: ",
@@ -897,10 +897,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_1>())).base_address
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_1, base_address),
         0usize,
         concat!(
             "Offset of field: ",
@@ -910,10 +907,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_1>())).reserved1
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_1, reserved1),
         2usize,
         concat!(
             "Offset of field: ",
@@ -923,10 +917,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_1>())).reserved2
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_1, reserved2),
         4usize,
         concat!(
             "Offset of field: ",
@@ -964,10 +955,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_2>())).bus
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_2, bus),
         0usize,
         concat!(
             "Offset of field: ",
@@ -977,10 +965,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_2>())).slot
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_2, slot),
         1usize,
         concat!(
             "Offset of field: ",
@@ -990,10 +975,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_2>())).function
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_2, function),
         2usize,
         concat!(
             "Offset of field: ",
@@ -1003,10 +985,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_2>())).channel
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_2, channel),
         3usize,
         concat!(
             "Offset of field: ",
@@ -1016,10 +995,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_2>())).reserved
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_2, reserved),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1053,10 +1029,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_3() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_3>())).reserved
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_3, reserved),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1090,10 +1063,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_4() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_4>())).reserved
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_4, reserved),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1127,10 +1097,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_5() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_5>())).reserved
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_5, reserved),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1164,10 +1131,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1__bindgen_ty_6() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1__bindgen_ty_6>())).reserved
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1__bindgen_ty_6, reserved),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1190,9 +1154,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1() {
         concat!("Alignment of ", stringify!(edd_device_params__bindgen_ty_1))
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1>())).isa as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1, isa),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1202,9 +1164,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1>())).pci as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1, pci),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1214,9 +1174,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1>())).ibnd as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1, ibnd),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1226,9 +1184,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1>())).xprs as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1, xprs),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1238,9 +1194,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1>())).htpt as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1, htpt),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1250,9 +1204,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_1>())).unknown as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_1, unknown),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1310,10 +1262,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_1>())).device
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_1, device),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1323,10 +1272,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_1>())).reserved1
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_1, reserved1),
         1usize,
         concat!(
             "Offset of field: ",
@@ -1336,10 +1282,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_1>())).reserved2
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_1, reserved2),
         2usize,
         concat!(
             "Offset of field: ",
@@ -1349,10 +1292,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_1>())).reserved3
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_1, reserved3),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1362,10 +1302,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_1>())).reserved4
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_1, reserved4),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1404,10 +1341,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_2>())).device
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_2, device),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1417,10 +1351,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_2>())).lun
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_2, lun),
         1usize,
         concat!(
             "Offset of field: ",
@@ -1430,10 +1361,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_2>())).reserved1
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_2, reserved1),
         2usize,
         concat!(
             "Offset of field: ",
@@ -1443,10 +1371,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_2>())).reserved2
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_2, reserved2),
         3usize,
         concat!(
             "Offset of field: ",
@@ -1456,10 +1381,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_2>())).reserved3
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_2, reserved3),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1469,10 +1391,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_2>())).reserved4
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_2, reserved4),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1509,10 +1428,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_3() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_3>())).id as *const _
-                as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_3, id),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1522,10 +1438,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_3() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_3>())).lun
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_3, lun),
         2usize,
         concat!(
             "Offset of field: ",
@@ -1535,10 +1448,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_3() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_3>())).reserved1
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_3, reserved1),
         10usize,
         concat!(
             "Offset of field: ",
@@ -1548,10 +1458,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_3() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_3>())).reserved2
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_3, reserved2),
         12usize,
         concat!(
             "Offset of field: ",
@@ -1586,10 +1493,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_4() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_4>())).serial_number
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_4, serial_number),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1599,10 +1503,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_4() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_4>())).reserved
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_4, reserved),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1637,10 +1538,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_5() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_5>())).eui
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_5, eui),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1650,10 +1548,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_5() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_5>())).reserved
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_5, reserved),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1688,10 +1583,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_6() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_6>())).wwid
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_6, wwid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1701,10 +1593,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_6() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_6>())).lun
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_6, lun),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1739,10 +1628,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_7() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_7>())).identity_tag
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_7, identity_tag),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1752,10 +1638,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_7() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_7>())).reserved
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_7, reserved),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1791,10 +1674,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_8() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_8>())).array_number
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_8, array_number),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1804,10 +1684,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_8() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_8>())).reserved1
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_8, reserved1),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1817,10 +1694,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_8() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_8>())).reserved2
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_8, reserved2),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1858,10 +1732,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_9() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_9>())).device
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_9, device),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1871,10 +1742,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_9() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_9>())).reserved1
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_9, reserved1),
         1usize,
         concat!(
             "Offset of field: ",
@@ -1884,10 +1752,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_9() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_9>())).reserved2
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_9, reserved2),
         2usize,
         concat!(
             "Offset of field: ",
@@ -1897,10 +1762,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_9() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_9>())).reserved3
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_9, reserved3),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1910,10 +1772,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_9() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_9>())).reserved4
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_9, reserved4),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1948,10 +1807,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_10() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_10>())).reserved1
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_10, reserved1),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1961,10 +1817,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2__bindgen_ty_10() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2__bindgen_ty_10>())).reserved2
-                as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2__bindgen_ty_10, reserved2),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1987,9 +1840,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         concat!("Alignment of ", stringify!(edd_device_params__bindgen_ty_2))
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).ata as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, ata),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1999,9 +1850,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).atapi as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, atapi),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2011,9 +1860,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).scsi as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, scsi),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2023,9 +1870,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).usb as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, usb),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2035,9 +1880,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).i1394 as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, i1394),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2047,9 +1890,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).fibre as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, fibre),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2059,9 +1900,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).i2o as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, i2o),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2071,9 +1910,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).raid as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, raid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2083,9 +1920,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).sata as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, sata),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2095,9 +1930,7 @@ fn bindgen_test_layout_edd_device_params__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params__bindgen_ty_2>())).unknown as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params__bindgen_ty_2, unknown),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2125,7 +1958,7 @@ fn bindgen_test_layout_edd_device_params() {
         concat!("Alignment of ", stringify!(edd_device_params))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).length as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, length),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2135,7 +1968,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).info_flags as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, info_flags),
         2usize,
         concat!(
             "Offset of field: ",
@@ -2145,9 +1978,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params>())).num_default_cylinders as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params, num_default_cylinders),
         4usize,
         concat!(
             "Offset of field: ",
@@ -2157,9 +1988,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params>())).num_default_heads as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params, num_default_heads),
         8usize,
         concat!(
             "Offset of field: ",
@@ -2169,9 +1998,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params>())).sectors_per_track as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params, sectors_per_track),
         12usize,
         concat!(
             "Offset of field: ",
@@ -2181,9 +2008,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params>())).number_of_sectors as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params, number_of_sectors),
         16usize,
         concat!(
             "Offset of field: ",
@@ -2193,9 +2018,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params>())).bytes_per_sector as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params, bytes_per_sector),
         24usize,
         concat!(
             "Offset of field: ",
@@ -2205,7 +2028,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).dpte_ptr as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, dpte_ptr),
         26usize,
         concat!(
             "Offset of field: ",
@@ -2215,7 +2038,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).key as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, key),
         30usize,
         concat!(
             "Offset of field: ",
@@ -2225,10 +2048,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params>())).device_path_info_length as *const _
-                as usize
-        },
+        ::std::mem::offset_of!(edd_device_params, device_path_info_length),
         32usize,
         concat!(
             "Offset of field: ",
@@ -2238,7 +2058,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).reserved2 as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, reserved2),
         33usize,
         concat!(
             "Offset of field: ",
@@ -2248,7 +2068,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).reserved3 as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, reserved3),
         34usize,
         concat!(
             "Offset of field: ",
@@ -2258,7 +2078,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).host_bus_type as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, host_bus_type),
         36usize,
         concat!(
             "Offset of field: ",
@@ -2268,9 +2088,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params>())).interface_type as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params, interface_type),
         40usize,
         concat!(
             "Offset of field: ",
@@ -2280,9 +2098,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_device_params>())).interface_path as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_device_params, interface_path),
         48usize,
         concat!(
             "Offset of field: ",
@@ -2292,7 +2108,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).device_path as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, device_path),
         56usize,
         concat!(
             "Offset of field: ",
@@ -2302,7 +2118,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).reserved4 as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, reserved4),
         72usize,
         concat!(
             "Offset of field: ",
@@ -2312,7 +2128,7 @@ fn bindgen_test_layout_edd_device_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_device_params>())).checksum as *const _ as usize },
+        ::std::mem::offset_of!(edd_device_params, checksum),
         73usize,
         concat!(
             "Offset of field: ",
@@ -2351,7 +2167,7 @@ fn bindgen_test_layout_edd_info() {
         concat!("Alignment of ", stringify!(edd_info))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_info>())).device as *const _ as usize },
+        ::std::mem::offset_of!(edd_info, device),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2361,7 +2177,7 @@ fn bindgen_test_layout_edd_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_info>())).version as *const _ as usize },
+        ::std::mem::offset_of!(edd_info, version),
         1usize,
         concat!(
             "Offset of field: ",
@@ -2371,7 +2187,7 @@ fn bindgen_test_layout_edd_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_info>())).interface_support as *const _ as usize },
+        ::std::mem::offset_of!(edd_info, interface_support),
         2usize,
         concat!(
             "Offset of field: ",
@@ -2381,7 +2197,7 @@ fn bindgen_test_layout_edd_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_info>())).legacy_max_cylinder as *const _ as usize },
+        ::std::mem::offset_of!(edd_info, legacy_max_cylinder),
         4usize,
         concat!(
             "Offset of field: ",
@@ -2391,7 +2207,7 @@ fn bindgen_test_layout_edd_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_info>())).legacy_max_head as *const _ as usize },
+        ::std::mem::offset_of!(edd_info, legacy_max_head),
         6usize,
         concat!(
             "Offset of field: ",
@@ -2401,9 +2217,7 @@ fn bindgen_test_layout_edd_info() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<edd_info>())).legacy_sectors_per_track as *const _ as usize
-        },
+        ::std::mem::offset_of!(edd_info, legacy_sectors_per_track),
         7usize,
         concat!(
             "Offset of field: ",
@@ -2413,7 +2227,7 @@ fn bindgen_test_layout_edd_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd_info>())).params as *const _ as usize },
+        ::std::mem::offset_of!(edd_info, params),
         8usize,
         concat!(
             "Offset of field: ",
@@ -2449,7 +2263,7 @@ fn bindgen_test_layout_edd() {
         concat!("Alignment of ", stringify!(edd))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd>())).mbr_signature as *const _ as usize },
+        ::std::mem::offset_of!(edd, mbr_signature),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2459,7 +2273,7 @@ fn bindgen_test_layout_edd() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd>())).edd_info as *const _ as usize },
+        ::std::mem::offset_of!(edd, edd_info),
         64usize,
         concat!(
             "Offset of field: ",
@@ -2469,7 +2283,7 @@ fn bindgen_test_layout_edd() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd>())).mbr_signature_nr as *const _ as usize },
+        ::std::mem::offset_of!(edd, mbr_signature_nr),
         556usize,
         concat!(
             "Offset of field: ",
@@ -2479,7 +2293,7 @@ fn bindgen_test_layout_edd() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edd>())).edd_info_nr as *const _ as usize },
+        ::std::mem::offset_of!(edd, edd_info_nr),
         557usize,
         concat!(
             "Offset of field: ",
@@ -2515,7 +2329,7 @@ fn bindgen_test_layout_ist_info() {
         concat!("Alignment of ", stringify!(ist_info))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ist_info>())).signature as *const _ as usize },
+        ::std::mem::offset_of!(ist_info, signature),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2525,7 +2339,7 @@ fn bindgen_test_layout_ist_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ist_info>())).command as *const _ as usize },
+        ::std::mem::offset_of!(ist_info, command),
         4usize,
         concat!(
             "Offset of field: ",
@@ -2535,7 +2349,7 @@ fn bindgen_test_layout_ist_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ist_info>())).event as *const _ as usize },
+        ::std::mem::offset_of!(ist_info, event),
         8usize,
         concat!(
             "Offset of field: ",
@@ -2545,7 +2359,7 @@ fn bindgen_test_layout_ist_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ist_info>())).perf_level as *const _ as usize },
+        ::std::mem::offset_of!(ist_info, perf_level),
         12usize,
         concat!(
             "Offset of field: ",
@@ -2573,7 +2387,7 @@ fn bindgen_test_layout_edid_info() {
         concat!("Alignment of ", stringify!(edid_info))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<edid_info>())).dummy as *const _ as usize },
+        ::std::mem::offset_of!(edid_info, dummy),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2609,7 +2423,7 @@ fn bindgen_test_layout_setup_data() {
         concat!("Alignment of ", stringify!(setup_data))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_data>())).next as *const _ as usize },
+        ::std::mem::offset_of!(setup_data, next),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2619,7 +2433,7 @@ fn bindgen_test_layout_setup_data() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_data>())).type_ as *const _ as usize },
+        ::std::mem::offset_of!(setup_data, type_),
         8usize,
         concat!(
             "Offset of field: ",
@@ -2629,7 +2443,7 @@ fn bindgen_test_layout_setup_data() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_data>())).len as *const _ as usize },
+        ::std::mem::offset_of!(setup_data, len),
         12usize,
         concat!(
             "Offset of field: ",
@@ -2639,7 +2453,7 @@ fn bindgen_test_layout_setup_data() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_data>())).data as *const _ as usize },
+        ::std::mem::offset_of!(setup_data, data),
         16usize,
         concat!(
             "Offset of field: ",
@@ -2704,7 +2518,7 @@ fn bindgen_test_layout_setup_header() {
         concat!("Alignment of ", stringify!(setup_header))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).setup_sects as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, setup_sects),
         0usize,
         concat!(
             "Offset of field: ",
@@ -2714,7 +2528,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).root_flags as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, root_flags),
         1usize,
         concat!(
             "Offset of field: ",
@@ -2724,7 +2538,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).syssize as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, syssize),
         3usize,
         concat!(
             "Offset of field: ",
@@ -2734,7 +2548,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).ram_size as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, ram_size),
         7usize,
         concat!(
             "Offset of field: ",
@@ -2744,7 +2558,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).vid_mode as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, vid_mode),
         9usize,
         concat!(
             "Offset of field: ",
@@ -2754,7 +2568,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).root_dev as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, root_dev),
         11usize,
         concat!(
             "Offset of field: ",
@@ -2764,7 +2578,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).boot_flag as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, boot_flag),
         13usize,
         concat!(
             "Offset of field: ",
@@ -2774,7 +2588,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).jump as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, jump),
         15usize,
         concat!(
             "Offset of field: ",
@@ -2784,7 +2598,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).header as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, header),
         17usize,
         concat!(
             "Offset of field: ",
@@ -2794,7 +2608,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).version as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, version),
         21usize,
         concat!(
             "Offset of field: ",
@@ -2804,7 +2618,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).realmode_swtch as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, realmode_swtch),
         23usize,
         concat!(
             "Offset of field: ",
@@ -2814,7 +2628,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).start_sys_seg as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, start_sys_seg),
         27usize,
         concat!(
             "Offset of field: ",
@@ -2824,7 +2638,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).kernel_version as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, kernel_version),
         29usize,
         concat!(
             "Offset of field: ",
@@ -2834,7 +2648,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).type_of_loader as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, type_of_loader),
         31usize,
         concat!(
             "Offset of field: ",
@@ -2844,7 +2658,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).loadflags as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, loadflags),
         32usize,
         concat!(
             "Offset of field: ",
@@ -2854,7 +2668,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).setup_move_size as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, setup_move_size),
         33usize,
         concat!(
             "Offset of field: ",
@@ -2864,7 +2678,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).code32_start as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, code32_start),
         35usize,
         concat!(
             "Offset of field: ",
@@ -2874,7 +2688,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).ramdisk_image as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, ramdisk_image),
         39usize,
         concat!(
             "Offset of field: ",
@@ -2884,7 +2698,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).ramdisk_size as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, ramdisk_size),
         43usize,
         concat!(
             "Offset of field: ",
@@ -2894,7 +2708,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).bootsect_kludge as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, bootsect_kludge),
         47usize,
         concat!(
             "Offset of field: ",
@@ -2904,7 +2718,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).heap_end_ptr as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, heap_end_ptr),
         51usize,
         concat!(
             "Offset of field: ",
@@ -2914,7 +2728,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).ext_loader_ver as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, ext_loader_ver),
         53usize,
         concat!(
             "Offset of field: ",
@@ -2924,7 +2738,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).ext_loader_type as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, ext_loader_type),
         54usize,
         concat!(
             "Offset of field: ",
@@ -2934,7 +2748,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).cmd_line_ptr as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, cmd_line_ptr),
         55usize,
         concat!(
             "Offset of field: ",
@@ -2944,7 +2758,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).initrd_addr_max as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, initrd_addr_max),
         59usize,
         concat!(
             "Offset of field: ",
@@ -2954,7 +2768,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).kernel_alignment as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, kernel_alignment),
         63usize,
         concat!(
             "Offset of field: ",
@@ -2964,7 +2778,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).relocatable_kernel as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, relocatable_kernel),
         67usize,
         concat!(
             "Offset of field: ",
@@ -2974,7 +2788,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).min_alignment as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, min_alignment),
         68usize,
         concat!(
             "Offset of field: ",
@@ -2984,7 +2798,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).xloadflags as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, xloadflags),
         69usize,
         concat!(
             "Offset of field: ",
@@ -2994,7 +2808,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).cmdline_size as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, cmdline_size),
         71usize,
         concat!(
             "Offset of field: ",
@@ -3004,7 +2818,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).hardware_subarch as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, hardware_subarch),
         75usize,
         concat!(
             "Offset of field: ",
@@ -3014,9 +2828,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<setup_header>())).hardware_subarch_data as *const _ as usize
-        },
+        ::std::mem::offset_of!(setup_header, hardware_subarch_data),
         79usize,
         concat!(
             "Offset of field: ",
@@ -3026,7 +2838,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).payload_offset as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, payload_offset),
         87usize,
         concat!(
             "Offset of field: ",
@@ -3036,7 +2848,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).payload_length as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, payload_length),
         91usize,
         concat!(
             "Offset of field: ",
@@ -3046,7 +2858,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).setup_data as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, setup_data),
         95usize,
         concat!(
             "Offset of field: ",
@@ -3056,7 +2868,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).pref_address as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, pref_address),
         103usize,
         concat!(
             "Offset of field: ",
@@ -3066,7 +2878,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).init_size as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, init_size),
         111usize,
         concat!(
             "Offset of field: ",
@@ -3076,7 +2888,7 @@ fn bindgen_test_layout_setup_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<setup_header>())).handover_offset as *const _ as usize },
+        ::std::mem::offset_of!(setup_header, handover_offset),
         115usize,
         concat!(
             "Offset of field: ",
@@ -3105,7 +2917,7 @@ fn bindgen_test_layout_sys_desc_table() {
         concat!("Alignment of ", stringify!(sys_desc_table))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sys_desc_table>())).length as *const _ as usize },
+        ::std::mem::offset_of!(sys_desc_table, length),
         0usize,
         concat!(
             "Offset of field: ",
@@ -3115,7 +2927,7 @@ fn bindgen_test_layout_sys_desc_table() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<sys_desc_table>())).table as *const _ as usize },
+        ::std::mem::offset_of!(sys_desc_table, table),
         2usize,
         concat!(
             "Offset of field: ",
@@ -3146,7 +2958,7 @@ fn bindgen_test_layout_olpc_ofw_header() {
         concat!("Alignment of ", stringify!(olpc_ofw_header))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<olpc_ofw_header>())).ofw_magic as *const _ as usize },
+        ::std::mem::offset_of!(olpc_ofw_header, ofw_magic),
         0usize,
         concat!(
             "Offset of field: ",
@@ -3156,7 +2968,7 @@ fn bindgen_test_layout_olpc_ofw_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<olpc_ofw_header>())).ofw_version as *const _ as usize },
+        ::std::mem::offset_of!(olpc_ofw_header, ofw_version),
         4usize,
         concat!(
             "Offset of field: ",
@@ -3166,7 +2978,7 @@ fn bindgen_test_layout_olpc_ofw_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<olpc_ofw_header>())).cif_handler as *const _ as usize },
+        ::std::mem::offset_of!(olpc_ofw_header, cif_handler),
         8usize,
         concat!(
             "Offset of field: ",
@@ -3176,7 +2988,7 @@ fn bindgen_test_layout_olpc_ofw_header() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<olpc_ofw_header>())).irq_desc_table as *const _ as usize },
+        ::std::mem::offset_of!(olpc_ofw_header, irq_desc_table),
         12usize,
         concat!(
             "Offset of field: ",
@@ -3211,7 +3023,7 @@ fn bindgen_test_layout_efi_info() {
         concat!("Alignment of ", stringify!(efi_info))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<efi_info>())).efi_loader_signature as *const _ as usize },
+        ::std::mem::offset_of!(efi_info, efi_loader_signature),
         0usize,
         concat!(
             "Offset of field: ",
@@ -3221,7 +3033,7 @@ fn bindgen_test_layout_efi_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<efi_info>())).efi_systab as *const _ as usize },
+        ::std::mem::offset_of!(efi_info, efi_systab),
         4usize,
         concat!(
             "Offset of field: ",
@@ -3231,7 +3043,7 @@ fn bindgen_test_layout_efi_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<efi_info>())).efi_memdesc_size as *const _ as usize },
+        ::std::mem::offset_of!(efi_info, efi_memdesc_size),
         8usize,
         concat!(
             "Offset of field: ",
@@ -3241,7 +3053,7 @@ fn bindgen_test_layout_efi_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<efi_info>())).efi_memdesc_version as *const _ as usize },
+        ::std::mem::offset_of!(efi_info, efi_memdesc_version),
         12usize,
         concat!(
             "Offset of field: ",
@@ -3251,7 +3063,7 @@ fn bindgen_test_layout_efi_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<efi_info>())).efi_memmap as *const _ as usize },
+        ::std::mem::offset_of!(efi_info, efi_memmap),
         16usize,
         concat!(
             "Offset of field: ",
@@ -3261,7 +3073,7 @@ fn bindgen_test_layout_efi_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<efi_info>())).efi_memmap_size as *const _ as usize },
+        ::std::mem::offset_of!(efi_info, efi_memmap_size),
         20usize,
         concat!(
             "Offset of field: ",
@@ -3271,7 +3083,7 @@ fn bindgen_test_layout_efi_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<efi_info>())).efi_systab_hi as *const _ as usize },
+        ::std::mem::offset_of!(efi_info, efi_systab_hi),
         24usize,
         concat!(
             "Offset of field: ",
@@ -3281,7 +3093,7 @@ fn bindgen_test_layout_efi_info() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<efi_info>())).efi_memmap_hi as *const _ as usize },
+        ::std::mem::offset_of!(efi_info, efi_memmap_hi),
         28usize,
         concat!(
             "Offset of field: ",
@@ -3311,7 +3123,7 @@ fn bindgen_test_layout_boot_e820_entry() {
         concat!("Alignment of ", stringify!(boot_e820_entry))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_e820_entry>())).addr as *const _ as usize },
+        ::std::mem::offset_of!(boot_e820_entry, addr),
         0usize,
         concat!(
             "Offset of field: ",
@@ -3321,7 +3133,7 @@ fn bindgen_test_layout_boot_e820_entry() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_e820_entry>())).size as *const _ as usize },
+        ::std::mem::offset_of!(boot_e820_entry, size),
         8usize,
         concat!(
             "Offset of field: ",
@@ -3331,7 +3143,7 @@ fn bindgen_test_layout_boot_e820_entry() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_e820_entry>())).type_ as *const _ as usize },
+        ::std::mem::offset_of!(boot_e820_entry, type_),
         16usize,
         concat!(
             "Offset of field: ",
@@ -3367,7 +3179,7 @@ fn bindgen_test_layout_jailhouse_setup_data() {
         concat!("Alignment of ", stringify!(jailhouse_setup_data))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<jailhouse_setup_data>())).version as *const _ as usize },
+        ::std::mem::offset_of!(jailhouse_setup_data, version),
         0usize,
         concat!(
             "Offset of field: ",
@@ -3377,9 +3189,7 @@ fn bindgen_test_layout_jailhouse_setup_data() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<jailhouse_setup_data>())).compatible_version as *const _ as usize
-        },
+        ::std::mem::offset_of!(jailhouse_setup_data, compatible_version),
         2usize,
         concat!(
             "Offset of field: ",
@@ -3389,9 +3199,7 @@ fn bindgen_test_layout_jailhouse_setup_data() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<jailhouse_setup_data>())).pm_timer_address as *const _ as usize
-        },
+        ::std::mem::offset_of!(jailhouse_setup_data, pm_timer_address),
         4usize,
         concat!(
             "Offset of field: ",
@@ -3401,7 +3209,7 @@ fn bindgen_test_layout_jailhouse_setup_data() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<jailhouse_setup_data>())).num_cpus as *const _ as usize },
+        ::std::mem::offset_of!(jailhouse_setup_data, num_cpus),
         6usize,
         concat!(
             "Offset of field: ",
@@ -3411,9 +3219,7 @@ fn bindgen_test_layout_jailhouse_setup_data() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<jailhouse_setup_data>())).pci_mmconfig_base as *const _ as usize
-        },
+        ::std::mem::offset_of!(jailhouse_setup_data, pci_mmconfig_base),
         8usize,
         concat!(
             "Offset of field: ",
@@ -3423,7 +3229,7 @@ fn bindgen_test_layout_jailhouse_setup_data() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<jailhouse_setup_data>())).tsc_khz as *const _ as usize },
+        ::std::mem::offset_of!(jailhouse_setup_data, tsc_khz),
         16usize,
         concat!(
             "Offset of field: ",
@@ -3433,7 +3239,7 @@ fn bindgen_test_layout_jailhouse_setup_data() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<jailhouse_setup_data>())).apic_khz as *const _ as usize },
+        ::std::mem::offset_of!(jailhouse_setup_data, apic_khz),
         20usize,
         concat!(
             "Offset of field: ",
@@ -3443,9 +3249,7 @@ fn bindgen_test_layout_jailhouse_setup_data() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<jailhouse_setup_data>())).standard_ioapic as *const _ as usize
-        },
+        ::std::mem::offset_of!(jailhouse_setup_data, standard_ioapic),
         24usize,
         concat!(
             "Offset of field: ",
@@ -3455,7 +3259,7 @@ fn bindgen_test_layout_jailhouse_setup_data() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<jailhouse_setup_data>())).cpu_ids as *const _ as usize },
+        ::std::mem::offset_of!(jailhouse_setup_data, cpu_ids),
         25usize,
         concat!(
             "Offset of field: ",
@@ -3521,7 +3325,7 @@ fn bindgen_test_layout_boot_params() {
         concat!("Alignment of ", stringify!(boot_params))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).screen_info as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, screen_info),
         0usize,
         concat!(
             "Offset of field: ",
@@ -3531,7 +3335,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).apm_bios_info as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, apm_bios_info),
         64usize,
         concat!(
             "Offset of field: ",
@@ -3541,7 +3345,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>()))._pad2 as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, _pad2),
         84usize,
         concat!(
             "Offset of field: ",
@@ -3551,7 +3355,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).tboot_addr as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, tboot_addr),
         88usize,
         concat!(
             "Offset of field: ",
@@ -3561,7 +3365,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).ist_info as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, ist_info),
         96usize,
         concat!(
             "Offset of field: ",
@@ -3571,7 +3375,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).acpi_rsdp_addr as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, acpi_rsdp_addr),
         112usize,
         concat!(
             "Offset of field: ",
@@ -3581,7 +3385,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>()))._pad3 as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, _pad3),
         120usize,
         concat!(
             "Offset of field: ",
@@ -3591,7 +3395,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).hd0_info as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, hd0_info),
         128usize,
         concat!(
             "Offset of field: ",
@@ -3601,7 +3405,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).hd1_info as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, hd1_info),
         144usize,
         concat!(
             "Offset of field: ",
@@ -3611,7 +3415,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).sys_desc_table as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, sys_desc_table),
         160usize,
         concat!(
             "Offset of field: ",
@@ -3621,7 +3425,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).olpc_ofw_header as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, olpc_ofw_header),
         176usize,
         concat!(
             "Offset of field: ",
@@ -3631,7 +3435,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).ext_ramdisk_image as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, ext_ramdisk_image),
         192usize,
         concat!(
             "Offset of field: ",
@@ -3641,7 +3445,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).ext_ramdisk_size as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, ext_ramdisk_size),
         196usize,
         concat!(
             "Offset of field: ",
@@ -3651,7 +3455,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).ext_cmd_line_ptr as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, ext_cmd_line_ptr),
         200usize,
         concat!(
             "Offset of field: ",
@@ -3661,7 +3465,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>()))._pad4 as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, _pad4),
         204usize,
         concat!(
             "Offset of field: ",
@@ -3671,7 +3475,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).edid_info as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, edid_info),
         320usize,
         concat!(
             "Offset of field: ",
@@ -3681,7 +3485,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).efi_info as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, efi_info),
         448usize,
         concat!(
             "Offset of field: ",
@@ -3691,7 +3495,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).alt_mem_k as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, alt_mem_k),
         480usize,
         concat!(
             "Offset of field: ",
@@ -3701,7 +3505,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).scratch as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, scratch),
         484usize,
         concat!(
             "Offset of field: ",
@@ -3711,7 +3515,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).e820_entries as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, e820_entries),
         488usize,
         concat!(
             "Offset of field: ",
@@ -3721,7 +3525,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).eddbuf_entries as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, eddbuf_entries),
         489usize,
         concat!(
             "Offset of field: ",
@@ -3731,9 +3535,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<boot_params>())).edd_mbr_sig_buf_entries as *const _ as usize
-        },
+        ::std::mem::offset_of!(boot_params, edd_mbr_sig_buf_entries),
         490usize,
         concat!(
             "Offset of field: ",
@@ -3743,7 +3545,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).kbd_status as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, kbd_status),
         491usize,
         concat!(
             "Offset of field: ",
@@ -3753,7 +3555,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).secure_boot as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, secure_boot),
         492usize,
         concat!(
             "Offset of field: ",
@@ -3763,7 +3565,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>()))._pad5 as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, _pad5),
         493usize,
         concat!(
             "Offset of field: ",
@@ -3773,7 +3575,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).sentinel as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, sentinel),
         495usize,
         concat!(
             "Offset of field: ",
@@ -3783,7 +3585,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>()))._pad6 as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, _pad6),
         496usize,
         concat!(
             "Offset of field: ",
@@ -3793,7 +3595,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).hdr as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, hdr),
         497usize,
         concat!(
             "Offset of field: ",
@@ -3803,7 +3605,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>()))._pad7 as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, _pad7),
         616usize,
         concat!(
             "Offset of field: ",
@@ -3813,7 +3615,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).edd_mbr_sig_buffer as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, edd_mbr_sig_buffer),
         656usize,
         concat!(
             "Offset of field: ",
@@ -3823,7 +3625,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).e820_table as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, e820_table),
         720usize,
         concat!(
             "Offset of field: ",
@@ -3833,7 +3635,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>()))._pad8 as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, _pad8),
         3280usize,
         concat!(
             "Offset of field: ",
@@ -3843,7 +3645,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>())).eddbuf as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, eddbuf),
         3328usize,
         concat!(
             "Offset of field: ",
@@ -3853,7 +3655,7 @@ fn bindgen_test_layout_boot_params() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<boot_params>()))._pad9 as *const _ as usize },
+        ::std::mem::offset_of!(boot_params, _pad9),
         3820usize,
         concat!(
             "Offset of field: ",
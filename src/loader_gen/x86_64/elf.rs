@@ -19,13 +19,45 @@
  * and relocate definitions and tests for clarity.
  */
 
+pub const ET_EXEC: u32 = 2;
+pub const ET_DYN: u32 = 3;
+
 pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+pub const PT_INTERP: u32 = 3;
 pub const PT_NOTE: u32 = 4;
 
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+
+pub const SHN_UNDEF: u32 = 0;
+
+pub const DT_NULL: u32 = 0;
+pub const DT_RELA: u32 = 7;
+pub const DT_RELASZ: u32 = 8;
+pub const DT_RELAENT: u32 = 9;
+pub const DT_REL: u32 = 17;
+pub const DT_RELSZ: u32 = 18;
+pub const DT_RELENT: u32 = 19;
+pub const DT_RELR: u32 = 36;
+
+pub const EM_386: u32 = 3;
+pub const EM_X86_64: u32 = 62;
+
+pub const R_X86_64_NONE: u32 = 0;
+pub const R_X86_64_RELATIVE: u32 = 8;
+pub const R_386_NONE: u32 = 0;
+pub const R_386_RELATIVE: u32 = 8;
+
+pub const PF_X: u32 = 1;
+pub const PF_W: u32 = 2;
+pub const PF_R: u32 = 4;
+
 pub const EI_MAG0: u32 = 0;
 pub const EI_MAG1: u32 = 1;
 pub const EI_MAG2: u32 = 2;
 pub const EI_MAG3: u32 = 3;
+pub const EI_CLASS: u32 = 4;
 pub const EI_DATA: u32 = 5;
 
 pub const ELFMAG0: u32 = 127;
@@ -36,7 +68,11 @@ pub const ELFMAG1: u8 = b'E';
 pub const ELFMAG2: u8 = b'L';
 pub const ELFMAG3: u8 = b'F';
 
+pub const ELFCLASS32: u32 = 1;
+pub const ELFCLASS64: u32 = 2;
+
 pub const ELFDATA2LSB: u32 = 1;
+pub const ELFDATA2MSB: u32 = 2;
 
 pub type __s8 = ::std::os::raw::c_schar;
 pub type __u8 = ::std::os::raw::c_uchar;
@@ -47,13 +83,70 @@ pub type __u32 = ::std::os::raw::c_uint;
 pub type __s64 = ::std::os::raw::c_longlong;
 pub type __u64 = ::std::os::raw::c_ulonglong;
 
+pub type Elf32_Addr = __u32;
+pub type Elf32_Half = __u16;
+pub type Elf32_Off = __u32;
+pub type Elf32_Sword = __s32;
+pub type Elf32_Word = __u32;
+
 pub type Elf64_Addr = __u64;
 pub type Elf64_Half = __u16;
 pub type Elf64_Off = __u64;
 pub type Elf64_Sword = __s32;
+pub type Elf64_Sxword = __s64;
 pub type Elf64_Word = __u32;
 pub type Elf64_Xword = __u64;
 
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf32_hdr {
+    pub e_ident: [::std::os::raw::c_uchar; 16usize],
+    pub e_type: Elf32_Half,
+    pub e_machine: Elf32_Half,
+    pub e_version: Elf32_Word,
+    pub e_entry: Elf32_Addr,
+    pub e_phoff: Elf32_Off,
+    pub e_shoff: Elf32_Off,
+    pub e_flags: Elf32_Word,
+    pub e_ehsize: Elf32_Half,
+    pub e_phentsize: Elf32_Half,
+    pub e_phnum: Elf32_Half,
+    pub e_shentsize: Elf32_Half,
+    pub e_shnum: Elf32_Half,
+    pub e_shstrndx: Elf32_Half,
+}
+pub type Elf32_Ehdr = elf32_hdr;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf32_phdr {
+    pub p_type: Elf32_Word,
+    pub p_offset: Elf32_Off,
+    pub p_vaddr: Elf32_Addr,
+    pub p_paddr: Elf32_Addr,
+    pub p_filesz: Elf32_Word,
+    pub p_memsz: Elf32_Word,
+    pub p_flags: Elf32_Word,
+    pub p_align: Elf32_Word,
+}
+pub type Elf32_Phdr = elf32_phdr;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf32_shdr {
+    pub sh_name: Elf32_Word,
+    pub sh_type: Elf32_Word,
+    pub sh_flags: Elf32_Word,
+    pub sh_addr: Elf32_Addr,
+    pub sh_offset: Elf32_Off,
+    pub sh_size: Elf32_Word,
+    pub sh_link: Elf32_Word,
+    pub sh_info: Elf32_Word,
+    pub sh_addralign: Elf32_Word,
+    pub sh_entsize: Elf32_Word,
+}
+pub type Elf32_Shdr = elf32_shdr;
+
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct elf64_hdr {
@@ -88,6 +181,22 @@ pub struct elf64_phdr {
 }
 pub type Elf64_Phdr = elf64_phdr;
 
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf64_shdr {
+    pub sh_name: Elf64_Word,
+    pub sh_type: Elf64_Word,
+    pub sh_flags: Elf64_Xword,
+    pub sh_addr: Elf64_Addr,
+    pub sh_offset: Elf64_Off,
+    pub sh_size: Elf64_Xword,
+    pub sh_link: Elf64_Word,
+    pub sh_info: Elf64_Word,
+    pub sh_addralign: Elf64_Xword,
+    pub sh_entsize: Elf64_Xword,
+}
+pub type Elf64_Shdr = elf64_shdr;
+
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct elf64_note {
@@ -97,158 +206,217 @@ pub struct elf64_note {
 }
 pub type Elf64_Nhdr = elf64_note;
 
+// The `d_un` union is flattened to its `d_val` member.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf32_dyn {
+    pub d_tag: Elf32_Sword,
+    pub d_val: Elf32_Word,
+}
+pub type Elf32_Dyn = elf32_dyn;
+
+// The `d_un` union is flattened to its `d_val` member.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf64_dyn {
+    pub d_tag: Elf64_Sxword,
+    pub d_val: Elf64_Xword,
+}
+pub type Elf64_Dyn = elf64_dyn;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf32_rel {
+    pub r_offset: Elf32_Addr,
+    pub r_info: Elf32_Word,
+}
+pub type Elf32_Rel = elf32_rel;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf64_rela {
+    pub r_offset: Elf64_Addr,
+    pub r_info: Elf64_Xword,
+    pub r_addend: Elf64_Sxword,
+}
+pub type Elf64_Rela = elf64_rela;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf32_sym {
+    pub st_name: Elf32_Word,
+    pub st_value: Elf32_Addr,
+    pub st_size: Elf32_Word,
+    pub st_info: ::std::os::raw::c_uchar,
+    pub st_other: ::std::os::raw::c_uchar,
+    pub st_shndx: Elf32_Half,
+}
+pub type Elf32_Sym = elf32_sym;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct elf64_sym {
+    pub st_name: Elf64_Word,
+    pub st_info: ::std::os::raw::c_uchar,
+    pub st_other: ::std::os::raw::c_uchar,
+    pub st_shndx: Elf64_Half,
+    pub st_value: Elf64_Addr,
+    pub st_size: Elf64_Xword,
+}
+pub type Elf64_Sym = elf64_sym;
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn bindgen_test_layout_elf64_hdr() {
+    fn bindgen_test_layout_elf32_hdr() {
         assert_eq!(
-            ::std::mem::size_of::<elf64_hdr>(),
-            64usize,
-            concat!("Size of: ", stringify!(elf64_hdr))
+            ::std::mem::size_of::<elf32_hdr>(),
+            52usize,
+            concat!("Size of: ", stringify!(elf32_hdr))
         );
         assert_eq!(
-            ::std::mem::align_of::<elf64_hdr>(),
-            8usize,
-            concat!("Alignment of ", stringify!(elf64_hdr))
+            ::std::mem::align_of::<elf32_hdr>(),
+            4usize,
+            concat!("Alignment of ", stringify!(elf32_hdr))
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_ident as *const _ as usize },
+            ::std::mem::offset_of!(elf32_hdr, e_ident),
             0usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_ident)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_type as *const _ as usize },
+            ::std::mem::offset_of!(elf32_hdr, e_type),
             16usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_type)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_machine as *const _ as usize },
+            ::std::mem::offset_of!(elf32_hdr, e_machine),
             18usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_machine)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_version as *const _ as usize },
+            ::std::mem::offset_of!(elf32_hdr, e_version),
             20usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_version)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_entry as *const _ as usize },
+            ::std::mem::offset_of!(elf32_hdr, e_entry),
             24usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_entry)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_phoff as *const _ as usize },
-            32usize,
+            ::std::mem::offset_of!(elf32_hdr, e_phoff),
+            28usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_phoff)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_shoff as *const _ as usize },
-            40usize,
+            ::std::mem::offset_of!(elf32_hdr, e_shoff),
+            32usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_shoff)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_flags as *const _ as usize },
-            48usize,
+            ::std::mem::offset_of!(elf32_hdr, e_flags),
+            36usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_flags)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_ehsize as *const _ as usize },
-            52usize,
+            ::std::mem::offset_of!(elf32_hdr, e_ehsize),
+            40usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_ehsize)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_phentsize as *const _ as usize },
-            54usize,
+            ::std::mem::offset_of!(elf32_hdr, e_phentsize),
+            42usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_phentsize)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_phnum as *const _ as usize },
-            56usize,
+            ::std::mem::offset_of!(elf32_hdr, e_phnum),
+            44usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_phnum)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_shentsize as *const _ as usize },
-            58usize,
+            ::std::mem::offset_of!(elf32_hdr, e_shentsize),
+            46usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_shentsize)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_shnum as *const _ as usize },
-            60usize,
+            ::std::mem::offset_of!(elf32_hdr, e_shnum),
+            48usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_shnum)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_hdr>())).e_shstrndx as *const _ as usize },
-            62usize,
+            ::std::mem::offset_of!(elf32_hdr, e_shstrndx),
+            50usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_hdr),
+                stringify!(elf32_hdr),
                 "::",
                 stringify!(e_shstrndx)
             )
@@ -256,93 +424,93 @@ mod tests {
     }
 
     #[test]
-    fn bindgen_test_layout_elf64_phdr() {
+    fn bindgen_test_layout_elf32_phdr() {
         assert_eq!(
-            ::std::mem::size_of::<elf64_phdr>(),
-            56usize,
-            concat!("Size of: ", stringify!(elf64_phdr))
+            ::std::mem::size_of::<elf32_phdr>(),
+            32usize,
+            concat!("Size of: ", stringify!(elf32_phdr))
         );
         assert_eq!(
-            ::std::mem::align_of::<elf64_phdr>(),
-            8usize,
-            concat!("Alignment of ", stringify!(elf64_phdr))
+            ::std::mem::align_of::<elf32_phdr>(),
+            4usize,
+            concat!("Alignment of ", stringify!(elf32_phdr))
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_phdr>())).p_type as *const _ as usize },
+            ::std::mem::offset_of!(elf32_phdr, p_type),
             0usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_phdr),
+                stringify!(elf32_phdr),
                 "::",
                 stringify!(p_type)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_phdr>())).p_flags as *const _ as usize },
+            ::std::mem::offset_of!(elf32_phdr, p_offset),
             4usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_phdr),
+                stringify!(elf32_phdr),
                 "::",
-                stringify!(p_flags)
+                stringify!(p_offset)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_phdr>())).p_offset as *const _ as usize },
+            ::std::mem::offset_of!(elf32_phdr, p_vaddr),
             8usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_phdr),
+                stringify!(elf32_phdr),
                 "::",
-                stringify!(p_offset)
+                stringify!(p_vaddr)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_phdr>())).p_vaddr as *const _ as usize },
-            16usize,
+            ::std::mem::offset_of!(elf32_phdr, p_paddr),
+            12usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_phdr),
+                stringify!(elf32_phdr),
                 "::",
-                stringify!(p_vaddr)
+                stringify!(p_paddr)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_phdr>())).p_paddr as *const _ as usize },
-            24usize,
+            ::std::mem::offset_of!(elf32_phdr, p_filesz),
+            16usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_phdr),
+                stringify!(elf32_phdr),
                 "::",
-                stringify!(p_paddr)
+                stringify!(p_filesz)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_phdr>())).p_filesz as *const _ as usize },
-            32usize,
+            ::std::mem::offset_of!(elf32_phdr, p_memsz),
+            20usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_phdr),
+                stringify!(elf32_phdr),
                 "::",
-                stringify!(p_filesz)
+                stringify!(p_memsz)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_phdr>())).p_memsz as *const _ as usize },
-            40usize,
+            ::std::mem::offset_of!(elf32_phdr, p_flags),
+            24usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_phdr),
+                stringify!(elf32_phdr),
                 "::",
-                stringify!(p_memsz)
+                stringify!(p_flags)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_phdr>())).p_align as *const _ as usize },
-            48usize,
+            ::std::mem::offset_of!(elf32_phdr, p_align),
+            28usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_phdr),
+                stringify!(elf32_phdr),
                 "::",
                 stringify!(p_align)
             )
@@ -350,45 +518,815 @@ mod tests {
     }
 
     #[test]
-    fn bindgen_test_layout_elf64_note() {
+    fn bindgen_test_layout_elf32_shdr() {
         assert_eq!(
-            ::std::mem::size_of::<elf64_note>(),
-            12usize,
-            concat!("Size of: ", stringify!(elf64_note))
+            ::std::mem::size_of::<elf32_shdr>(),
+            40usize,
+            concat!("Size of: ", stringify!(elf32_shdr))
         );
         assert_eq!(
-            ::std::mem::align_of::<elf64_note>(),
+            ::std::mem::align_of::<elf32_shdr>(),
             4usize,
-            concat!("Alignment of ", stringify!(elf64_note))
+            concat!("Alignment of ", stringify!(elf32_shdr))
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_note>())).n_namesz as *const _ as usize },
+            ::std::mem::offset_of!(elf32_shdr, sh_name),
             0usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_note),
+                stringify!(elf32_shdr),
                 "::",
-                stringify!(n_namesz)
+                stringify!(sh_name)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_note>())).n_descsz as *const _ as usize },
+            ::std::mem::offset_of!(elf32_shdr, sh_type),
             4usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_note),
+                stringify!(elf32_shdr),
                 "::",
-                stringify!(n_descsz)
+                stringify!(sh_type)
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<elf64_note>())).n_type as *const _ as usize },
+            ::std::mem::offset_of!(elf32_shdr, sh_flags),
             8usize,
             concat!(
                 "Offset of field: ",
-                stringify!(elf64_note),
+                stringify!(elf32_shdr),
                 "::",
-                stringify!(n_type)
+                stringify!(sh_flags)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_shdr, sh_addr),
+            12usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_shdr),
+                "::",
+                stringify!(sh_addr)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_shdr, sh_offset),
+            16usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_shdr),
+                "::",
+                stringify!(sh_offset)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_shdr, sh_size),
+            20usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_shdr),
+                "::",
+                stringify!(sh_size)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_shdr, sh_link),
+            24usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_shdr),
+                "::",
+                stringify!(sh_link)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_shdr, sh_info),
+            28usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_shdr),
+                "::",
+                stringify!(sh_info)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_shdr, sh_addralign),
+            32usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_shdr),
+                "::",
+                stringify!(sh_addralign)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_shdr, sh_entsize),
+            36usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_shdr),
+                "::",
+                stringify!(sh_entsize)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf64_hdr() {
+        assert_eq!(
+            ::std::mem::size_of::<elf64_hdr>(),
+            64usize,
+            concat!("Size of: ", stringify!(elf64_hdr))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf64_hdr>(),
+            8usize,
+            concat!("Alignment of ", stringify!(elf64_hdr))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_ident),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_ident)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_type),
+            16usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_type)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_machine),
+            18usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_machine)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_version),
+            20usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_version)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_entry),
+            24usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_entry)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_phoff),
+            32usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_phoff)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_shoff),
+            40usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_shoff)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_flags),
+            48usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_flags)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_ehsize),
+            52usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_ehsize)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_phentsize),
+            54usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_phentsize)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_phnum),
+            56usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_phnum)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_shentsize),
+            58usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_shentsize)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_shnum),
+            60usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_shnum)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_hdr, e_shstrndx),
+            62usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_hdr),
+                "::",
+                stringify!(e_shstrndx)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf64_phdr() {
+        assert_eq!(
+            ::std::mem::size_of::<elf64_phdr>(),
+            56usize,
+            concat!("Size of: ", stringify!(elf64_phdr))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf64_phdr>(),
+            8usize,
+            concat!("Alignment of ", stringify!(elf64_phdr))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_phdr, p_type),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_phdr),
+                "::",
+                stringify!(p_type)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_phdr, p_flags),
+            4usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_phdr),
+                "::",
+                stringify!(p_flags)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_phdr, p_offset),
+            8usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_phdr),
+                "::",
+                stringify!(p_offset)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_phdr, p_vaddr),
+            16usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_phdr),
+                "::",
+                stringify!(p_vaddr)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_phdr, p_paddr),
+            24usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_phdr),
+                "::",
+                stringify!(p_paddr)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_phdr, p_filesz),
+            32usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_phdr),
+                "::",
+                stringify!(p_filesz)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_phdr, p_memsz),
+            40usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_phdr),
+                "::",
+                stringify!(p_memsz)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_phdr, p_align),
+            48usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_phdr),
+                "::",
+                stringify!(p_align)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf64_shdr() {
+        assert_eq!(
+            ::std::mem::size_of::<elf64_shdr>(),
+            64usize,
+            concat!("Size of: ", stringify!(elf64_shdr))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf64_shdr>(),
+            8usize,
+            concat!("Alignment of ", stringify!(elf64_shdr))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_name),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_name)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_type),
+            4usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_type)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_flags),
+            8usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_flags)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_addr),
+            16usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_addr)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_offset),
+            24usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_offset)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_size),
+            32usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_size)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_link),
+            40usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_link)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_info),
+            44usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_info)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_addralign),
+            48usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_addralign)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_shdr, sh_entsize),
+            56usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_shdr),
+                "::",
+                stringify!(sh_entsize)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf64_note() {
+        assert_eq!(
+            ::std::mem::size_of::<elf64_note>(),
+            12usize,
+            concat!("Size of: ", stringify!(elf64_note))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf64_note>(),
+            4usize,
+            concat!("Alignment of ", stringify!(elf64_note))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_note, n_namesz),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_note),
+                "::",
+                stringify!(n_namesz)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_note, n_descsz),
+            4usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_note),
+                "::",
+                stringify!(n_descsz)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_note, n_type),
+            8usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_note),
+                "::",
+                stringify!(n_type)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf32_dyn() {
+        assert_eq!(
+            ::std::mem::size_of::<elf32_dyn>(),
+            8usize,
+            concat!("Size of: ", stringify!(elf32_dyn))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf32_dyn>(),
+            4usize,
+            concat!("Alignment of ", stringify!(elf32_dyn))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_dyn, d_tag),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_dyn),
+                "::",
+                stringify!(d_tag)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_dyn, d_val),
+            4usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_dyn),
+                "::",
+                stringify!(d_val)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf64_dyn() {
+        assert_eq!(
+            ::std::mem::size_of::<elf64_dyn>(),
+            16usize,
+            concat!("Size of: ", stringify!(elf64_dyn))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf64_dyn>(),
+            8usize,
+            concat!("Alignment of ", stringify!(elf64_dyn))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_dyn, d_tag),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_dyn),
+                "::",
+                stringify!(d_tag)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_dyn, d_val),
+            8usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_dyn),
+                "::",
+                stringify!(d_val)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf32_rel() {
+        assert_eq!(
+            ::std::mem::size_of::<elf32_rel>(),
+            8usize,
+            concat!("Size of: ", stringify!(elf32_rel))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf32_rel>(),
+            4usize,
+            concat!("Alignment of ", stringify!(elf32_rel))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_rel, r_offset),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_rel),
+                "::",
+                stringify!(r_offset)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_rel, r_info),
+            4usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_rel),
+                "::",
+                stringify!(r_info)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf64_rela() {
+        assert_eq!(
+            ::std::mem::size_of::<elf64_rela>(),
+            24usize,
+            concat!("Size of: ", stringify!(elf64_rela))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf64_rela>(),
+            8usize,
+            concat!("Alignment of ", stringify!(elf64_rela))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_rela, r_offset),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_rela),
+                "::",
+                stringify!(r_offset)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_rela, r_info),
+            8usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_rela),
+                "::",
+                stringify!(r_info)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_rela, r_addend),
+            16usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_rela),
+                "::",
+                stringify!(r_addend)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf32_sym() {
+        assert_eq!(
+            ::std::mem::size_of::<elf32_sym>(),
+            16usize,
+            concat!("Size of: ", stringify!(elf32_sym))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf32_sym>(),
+            4usize,
+            concat!("Alignment of ", stringify!(elf32_sym))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_sym, st_name),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_sym),
+                "::",
+                stringify!(st_name)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_sym, st_value),
+            4usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_sym),
+                "::",
+                stringify!(st_value)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_sym, st_size),
+            8usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_sym),
+                "::",
+                stringify!(st_size)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_sym, st_info),
+            12usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_sym),
+                "::",
+                stringify!(st_info)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_sym, st_other),
+            13usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_sym),
+                "::",
+                stringify!(st_other)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf32_sym, st_shndx),
+            14usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf32_sym),
+                "::",
+                stringify!(st_shndx)
+            )
+        );
+    }
+
+    #[test]
+    fn bindgen_test_layout_elf64_sym() {
+        assert_eq!(
+            ::std::mem::size_of::<elf64_sym>(),
+            24usize,
+            concat!("Size of: ", stringify!(elf64_sym))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<elf64_sym>(),
+            8usize,
+            concat!("Alignment of ", stringify!(elf64_sym))
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_sym, st_name),
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_sym),
+                "::",
+                stringify!(st_name)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_sym, st_info),
+            4usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_sym),
+                "::",
+                stringify!(st_info)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_sym, st_other),
+            5usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_sym),
+                "::",
+                stringify!(st_other)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_sym, st_shndx),
+            6usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_sym),
+                "::",
+                stringify!(st_shndx)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_sym, st_value),
+            8usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_sym),
+                "::",
+                stringify!(st_value)
+            )
+        );
+        assert_eq!(
+            ::std::mem::offset_of!(elf64_sym, st_size),
+            16usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(elf64_sym),
+                "::",
+                stringify!(st_size)
             )
         );
     }
@@ -10,7 +10,7 @@
 //! Bindgen autogenerated structs for `x86_64` boot parameters.
 
 #![cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-#![cfg_attr(feature = "cargo-clippy", allow(clippy::all))]
+#![allow(clippy::all)]
 #![allow(dead_code)]
 #![allow(missing_docs)]
 #![allow(non_camel_case_types)]
@@ -21,6 +21,8 @@
 #[doc(hidden)]
 pub mod bootparam;
 
+/// ELF structs and constants used by the ELF loader, e.g. to inspect the
+/// headers returned by `Elf::parse_header` and `Elf::parse_segments`.
 #[cfg(feature = "elf")]
 pub mod elf;
 
@@ -171,7 +171,7 @@ mod tests {
             concat!("Alignment of ", stringify!(hvm_start_info))
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_start_info>())).magic as *const _ as usize },
+            ::std::mem::offset_of!(hvm_start_info, magic),
             0usize,
             concat!(
                 "Offset of field: ",
@@ -181,7 +181,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_start_info>())).version as *const _ as usize },
+            ::std::mem::offset_of!(hvm_start_info, version),
             4usize,
             concat!(
                 "Offset of field: ",
@@ -191,7 +191,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_start_info>())).flags as *const _ as usize },
+            ::std::mem::offset_of!(hvm_start_info, flags),
             8usize,
             concat!(
                 "Offset of field: ",
@@ -201,7 +201,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_start_info>())).nr_modules as *const _ as usize },
+            ::std::mem::offset_of!(hvm_start_info, nr_modules),
             12usize,
             concat!(
                 "Offset of field: ",
@@ -211,9 +211,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe {
-                &(*(::std::ptr::null::<hvm_start_info>())).modlist_paddr as *const _ as usize
-            },
+            ::std::mem::offset_of!(hvm_start_info, modlist_paddr),
             16usize,
             concat!(
                 "Offset of field: ",
@@ -223,9 +221,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe {
-                &(*(::std::ptr::null::<hvm_start_info>())).cmdline_paddr as *const _ as usize
-            },
+            ::std::mem::offset_of!(hvm_start_info, cmdline_paddr),
             24usize,
             concat!(
                 "Offset of field: ",
@@ -235,7 +231,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_start_info>())).rsdp_paddr as *const _ as usize },
+            ::std::mem::offset_of!(hvm_start_info, rsdp_paddr),
             32usize,
             concat!(
                 "Offset of field: ",
@@ -245,7 +241,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_start_info>())).memmap_paddr as *const _ as usize },
+            ::std::mem::offset_of!(hvm_start_info, memmap_paddr),
             40usize,
             concat!(
                 "Offset of field: ",
@@ -255,9 +251,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe {
-                &(*(::std::ptr::null::<hvm_start_info>())).memmap_entries as *const _ as usize
-            },
+            ::std::mem::offset_of!(hvm_start_info, memmap_entries),
             48usize,
             concat!(
                 "Offset of field: ",
@@ -267,7 +261,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_start_info>())).reserved as *const _ as usize },
+            ::std::mem::offset_of!(hvm_start_info, reserved),
             52usize,
             concat!(
                 "Offset of field: ",
@@ -291,7 +285,7 @@ mod tests {
             concat!("Alignment of ", stringify!(hvm_modlist_entry))
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_modlist_entry>())).paddr as *const _ as usize },
+            ::std::mem::offset_of!(hvm_modlist_entry, paddr),
             0usize,
             concat!(
                 "Offset of field: ",
@@ -301,7 +295,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_modlist_entry>())).size as *const _ as usize },
+            ::std::mem::offset_of!(hvm_modlist_entry, size),
             8usize,
             concat!(
                 "Offset of field: ",
@@ -311,9 +305,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe {
-                &(*(::std::ptr::null::<hvm_modlist_entry>())).cmdline_paddr as *const _ as usize
-            },
+            ::std::mem::offset_of!(hvm_modlist_entry, cmdline_paddr),
             16usize,
             concat!(
                 "Offset of field: ",
@@ -323,7 +315,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_modlist_entry>())).reserved as *const _ as usize },
+            ::std::mem::offset_of!(hvm_modlist_entry, reserved),
             24usize,
             concat!(
                 "Offset of field: ",
@@ -347,7 +339,7 @@ mod tests {
             concat!("Alignment of ", stringify!(hvm_memmap_table_entry))
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_memmap_table_entry>())).addr as *const _ as usize },
+            ::std::mem::offset_of!(hvm_memmap_table_entry, addr),
             0usize,
             concat!(
                 "Offset of field: ",
@@ -357,7 +349,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe { &(*(::std::ptr::null::<hvm_memmap_table_entry>())).size as *const _ as usize },
+            ::std::mem::offset_of!(hvm_memmap_table_entry, size),
             8usize,
             concat!(
                 "Offset of field: ",
@@ -367,9 +359,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe {
-                &(*(::std::ptr::null::<hvm_memmap_table_entry>())).type_ as *const _ as usize
-            },
+            ::std::mem::offset_of!(hvm_memmap_table_entry, type_),
             16usize,
             concat!(
                 "Offset of field: ",
@@ -379,9 +369,7 @@ mod tests {
             )
         );
         assert_eq!(
-            unsafe {
-                &(*(::std::ptr::null::<hvm_memmap_table_entry>())).reserved as *const _ as usize
-            },
+            ::std::mem::offset_of!(hvm_memmap_table_entry, reserved),
             20usize,
             concat!(
                 "Offset of field: ",